@@ -0,0 +1,4 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS books_search_vector_idx;
+
+ALTER TABLE books DROP COLUMN IF EXISTS search_vector;
@@ -0,0 +1,10 @@
+-- Add up migration script here
+ALTER TABLE books
+  ADD COLUMN search_vector tsvector GENERATED ALWAYS AS (
+    setweight(to_tsvector('simple', title), 'A') ||
+    setweight(to_tsvector('simple', author), 'B') ||
+    setweight(to_tsvector('simple', isbn), 'B') ||
+    setweight(to_tsvector('simple', description), 'C')
+  ) STORED;
+
+CREATE INDEX IF NOT EXISTS books_search_vector_idx ON books USING GIN (search_vector);
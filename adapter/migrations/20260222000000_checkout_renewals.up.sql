@@ -0,0 +1,13 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS checkout_renewals (
+  renewal_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  checkout_id UUID NOT NULL,
+  user_id UUID NOT NULL,
+  renewed_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+  due_at TIMESTAMP(3) WITH TIME ZONE NOT NULL,
+  FOREIGN KEY (user_id) REFERENCES users(user_id)
+    ON UPDATE CASCADE
+    ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS checkout_renewals_checkout_id_idx ON checkout_renewals (checkout_id);
@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP TRIGGER IF EXISTS loan_policies_updated_at_trigger ON loan_policies;
+DROP TABLE IF EXISTS loan_policies;
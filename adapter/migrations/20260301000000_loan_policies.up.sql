@@ -0,0 +1,16 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS loan_policies (
+  role_id UUID PRIMARY KEY,
+  max_loans BIGINT NOT NULL CHECK (max_loans >= 0),
+  loan_period_days BIGINT NOT NULL CHECK (loan_period_days > 0),
+  max_renewals BIGINT NOT NULL CHECK (max_renewals >= 0),
+  created_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+  updated_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+  FOREIGN KEY (role_id) REFERENCES roles(role_id)
+    ON UPDATE CASCADE
+    ON DELETE CASCADE
+);
+
+CREATE TRIGGER loan_policies_updated_at_trigger
+  BEFORE UPDATE ON loan_policies FOR EACH ROW
+  EXECUTE PROCEDURE set_updated_at();
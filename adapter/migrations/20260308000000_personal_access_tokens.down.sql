@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP TRIGGER IF EXISTS personal_access_tokens_updated_at_trigger ON personal_access_tokens;
+DROP TABLE IF EXISTS personal_access_tokens;
@@ -0,0 +1,6 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS password_reset_tokens;
+DROP TABLE IF EXISTS refresh_tokens;
+DROP TABLE IF EXISTS refresh_token_families;
+DROP TABLE IF EXISTS revoked_access_tokens;
+DROP TABLE IF EXISTS auth_sessions;
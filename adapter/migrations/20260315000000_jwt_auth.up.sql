@@ -0,0 +1,56 @@
+-- Add up migration script here
+-- AUTH_BACKEND=jwt の場合に、Redis の代わりに認証関連の状態を保存するテーブル
+CREATE TABLE IF NOT EXISTS auth_sessions (
+  session_id VARCHAR(64) PRIMARY KEY,
+  user_id UUID NOT NULL,
+  family_id VARCHAR(64) NOT NULL,
+  device TEXT,
+  ip_address TEXT,
+  created_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+  expires_at TIMESTAMP(3) WITH TIME ZONE NOT NULL,
+  FOREIGN KEY (user_id) REFERENCES users(user_id)
+    ON UPDATE CASCADE
+    ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS auth_sessions_user_id_idx ON auth_sessions(user_id);
+
+-- 有効期限前に失効させたアクセストークン。有効期限を過ぎた行は不要になる
+CREATE TABLE IF NOT EXISTS revoked_access_tokens (
+  session_id VARCHAR(64) PRIMARY KEY,
+  user_id UUID NOT NULL,
+  expires_at TIMESTAMP(3) WITH TIME ZONE NOT NULL,
+  FOREIGN KEY (user_id) REFERENCES users(user_id)
+    ON UPDATE CASCADE
+    ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS refresh_token_families (
+  family_id VARCHAR(64) PRIMARY KEY,
+  user_id UUID NOT NULL,
+  expires_at TIMESTAMP(3) WITH TIME ZONE NOT NULL,
+  FOREIGN KEY (user_id) REFERENCES users(user_id)
+    ON UPDATE CASCADE
+    ON DELETE CASCADE
+);
+
+-- 使用済みのトークンを検出するため、系列を失効させても行は残す
+CREATE TABLE IF NOT EXISTS refresh_tokens (
+  token_hash VARCHAR(64) PRIMARY KEY,
+  user_id UUID NOT NULL,
+  family_id VARCHAR(64) NOT NULL,
+  rotated BOOLEAN NOT NULL DEFAULT FALSE,
+  expires_at TIMESTAMP(3) WITH TIME ZONE NOT NULL,
+  FOREIGN KEY (user_id) REFERENCES users(user_id)
+    ON UPDATE CASCADE
+    ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS password_reset_tokens (
+  token_hash VARCHAR(64) PRIMARY KEY,
+  user_id UUID NOT NULL,
+  expires_at TIMESTAMP(3) WITH TIME ZONE NOT NULL,
+  FOREIGN KEY (user_id) REFERENCES users(user_id)
+    ON UPDATE CASCADE
+    ON DELETE CASCADE
+);
@@ -0,0 +1,17 @@
+-- Add up migration script here
+-- AUTH_BACKEND=jwt の場合に、ログインの失敗回数とロックを保存するテーブル
+CREATE TABLE IF NOT EXISTS login_failures (
+  attempt_key VARCHAR(512) PRIMARY KEY,
+  failure_count INTEGER NOT NULL,
+  locked_until TIMESTAMP(3) WITH TIME ZONE,
+  expires_at TIMESTAMP(3) WITH TIME ZONE NOT NULL
+);
+
+-- アカウントごとにログインに失敗した接続元を保存するテーブル。
+-- 管理者がアカウントのロックを解除する際に、これらの接続元のロックも併せて解除する
+CREATE TABLE IF NOT EXISTS login_failure_ips (
+  email VARCHAR(512) NOT NULL,
+  ip_address VARCHAR(512) NOT NULL,
+  expires_at TIMESTAMP(3) WITH TIME ZONE NOT NULL,
+  PRIMARY KEY (email, ip_address)
+);
@@ -0,0 +1,5 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS mfa_challenges;
+DROP TABLE IF EXISTS mfa_recovery_codes;
+DROP TRIGGER IF EXISTS user_mfa_updated_at_trigger ON user_mfa;
+DROP TABLE IF EXISTS user_mfa;
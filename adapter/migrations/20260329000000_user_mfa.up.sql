@@ -0,0 +1,38 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS user_mfa (
+  user_id UUID PRIMARY KEY,
+  secret VARCHAR(64) NOT NULL,
+  -- 確認コードの入力で登録が完了するまでは NULL
+  enabled_at TIMESTAMP(3) WITH TIME ZONE,
+  -- 最後に受け付けた確認コードのタイムステップ。同じコードの再利用を防ぐ
+  last_used_step BIGINT,
+  created_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+  updated_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+  FOREIGN KEY (user_id) REFERENCES users(user_id)
+    ON UPDATE CASCADE
+    ON DELETE CASCADE
+);
+
+CREATE TRIGGER user_mfa_updated_at_trigger
+  BEFORE UPDATE ON user_mfa FOR EACH ROW
+  EXECUTE PROCEDURE set_updated_at();
+
+CREATE TABLE IF NOT EXISTS mfa_recovery_codes (
+  user_id UUID NOT NULL,
+  code_hash VARCHAR(64) NOT NULL,
+  used_at TIMESTAMP(3) WITH TIME ZONE,
+  PRIMARY KEY (user_id, code_hash),
+  FOREIGN KEY (user_id) REFERENCES user_mfa(user_id)
+    ON UPDATE CASCADE
+    ON DELETE CASCADE
+);
+
+-- AUTH_BACKEND=jwt の場合に、確認コードの入力待ちを保存するテーブル
+CREATE TABLE IF NOT EXISTS mfa_challenges (
+  token_hash VARCHAR(64) PRIMARY KEY,
+  user_id UUID NOT NULL,
+  expires_at TIMESTAMP(3) WITH TIME ZONE NOT NULL,
+  FOREIGN KEY (user_id) REFERENCES users(user_id)
+    ON UPDATE CASCADE
+    ON DELETE CASCADE
+);
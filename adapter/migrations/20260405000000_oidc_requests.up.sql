@@ -0,0 +1,8 @@
+-- Add up migration script here
+-- AUTH_BACKEND=jwt の場合に、シングルサインオンのコールバック待ちを保存するテーブル
+CREATE TABLE IF NOT EXISTS oidc_requests (
+  state_hash VARCHAR(64) PRIMARY KEY,
+  nonce VARCHAR(255) NOT NULL,
+  code_verifier VARCHAR(255) NOT NULL,
+  expires_at TIMESTAMP(3) WITH TIME ZONE NOT NULL
+);
@@ -0,0 +1,11 @@
+-- Add up migration script here
+-- AUTH_BACKEND=jwt の場合に、メールアドレス変更の確認待ちを保存するテーブル
+CREATE TABLE IF NOT EXISTS email_verification_tokens (
+  token_hash VARCHAR(64) PRIMARY KEY,
+  user_id UUID NOT NULL,
+  email VARCHAR(255) NOT NULL,
+  expires_at TIMESTAMP(3) WITH TIME ZONE NOT NULL,
+  FOREIGN KEY (user_id) REFERENCES users(user_id)
+    ON UPDATE CASCADE
+    ON DELETE CASCADE
+);
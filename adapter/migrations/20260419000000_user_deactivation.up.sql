@@ -0,0 +1,3 @@
+-- Add up migration script here
+-- 無効化されたユーザーは NULL 以外になる。貸出や蔵書の履歴を残すため、行は削除しない
+ALTER TABLE users ADD COLUMN IF NOT EXISTS deactivated_at TIMESTAMP(3) WITH TIME ZONE;
@@ -0,0 +1,5 @@
+-- Add down migration script here
+-- ロールの削除で利用者ごと消えないよう、先に一般ユーザーに戻す
+UPDATE users SET role_id = (SELECT role_id FROM roles WHERE name = 'User')
+WHERE role_id = (SELECT role_id FROM roles WHERE name = 'Librarian');
+DELETE FROM roles WHERE name = 'Librarian';
@@ -0,0 +1,2 @@
+-- Add up migration script here
+INSERT INTO roles(name) VALUES ('Librarian') ON CONFLICT DO NOTHING;
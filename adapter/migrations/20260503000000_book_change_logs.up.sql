@@ -0,0 +1,15 @@
+-- Add up migration script here
+-- 蔵書の変更・削除を誰が行ったかの記録。削除後も残すため、books への外部キーは設定しない
+CREATE TABLE IF NOT EXISTS book_change_logs (
+  book_change_log_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  book_id UUID NOT NULL,
+  -- カーネルの BookChangeAction で定義した値に限る
+  action VARCHAR(16) NOT NULL CHECK (action IN ('update', 'delete', 'transfer')),
+  performed_by UUID,
+  performed_at TIMESTAMP(3) WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+  FOREIGN KEY (performed_by) REFERENCES users(user_id)
+    ON UPDATE CASCADE
+    ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS book_change_logs_book_id_idx ON book_change_logs(book_id);
@@ -42,6 +42,21 @@ pub fn connect_database_with(cfg: &DatabaseConfig) -> ConnectionPool {
     PgPool::connect_lazy_with(make_pg_connect_options(cfg)).into()
 }
 
+// 検索語を LIKE の部分一致のパターンにする。ワイルドカードは文字どおりに扱う。
+// クエリ側では ESCAPE '\' を指定する
+pub fn like_pattern(value: &str) -> String {
+    let mut pattern = String::with_capacity(value.len() + 2);
+    pattern.push('%');
+    for c in value.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+    pattern.push('%');
+    pattern
+}
+
 pub enum ConnectionGuard<'g, 't: 'g> {
     Pool(Box<PoolConnection<Postgres>>),
     Transaction(MutexGuard<'g, Transaction<'t, Postgres>>),
@@ -1,18 +1,157 @@
 use crate::redis::model::{RedisKey, RedisValue};
 use kernel::model::{
-    auth::{AccessToken, event::CreateToken},
+    auth::{
+        AccessToken, EmailVerificationToken, LoginAttemptKey, MfaChallengeToken,
+        PasswordResetToken, PendingEmailChange, RefreshToken, RefreshTokenEntry,
+        event::{
+            CreateEmailVerificationToken, CreateMfaChallenge, CreatePasswordResetToken,
+            CreateRefreshToken, CreateToken,
+        },
+    },
     id::UserId,
+    oidc::OidcAuthorizationRequest,
+    session::{Session, SessionClient},
 };
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use shared::error::AppError;
+use sqlx::types::chrono::{DateTime, Utc};
 use std::str::FromStr;
 
 pub struct AuthorizationKey(String);
 pub struct AuthorizedUserId(UserId);
+// アクセストークンと区別するため、キーにはプレフィックスを付ける
+pub struct PasswordResetKey(String);
+pub struct EmailVerificationKey(String);
+pub struct MfaChallengeKey(String);
+pub struct OidcRequestKey(String);
 
-pub fn from(event: CreateToken) -> (AuthorizationKey, AuthorizedUserId) {
+// セッションの ID にはアクセストークンのハッシュを使い、一覧にトークン自体を出さない
+pub struct SessionKey(String);
+pub struct UserSessionsKey(UserId);
+#[derive(PartialEq, Eq)]
+pub struct SessionId(String);
+
+// リフレッシュトークンもハッシュをキーにし、Redis 上にトークン自体を残さない
+pub struct RefreshTokenKey(String);
+// 系列のキーが残っている間だけ、その系列のリフレッシュトークンを有効とする
+pub struct RefreshFamilyKey(String);
+
+pub struct LoginFailuresKey(String);
+pub struct LoginFailureCount(pub u32);
+pub struct LoginLockoutKey(String);
+pub struct LockedUntil(pub DateTime<Utc>);
+// アカウントごとに、ログインに失敗した接続元を集合として保存する
+pub struct LoginFailureIpsKey(String);
+pub struct LoginFailureIp(pub String);
+
+#[derive(Serialize, Deserialize)]
+pub struct EmailVerificationValue {
+    pub user_id: UserId,
+    pub email: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct OidcRequestValue {
+    pub nonce: String,
+    pub code_verifier: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RefreshTokenValue {
+    pub user_id: UserId,
+    pub family_id: String,
+    pub rotated: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SessionValue {
+    pub access_token: String,
+    pub user_id: UserId,
+    pub device: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+    // リフレッシュトークン導入前に作られたセッションには無い
+    #[serde(default)]
+    pub family_id: Option<String>,
+}
+
+pub fn from(event: CreateToken) -> (AuthorizationKey, AuthorizedUserId, SessionKey, SessionValue) {
+    let now = Utc::now();
+    let session_key = SessionKey::from(&AccessToken(event.access_token.clone()));
+    let session = SessionValue {
+        access_token: event.access_token.clone(),
+        user_id: event.user_id,
+        device: event.client.device,
+        ip_address: event.client.ip_address,
+        created_at: now,
+        last_seen_at: now,
+        family_id: Some(event.family_id),
+    };
     (
         AuthorizationKey(event.access_token),
         AuthorizedUserId(event.user_id),
+        session_key,
+        session,
+    )
+}
+
+pub fn from_password_reset(
+    event: CreatePasswordResetToken,
+) -> (PasswordResetKey, AuthorizedUserId) {
+    (
+        PasswordResetKey(event.reset_token),
+        AuthorizedUserId(event.user_id),
+    )
+}
+
+pub fn from_email_verification(
+    event: CreateEmailVerificationToken,
+) -> (EmailVerificationKey, EmailVerificationValue) {
+    (
+        EmailVerificationKey(event.verification_token),
+        EmailVerificationValue {
+            user_id: event.user_id,
+            email: event.email.into_inner(),
+        },
+    )
+}
+
+pub fn from_mfa_challenge(event: CreateMfaChallenge) -> (MfaChallengeKey, AuthorizedUserId) {
+    (
+        MfaChallengeKey(event.challenge_token),
+        AuthorizedUserId(event.user_id),
+    )
+}
+
+pub fn from_oidc_request(request: &OidcAuthorizationRequest) -> (OidcRequestKey, OidcRequestValue) {
+    (
+        OidcRequestKey(request.state.clone()),
+        OidcRequestValue {
+            nonce: request.nonce.clone(),
+            code_verifier: request.code_verifier.clone(),
+        },
+    )
+}
+
+pub fn from_refresh_token(
+    event: CreateRefreshToken,
+) -> (
+    RefreshTokenKey,
+    RefreshTokenValue,
+    RefreshFamilyKey,
+    AuthorizedUserId,
+) {
+    (
+        RefreshTokenKey(event.refresh_token),
+        RefreshTokenValue {
+            user_id: event.user_id,
+            family_id: event.family_id.clone(),
+            rotated: false,
+        },
+        RefreshFamilyKey(event.family_id),
+        AuthorizedUserId(event.user_id),
     )
 }
 
@@ -42,6 +181,366 @@ impl RedisKey for AuthorizationKey {
     }
 }
 
+impl From<PasswordResetKey> for PasswordResetToken {
+    fn from(key: PasswordResetKey) -> Self {
+        Self(key.0)
+    }
+}
+
+impl From<&PasswordResetToken> for PasswordResetKey {
+    fn from(token: &PasswordResetToken) -> Self {
+        Self(token.0.to_string())
+    }
+}
+
+impl RedisKey for PasswordResetKey {
+    type Value = AuthorizedUserId;
+
+    fn inner(&self) -> String {
+        format!("password_reset:{}", self.0)
+    }
+}
+
+impl From<EmailVerificationKey> for EmailVerificationToken {
+    fn from(key: EmailVerificationKey) -> Self {
+        Self(key.0)
+    }
+}
+
+impl From<&EmailVerificationToken> for EmailVerificationKey {
+    fn from(token: &EmailVerificationToken) -> Self {
+        Self(token.0.to_string())
+    }
+}
+
+impl RedisKey for EmailVerificationKey {
+    type Value = EmailVerificationValue;
+
+    fn inner(&self) -> String {
+        format!("email_verification:{}", self.0)
+    }
+}
+
+impl RedisValue for EmailVerificationValue {
+    fn inner(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+impl TryFrom<String> for EmailVerificationValue {
+    type Error = AppError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        serde_json::from_str(&s).map_err(|e| AppError::ConversionEntityError(e.to_string()))
+    }
+}
+
+impl TryFrom<EmailVerificationValue> for PendingEmailChange {
+    type Error = AppError;
+
+    fn try_from(value: EmailVerificationValue) -> Result<Self, Self::Error> {
+        Ok(Self {
+            user_id: value.user_id,
+            email: value.email.parse()?,
+        })
+    }
+}
+
+impl From<MfaChallengeKey> for MfaChallengeToken {
+    fn from(key: MfaChallengeKey) -> Self {
+        Self(key.0)
+    }
+}
+
+impl From<&MfaChallengeToken> for MfaChallengeKey {
+    fn from(token: &MfaChallengeToken) -> Self {
+        Self(token.0.to_string())
+    }
+}
+
+impl RedisKey for MfaChallengeKey {
+    type Value = AuthorizedUserId;
+
+    fn inner(&self) -> String {
+        format!("mfa_challenge:{}", self.0)
+    }
+}
+
+impl OidcRequestKey {
+    pub fn new(state: &str) -> Self {
+        Self(state.to_string())
+    }
+
+    pub fn into_request(self, value: OidcRequestValue) -> OidcAuthorizationRequest {
+        OidcAuthorizationRequest {
+            state: self.0,
+            nonce: value.nonce,
+            code_verifier: value.code_verifier,
+        }
+    }
+}
+
+impl RedisKey for OidcRequestKey {
+    type Value = OidcRequestValue;
+
+    fn inner(&self) -> String {
+        format!("oidc_request:{}", self.0)
+    }
+}
+
+impl RedisValue for OidcRequestValue {
+    fn inner(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+impl TryFrom<String> for OidcRequestValue {
+    type Error = AppError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        serde_json::from_str(&s).map_err(|e| AppError::ConversionEntityError(e.to_string()))
+    }
+}
+
+impl SessionKey {
+    pub fn new(session_id: &str) -> Self {
+        Self(session_id.to_string())
+    }
+
+    pub fn session_id(&self) -> SessionId {
+        SessionId(self.0.clone())
+    }
+}
+
+impl From<&AccessToken> for SessionKey {
+    fn from(token: &AccessToken) -> Self {
+        Self(format!("{:x}", Sha256::digest(token.0.as_bytes())))
+    }
+}
+
+impl From<&SessionId> for SessionKey {
+    fn from(id: &SessionId) -> Self {
+        Self(id.0.clone())
+    }
+}
+
+impl RedisKey for SessionKey {
+    type Value = SessionValue;
+
+    fn inner(&self) -> String {
+        format!("session:{}", self.0)
+    }
+}
+
+impl RedisValue for SessionValue {
+    fn inner(&self) -> String {
+        // 文字列をキーとする構造体のため、シリアライズには失敗しない
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+impl TryFrom<String> for SessionValue {
+    type Error = AppError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        serde_json::from_str(&s).map_err(|e| AppError::ConversionEntityError(e.to_string()))
+    }
+}
+
+impl SessionValue {
+    pub fn into_session(self, key: &SessionKey, current: bool) -> Session {
+        Session::new(
+            key.0.clone(),
+            SessionClient::new(self.device, self.ip_address),
+            self.created_at,
+            self.last_seen_at,
+            current,
+        )
+    }
+}
+
+impl From<RefreshTokenKey> for RefreshToken {
+    fn from(key: RefreshTokenKey) -> Self {
+        Self(key.0)
+    }
+}
+
+impl From<&RefreshToken> for RefreshTokenKey {
+    fn from(token: &RefreshToken) -> Self {
+        Self(token.0.to_string())
+    }
+}
+
+impl RedisKey for RefreshTokenKey {
+    type Value = RefreshTokenValue;
+
+    fn inner(&self) -> String {
+        format!("refresh_token:{:x}", Sha256::digest(self.0.as_bytes()))
+    }
+}
+
+impl RedisValue for RefreshTokenValue {
+    fn inner(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+impl TryFrom<String> for RefreshTokenValue {
+    type Error = AppError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        serde_json::from_str(&s).map_err(|e| AppError::ConversionEntityError(e.to_string()))
+    }
+}
+
+impl From<RefreshTokenValue> for RefreshTokenEntry {
+    fn from(value: RefreshTokenValue) -> Self {
+        Self {
+            user_id: value.user_id,
+            family_id: value.family_id,
+            rotated: value.rotated,
+        }
+    }
+}
+
+impl RefreshFamilyKey {
+    pub fn new(family_id: &str) -> Self {
+        Self(family_id.to_string())
+    }
+}
+
+impl RedisKey for RefreshFamilyKey {
+    type Value = AuthorizedUserId;
+
+    fn inner(&self) -> String {
+        format!("refresh_family:{}", self.0)
+    }
+}
+
+// 失敗回数の記録に使うキー。Redis と DB のどちらに保存する場合も同じ形式にする
+pub fn login_attempt_key(key: &LoginAttemptKey) -> String {
+    match key {
+        LoginAttemptKey::Email(email) => format!("email:{email}"),
+        LoginAttemptKey::IpAddress(ip_address) => format!("ip:{ip_address}"),
+    }
+}
+
+impl From<&LoginAttemptKey> for LoginFailuresKey {
+    fn from(key: &LoginAttemptKey) -> Self {
+        Self(login_attempt_key(key))
+    }
+}
+
+impl RedisKey for LoginFailuresKey {
+    type Value = LoginFailureCount;
+
+    fn inner(&self) -> String {
+        format!("login_failures:{}", self.0)
+    }
+}
+
+impl RedisValue for LoginFailureCount {
+    fn inner(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+impl TryFrom<String> for LoginFailureCount {
+    type Error = AppError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+            .map(Self)
+            .map_err(|e: std::num::ParseIntError| AppError::ConversionEntityError(e.to_string()))
+    }
+}
+
+impl From<&LoginAttemptKey> for LoginLockoutKey {
+    fn from(key: &LoginAttemptKey) -> Self {
+        Self(login_attempt_key(key))
+    }
+}
+
+impl RedisKey for LoginLockoutKey {
+    type Value = LockedUntil;
+
+    fn inner(&self) -> String {
+        format!("login_lockout:{}", self.0)
+    }
+}
+
+impl RedisValue for LockedUntil {
+    fn inner(&self) -> String {
+        self.0.to_rfc3339()
+    }
+}
+
+impl TryFrom<String> for LockedUntil {
+    type Error = AppError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        DateTime::parse_from_rfc3339(&s)
+            .map(|dt| Self(dt.with_timezone(&Utc)))
+            .map_err(|e| AppError::ConversionEntityError(e.to_string()))
+    }
+}
+
+impl UserSessionsKey {
+    pub fn new(user_id: UserId) -> Self {
+        Self(user_id)
+    }
+}
+
+impl LoginFailureIpsKey {
+    pub fn new(email: &str) -> Self {
+        Self(email.to_string())
+    }
+}
+
+impl RedisKey for LoginFailureIpsKey {
+    type Value = LoginFailureIp;
+
+    fn inner(&self) -> String {
+        format!("login_failure_ips:{}", self.0)
+    }
+}
+
+impl RedisValue for LoginFailureIp {
+    fn inner(&self) -> String {
+        self.0.clone()
+    }
+}
+
+impl TryFrom<String> for LoginFailureIp {
+    type Error = AppError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Ok(Self(s))
+    }
+}
+
+impl RedisKey for UserSessionsKey {
+    type Value = SessionId;
+
+    fn inner(&self) -> String {
+        format!("user_sessions:{}", self.0)
+    }
+}
+
+impl RedisValue for SessionId {
+    fn inner(&self) -> String {
+        self.0.clone()
+    }
+}
+
+impl TryFrom<String> for SessionId {
+    type Error = AppError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Ok(Self(s))
+    }
+}
+
 impl RedisValue for AuthorizedUserId {
     fn inner(&self) -> String {
         self.0.to_string()
@@ -63,3 +562,40 @@ impl AuthorizedUserId {
         self.0
     }
 }
+
+// AUTH_BACKEND=jwt の場合に auth_sessions テーブルから読み込むセッション
+pub struct AuthSessionRow {
+    pub session_id: String,
+    pub device: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl AuthSessionRow {
+    // 署名済みトークンは利用のたびに記録しないため、最終利用日時には発行日時を使う
+    pub fn into_session(self, current: bool) -> Session {
+        Session::new(
+            self.session_id,
+            SessionClient::new(self.device, self.ip_address),
+            self.created_at,
+            self.created_at,
+            current,
+        )
+    }
+}
+
+pub struct RefreshTokenRow {
+    pub user_id: UserId,
+    pub family_id: String,
+    pub rotated: bool,
+}
+
+impl From<RefreshTokenRow> for RefreshTokenEntry {
+    fn from(value: RefreshTokenRow) -> Self {
+        Self {
+            user_id: value.user_id,
+            family_id: value.family_id,
+            rotated: value.rotated,
+        }
+    }
+}
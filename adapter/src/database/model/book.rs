@@ -1,10 +1,11 @@
 use chrono::{DateTime, Utc};
 use kernel::model::{
-    book::{Book, Checkout},
+    book::{Book, BookChangeAction, BookChangeLog, Checkout},
     id::{BookId, CheckoutId, UserId},
     user::{BookOwner, CheckoutUser},
 };
 use shared::error::{AppError, AppResult};
+use std::str::FromStr;
 
 pub struct BookRow {
     pub book_id: BookId,
@@ -50,6 +51,7 @@ pub struct BookCheckoutRow {
     pub user_id: UserId,
     pub user_name: String,
     pub checked_out_at: DateTime<Utc>,
+    pub due_at: DateTime<Utc>,
 }
 
 impl TryFrom<BookCheckoutRow> for Checkout {
@@ -62,11 +64,37 @@ impl TryFrom<BookCheckoutRow> for Checkout {
             user_id,
             user_name,
             checked_out_at,
+            due_at,
         } = value;
         Ok(Checkout::new(
             checkout_id,
             CheckoutUser::new(user_id, user_name.parse()?),
             checked_out_at,
+            due_at,
+        ))
+    }
+}
+
+pub struct BookChangeLogRow {
+    pub action: String,
+    pub performed_by: Option<UserId>,
+    pub performed_at: DateTime<Utc>,
+}
+
+impl TryFrom<BookChangeLogRow> for BookChangeLog {
+    type Error = AppError;
+
+    fn try_from(value: BookChangeLogRow) -> Result<Self, Self::Error> {
+        let BookChangeLogRow {
+            action,
+            performed_by,
+            performed_at,
+        } = value;
+        Ok(BookChangeLog::new(
+            BookChangeAction::from_str(action.as_str())
+                .map_err(|e| AppError::ConversionEntityError(e.to_string()))?,
+            performed_by,
+            performed_at,
         ))
     }
 }
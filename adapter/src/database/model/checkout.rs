@@ -9,6 +9,7 @@ pub struct CheckoutStateRow {
     pub book_id: BookId,
     pub checkout_id: Option<CheckoutId>,
     pub user_id: Option<UserId>,
+    pub renewal_count: i64,
 }
 
 impl From<CheckoutStateRow> for CheckoutState {
@@ -17,11 +18,13 @@ impl From<CheckoutStateRow> for CheckoutState {
             book_id,
             checkout_id,
             user_id,
+            renewal_count,
         } = value;
         CheckoutState {
             book_id,
             checkout_id,
             user_id,
+            renewal_count,
         }
     }
 }
@@ -31,6 +34,8 @@ pub struct CheckoutRow {
     pub book_id: BookId,
     pub user_id: UserId,
     pub checked_out_at: DateTime<Utc>,
+    pub due_at: DateTime<Utc>,
+    pub renewal_count: i64,
     pub title: String,
     pub author: String,
     pub isbn: String,
@@ -45,6 +50,8 @@ impl TryFrom<CheckoutRow> for Checkout {
             book_id,
             user_id,
             checked_out_at,
+            due_at,
+            renewal_count,
             title,
             author,
             isbn,
@@ -53,6 +60,8 @@ impl TryFrom<CheckoutRow> for Checkout {
             checkout_id,
             user_id,
             checked_out_at,
+            due_at,
+            renewal_count,
             None,
             CheckoutBook::new(book_id, title.parse()?, author.parse()?, isbn.parse()?),
         ))
@@ -64,6 +73,8 @@ pub struct ReturnedCheckoutRow {
     pub book_id: BookId,
     pub user_id: UserId,
     pub checked_out_at: DateTime<Utc>,
+    pub due_at: DateTime<Utc>,
+    pub renewal_count: i64,
     pub returned_at: DateTime<Utc>,
     pub title: String,
     pub author: String,
@@ -79,6 +90,8 @@ impl TryFrom<ReturnedCheckoutRow> for Checkout {
             book_id,
             user_id,
             checked_out_at,
+            due_at,
+            renewal_count,
             returned_at,
             title,
             author,
@@ -88,6 +101,8 @@ impl TryFrom<ReturnedCheckoutRow> for Checkout {
             checkout_id,
             user_id,
             checked_out_at,
+            due_at,
+            renewal_count,
             Some(returned_at),
             CheckoutBook::new(book_id, title.parse()?, author.parse()?, isbn.parse()?),
         ))
@@ -0,0 +1,48 @@
+use kernel::model::{
+    checkout::CheckoutBook,
+    hold::Hold,
+    id::{BookId, HoldId, UserId},
+};
+use shared::error::AppError;
+use sqlx::types::chrono::{DateTime, Utc};
+
+pub struct HoldRow {
+    pub hold_id: HoldId,
+    pub book_id: BookId,
+    pub user_id: UserId,
+    pub position: i64,
+    pub created_at: DateTime<Utc>,
+    pub ready_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub title: String,
+    pub author: String,
+    pub isbn: String,
+}
+
+impl TryFrom<HoldRow> for Hold {
+    type Error = AppError;
+
+    fn try_from(value: HoldRow) -> Result<Self, Self::Error> {
+        let HoldRow {
+            hold_id,
+            book_id,
+            user_id,
+            position,
+            created_at,
+            ready_at,
+            expires_at,
+            title,
+            author,
+            isbn,
+        } = value;
+        Ok(Hold::new(
+            hold_id,
+            user_id,
+            position,
+            created_at,
+            ready_at,
+            expires_at,
+            CheckoutBook::new(book_id, title.parse()?, author.parse()?, isbn.parse()?),
+        ))
+    }
+}
@@ -0,0 +1,30 @@
+use kernel::model::{loan_policy::LoanPolicy, role::Role};
+use shared::error::AppError;
+use std::str::FromStr;
+
+pub struct LoanPolicyRow {
+    pub role_name: String,
+    pub max_loans: i64,
+    pub loan_period_days: i64,
+    pub max_renewals: i64,
+}
+
+impl TryFrom<LoanPolicyRow> for LoanPolicy {
+    type Error = AppError;
+
+    fn try_from(value: LoanPolicyRow) -> Result<Self, Self::Error> {
+        let LoanPolicyRow {
+            role_name,
+            max_loans,
+            loan_period_days,
+            max_renewals,
+        } = value;
+        Ok(LoanPolicy::new(
+            Role::from_str(role_name.as_str())
+                .map_err(|e| AppError::ConversionEntityError(e.to_string()))?,
+            max_loans,
+            loan_period_days,
+            max_renewals,
+        ))
+    }
+}
@@ -0,0 +1,23 @@
+use kernel::model::{id::UserId, mfa::UserMfa};
+use sqlx::types::chrono::{DateTime, Utc};
+
+pub struct UserMfaRow {
+    pub user_id: UserId,
+    pub secret: String,
+    pub enabled_at: Option<DateTime<Utc>>,
+}
+
+impl From<UserMfaRow> for UserMfa {
+    fn from(value: UserMfaRow) -> Self {
+        let UserMfaRow {
+            user_id,
+            secret,
+            enabled_at,
+        } = value;
+        UserMfa {
+            user_id,
+            secret,
+            enabled: enabled_at.is_some(),
+        }
+    }
+}
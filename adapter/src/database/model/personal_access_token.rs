@@ -0,0 +1,48 @@
+use kernel::model::{
+    id::{PersonalAccessTokenId, UserId},
+    personal_access_token::{PersonalAccessToken, TokenScope},
+};
+use shared::error::AppError;
+use sqlx::types::chrono::{DateTime, Utc};
+use std::str::FromStr;
+
+pub struct PersonalAccessTokenRow {
+    pub token_id: PersonalAccessTokenId,
+    pub user_id: UserId,
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TryFrom<PersonalAccessTokenRow> for PersonalAccessToken {
+    type Error = AppError;
+
+    fn try_from(value: PersonalAccessTokenRow) -> Result<Self, Self::Error> {
+        let PersonalAccessTokenRow {
+            token_id,
+            user_id,
+            name,
+            scopes,
+            expires_at,
+            last_used_at,
+            created_at,
+        } = value;
+        let scopes = scopes
+            .iter()
+            .map(|s| {
+                TokenScope::from_str(s).map_err(|e| AppError::ConversionEntityError(e.to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(PersonalAccessToken::new(
+            token_id,
+            user_id,
+            name,
+            scopes,
+            expires_at,
+            last_used_at,
+            created_at,
+        ))
+    }
+}
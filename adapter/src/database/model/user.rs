@@ -3,12 +3,17 @@ use shared::error::AppError;
 use sqlx::types::chrono::{DateTime, Utc};
 use std::str::FromStr;
 
-pub struct UserItem {
+pub struct UserRow {
     pub user_id: UserId,
-    pub password_hash: String,
+    pub name: String,
+    pub email: String,
+    pub role_name: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
 }
 
-pub struct UserRow {
+pub struct PaginatedUserRow {
+    pub total: i64,
     pub user_id: UserId,
     pub name: String,
     pub email: String,
@@ -17,6 +22,28 @@ pub struct UserRow {
     pub updated_at: DateTime<Utc>,
 }
 
+impl From<PaginatedUserRow> for UserRow {
+    fn from(value: PaginatedUserRow) -> Self {
+        let PaginatedUserRow {
+            user_id,
+            name,
+            email,
+            role_name,
+            created_at,
+            updated_at,
+            ..
+        } = value;
+        Self {
+            user_id,
+            name,
+            email,
+            role_name,
+            created_at,
+            updated_at,
+        }
+    }
+}
+
 impl TryFrom<UserRow> for User {
     type Error = AppError;
 
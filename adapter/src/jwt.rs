@@ -0,0 +1,148 @@
+use chrono::{DateTime, Utc};
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use kernel::model::id::UserId;
+use serde::{Deserialize, Serialize};
+use shared::{
+    config::{JwtAlgorithm, JwtConfig},
+    error::{AppError, AppResult},
+};
+use std::{
+    collections::HashMap,
+    sync::{Mutex, RwLock},
+    time::{Duration, Instant},
+};
+
+// 他のインスタンスで失効したトークンを取り込むまでの最大の遅れ
+const REVOCATION_SYNC_INTERVAL: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Claims {
+    pub sub: UserId,
+    // セッション ID として、失効リストとセッション一覧の照合に使う
+    pub jti: String,
+    // リフレッシュトークンの系列
+    pub fam: String,
+    pub iat: i64,
+    pub exp: i64,
+}
+
+/// 起動時に設定から読み込んだ署名鍵と検証鍵。
+/// プロセス内で共有するため、失効リストの写しも一緒に持つ。
+pub struct JwtKeys {
+    algorithm: Algorithm,
+    encoding: EncodingKey,
+    decoding: DecodingKey,
+    pub revoked: RevokedTokens,
+}
+
+impl JwtKeys {
+    pub fn new(config: &JwtConfig) -> AppResult<Self> {
+        let to_error =
+            |e: jsonwebtoken::errors::Error| AppError::ConversionEntityError(e.to_string());
+        let (algorithm, encoding, decoding) = match config.algorithm {
+            JwtAlgorithm::HS256 => (
+                Algorithm::HS256,
+                EncodingKey::from_secret(config.signing_key.as_bytes()),
+                DecodingKey::from_secret(config.verifying_key.as_bytes()),
+            ),
+            JwtAlgorithm::EdDSA => (
+                Algorithm::EdDSA,
+                EncodingKey::from_ed_pem(config.signing_key.as_bytes()).map_err(to_error)?,
+                DecodingKey::from_ed_pem(config.verifying_key.as_bytes()).map_err(to_error)?,
+            ),
+        };
+        Ok(Self {
+            algorithm,
+            encoding,
+            decoding,
+            revoked: RevokedTokens::default(),
+        })
+    }
+
+    pub fn encode(&self, claims: &Claims) -> AppResult<String> {
+        jsonwebtoken::encode(&Header::new(self.algorithm), claims, &self.encoding)
+            .map_err(|e| AppError::ConversionEntityError(e.to_string()))
+    }
+
+    // 署名が正しく、有効期限内のトークンだけを受け付ける
+    pub fn decode(&self, token: &str) -> Option<Claims> {
+        let mut validation = Validation::new(self.algorithm);
+        validation.leeway = 0;
+        jsonwebtoken::decode::<Claims>(token, &self.decoding, &validation)
+            .ok()
+            .map(|data| data.claims)
+    }
+}
+
+/// 失効リストをメモリに写したもの。
+/// リクエストごとにデータベースを引かないよう、一定の間隔でだけ読み直す。
+#[derive(Default)]
+pub struct RevokedTokens {
+    inner: RwLock<RevokedTokensInner>,
+}
+
+#[derive(Default)]
+struct RevokedTokensInner {
+    // jti とトークンの有効期限
+    tokens: HashMap<String, DateTime<Utc>>,
+    synced_at: Option<Instant>,
+}
+
+impl RevokedTokens {
+    pub fn contains(&self, jti: &str) -> bool {
+        self.inner
+            .read()
+            .map(|inner| inner.tokens.contains_key(jti))
+            .unwrap_or(false)
+    }
+
+    // 自プロセスで失効させたトークンは、次の同期を待たずに反映する
+    pub fn insert(&self, jti: String, expires_at: DateTime<Utc>) {
+        if let Ok(mut inner) = self.inner.write() {
+            inner.tokens.insert(jti, expires_at);
+        }
+    }
+
+    pub fn needs_sync(&self) -> bool {
+        self.inner
+            .read()
+            .map(|inner| {
+                inner
+                    .synced_at
+                    .is_none_or(|at| at.elapsed() >= REVOCATION_SYNC_INTERVAL)
+            })
+            .unwrap_or(true)
+    }
+
+    // データベースから読み直した一覧を取り込み、期限切れのものを捨てる。
+    // 失効は取り消されないので、読み直した後に自プロセスで載せたものも残しておく
+    pub fn sync(&self, tokens: impl IntoIterator<Item = (String, DateTime<Utc>)>) {
+        let now = Utc::now();
+        if let Ok(mut inner) = self.inner.write() {
+            inner.tokens.extend(tokens);
+            inner.tokens.retain(|_, expires_at| *expires_at > now);
+            inner.synced_at = Some(Instant::now());
+        }
+    }
+}
+
+/// トランザクションの中で失効させたトークン。
+/// ロールバックされた失効がメモリに残らないよう、コミットしてから失効リストに反映する。
+#[derive(Default)]
+pub struct PendingRevocations {
+    tokens: Mutex<Vec<(String, DateTime<Utc>)>>,
+}
+
+impl PendingRevocations {
+    pub fn push(&self, jti: String, expires_at: DateTime<Utc>) {
+        if let Ok(mut tokens) = self.tokens.lock() {
+            tokens.push((jti, expires_at));
+        }
+    }
+
+    pub fn apply(self, revoked: &RevokedTokens) {
+        for (jti, expires_at) in self.tokens.into_inner().unwrap_or_default() {
+            revoked.insert(jti, expires_at);
+        }
+    }
+}
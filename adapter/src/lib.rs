@@ -1,4 +1,8 @@
 pub mod database;
+pub mod jwt;
+pub mod mail;
+pub mod oidc;
+pub mod password;
 pub mod redis;
 pub mod repository;
 pub mod unit_of_work;
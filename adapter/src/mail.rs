@@ -0,0 +1,88 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use kernel::{mail::MailSender, model::mail::Mail};
+use shared::{config::MailConfig, error::AppResult};
+use std::path::{Path, PathBuf};
+
+// 開発環境向けのメール送信。実際には送信せず、ファイルないしはログに書き出す
+pub struct LocalMailSender {
+    sender: String,
+    output_dir: Option<PathBuf>,
+}
+
+impl LocalMailSender {
+    pub fn new(config: &MailConfig) -> Self {
+        Self {
+            sender: config.sender.clone(),
+            output_dir: config.output_dir.as_ref().map(PathBuf::from),
+        }
+    }
+}
+
+#[async_trait]
+impl MailSender for LocalMailSender {
+    async fn send(&self, mail: Mail) -> AppResult<()> {
+        let Some(dir) = &self.output_dir else {
+            tracing::info!(
+                mail.from = %self.sender,
+                mail.to = %mail.to,
+                mail.subject = %mail.subject,
+                mail.body = %mail.body,
+                "Mail sent"
+            );
+            return Ok(());
+        };
+
+        let content = format!(
+            "From: {}\nTo: {}\nSubject: {}\n\n{}",
+            self.sender, mail.to, mail.subject, mail.body
+        );
+        let path = dir.join(format!(
+            "{}-{}.eml",
+            Utc::now().format("%Y%m%d%H%M%S%f"),
+            mail.to
+        ));
+        // 書き出しに失敗してもリクエスト自体は失敗させない
+        if let Err(e) = write_mail(dir, &path, content).await {
+            tracing::error!(error = %e, path = %path.display(), "Failed to write mail");
+        }
+        Ok(())
+    }
+}
+
+async fn write_mail(dir: &Path, path: &Path, content: String) -> std::io::Result<()> {
+    tokio::fs::create_dir_all(dir).await?;
+    tokio::fs::write(path, content).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_write_mail_to_file() -> anyhow::Result<()> {
+        let dir = std::env::temp_dir().join(format!("mail-{}", Utc::now().timestamp_micros()));
+        let sender = LocalMailSender::new(&MailConfig {
+            sender: "noreply@example.com".into(),
+            output_dir: Some(dir.to_string_lossy().into()),
+        });
+
+        sender
+            .send(Mail::new(
+                "test@example.com".parse()?,
+                "subject".into(),
+                "body".into(),
+            ))
+            .await?;
+
+        let mut entries = tokio::fs::read_dir(&dir).await?;
+        let entry = entries.next_entry().await?.unwrap();
+        let content = tokio::fs::read_to_string(entry.path()).await?;
+        assert!(content.contains("To: test@example.com"));
+        assert!(content.contains("Subject: subject"));
+        assert!(content.ends_with("body"));
+
+        tokio::fs::remove_dir_all(&dir).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,118 @@
+use argon2::{
+    Algorithm, Argon2, Params, PasswordHasher as _, PasswordVerifier, Version,
+    password_hash::{self, PasswordHash, SaltString, rand_core::OsRng},
+};
+use kernel::password::PasswordHasher;
+use shared::{config::PasswordHashConfig, error::AppResult};
+
+// argon2id でハッシュ化する。移行前に bcrypt で作られたハッシュも照合できる
+pub struct Argon2PasswordHasher {
+    params: Params,
+}
+
+impl Argon2PasswordHasher {
+    pub fn new(config: &PasswordHashConfig) -> AppResult<Self> {
+        let params = Params::new(
+            config.memory_cost,
+            config.time_cost,
+            config.parallelism,
+            None,
+        )
+        .map_err(password_hash::Error::from)?;
+        Ok(Self { params })
+    }
+
+    fn argon2(&self) -> Argon2<'static> {
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, self.params.clone())
+    }
+}
+
+impl PasswordHasher for Argon2PasswordHasher {
+    fn hash(&self, password: &str) -> AppResult<String> {
+        let salt = SaltString::generate(&mut OsRng);
+        let hash = self.argon2().hash_password(password.as_bytes(), &salt)?;
+        Ok(hash.to_string())
+    }
+
+    fn verify(&self, password: &str, password_hash: &str) -> AppResult<bool> {
+        if is_bcrypt(password_hash) {
+            return Ok(bcrypt::verify(password, password_hash)?);
+        }
+        // 照合にはハッシュに記録されたパラメータを使う
+        let parsed = PasswordHash::new(password_hash)?;
+        match self.argon2().verify_password(password.as_bytes(), &parsed) {
+            Ok(()) => Ok(true),
+            Err(password_hash::Error::Password) => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn needs_rehash(&self, password_hash: &str) -> bool {
+        if is_bcrypt(password_hash) {
+            return true;
+        }
+        let Ok(parsed) = PasswordHash::new(password_hash) else {
+            return true;
+        };
+        if parsed.algorithm != Algorithm::Argon2id.ident()
+            || parsed.version != Some(Version::V0x13.into())
+        {
+            return true;
+        }
+        Params::try_from(&parsed).map_or(true, |params| {
+            params.m_cost() != self.params.m_cost()
+                || params.t_cost() != self.params.t_cost()
+                || params.p_cost() != self.params.p_cost()
+        })
+    }
+}
+
+fn is_bcrypt(password_hash: &str) -> bool {
+    ["$2a$", "$2b$", "$2x$", "$2y$"]
+        .iter()
+        .any(|prefix| password_hash.starts_with(prefix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Argon2PasswordHasher;
+    use kernel::password::PasswordHasher;
+    use shared::config::PasswordHashConfig;
+
+    fn init_hasher(memory_cost: u32) -> Argon2PasswordHasher {
+        Argon2PasswordHasher::new(&PasswordHashConfig {
+            memory_cost,
+            time_cost: 1,
+            parallelism: 1,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_hash_and_verify() -> anyhow::Result<()> {
+        let hasher = init_hasher(1024);
+        let hash = hasher.hash("password")?;
+        assert!(hash.starts_with("$argon2id$"));
+        assert!(hasher.verify("password", &hash)?);
+        assert!(!hasher.verify("wrong_password", &hash)?);
+        assert!(!hasher.needs_rehash(&hash));
+
+        // パラメータを変更すると作り直しの対象になるが、照合はできる
+        let stronger = init_hasher(2048);
+        assert!(stronger.verify("password", &hash)?);
+        assert!(stronger.needs_rehash(&hash));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_verify_bcrypt() -> anyhow::Result<()> {
+        let hasher = init_hasher(1024);
+        let hash = bcrypt::hash("password", 4)?;
+        assert!(hasher.verify("password", &hash)?);
+        assert!(!hasher.verify("wrong_password", &hash)?);
+        assert!(hasher.needs_rehash(&hash));
+
+        Ok(())
+    }
+}
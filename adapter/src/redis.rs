@@ -1,5 +1,5 @@
 use self::model::{RedisKey, RedisValue};
-use redis::{AsyncCommands, Client};
+use redis::{AsyncCommands, Client, SetExpiry, SetOptions};
 use shared::{config::RedisConfig, error::AppResult};
 
 pub mod model;
@@ -21,18 +21,88 @@ impl RedisClient {
         Ok(())
     }
 
+    // 既存のキーの有効期限を変えずに値だけを更新する
+    pub async fn set_keep_ttl<T: RedisKey>(&self, key: &T, value: &T::Value) -> AppResult<()> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        conn.set_options::<_, _, ()>(
+            key.inner(),
+            value.inner(),
+            SetOptions::default().with_expiration(SetExpiry::KEEPTTL),
+        )
+        .await?;
+        Ok(())
+    }
+
     pub async fn get<T: RedisKey>(&self, key: &T) -> AppResult<Option<T::Value>> {
         let mut conn = self.client.get_multiplexed_async_connection().await?;
         let result: Option<String> = conn.get(key.inner()).await?;
         result.map(T::Value::try_from).transpose()
     }
 
+    pub async fn get_del<T: RedisKey>(&self, key: &T) -> AppResult<Option<T::Value>> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let result: Option<String> = conn.get_del(key.inner()).await?;
+        result.map(T::Value::try_from).transpose()
+    }
+
+    // キーを数値として扱い、1 増やした上で有効期限を延ばす。
+    // 途中で失敗して有効期限のないキーが残らないよう、MULTI でまとめて実行する
+    pub async fn incr<T: RedisKey>(&self, key: &T, ttl: u64) -> AppResult<i64> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let (result,): (i64,) = redis::pipe()
+            .atomic()
+            .incr(key.inner(), 1)
+            .expire(key.inner(), ttl as i64)
+            .ignore()
+            .query_async(&mut conn)
+            .await?;
+        Ok(result)
+    }
+
+    pub async fn expire<T: RedisKey>(&self, key: &T, ttl: u64) -> AppResult<()> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        conn.expire::<_, ()>(key.inner(), ttl as i64).await?;
+        Ok(())
+    }
+
     pub async fn delete<T: RedisKey>(&self, key: &T) -> AppResult<()> {
         let mut conn = self.client.get_multiplexed_async_connection().await?;
         conn.del::<_, ()>(key.inner()).await?;
         Ok(())
     }
 
+    // キーを集合として扱い、要素を追加した上で集合全体の有効期限を延ばす。
+    // incr と同じく MULTI でまとめて実行する
+    pub async fn add_member<T: RedisKey>(
+        &self,
+        key: &T,
+        member: &T::Value,
+        ttl: u64,
+    ) -> AppResult<()> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        redis::pipe()
+            .atomic()
+            .sadd(key.inner(), member.inner())
+            .ignore()
+            .expire(key.inner(), ttl as i64)
+            .ignore()
+            .query_async::<_, ()>(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn members<T: RedisKey>(&self, key: &T) -> AppResult<Vec<T::Value>> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let result: Vec<String> = conn.smembers(key.inner()).await?;
+        result.into_iter().map(T::Value::try_from).collect()
+    }
+
+    pub async fn remove_member<T: RedisKey>(&self, key: &T, member: &T::Value) -> AppResult<()> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        conn.srem::<_, _, ()>(key.inner(), member.inner()).await?;
+        Ok(())
+    }
+
     pub async fn try_connect(&self) -> AppResult<()> {
         let _ = self.client.get_multiplexed_async_connection().await?;
         Ok(())
@@ -108,6 +178,15 @@ mod tests {
         let res_nonexist = client.get(&TestContentKey("redis:key".to_string())).await?;
         assert!(res_nonexist.is_none());
 
+        // 値を増やすと同時に有効期限も設定される
+        let counter = TestContentKey("redis:counter".to_string());
+        assert_eq!(client.incr(&counter, 1000).await?, 1);
+        assert_eq!(client.incr(&counter, 1000).await?, 2);
+        let mut conn = client.client.get_multiplexed_async_connection().await?;
+        let ttl: i64 = conn.ttl(counter.inner()).await?;
+        assert!(ttl > 0);
+        client.delete(&counter).await?;
+
         Ok(())
     }
 }
@@ -1,18 +1,40 @@
 use crate::{
-    database::model::auth::{AuthorizationKey, AuthorizedUserId, from},
+    database::model::auth::{
+        AuthorizationKey, AuthorizedUserId, EmailVerificationKey, LockedUntil, LoginFailureIp,
+        LoginFailureIpsKey, LoginFailuresKey, LoginLockoutKey, MfaChallengeKey, OidcRequestKey,
+        PasswordResetKey, RefreshFamilyKey, RefreshTokenKey, SessionKey, SessionValue,
+        UserSessionsKey, from, from_email_verification, from_mfa_challenge, from_oidc_request,
+        from_password_reset, from_refresh_token,
+    },
     redis::RedisClient,
 };
 use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
 use kernel::{
     model::{
-        auth::{AccessToken, event::CreateToken},
+        auth::{
+            AccessToken, EmailVerificationToken, LoginAttemptKey, MfaChallengeToken,
+            PasswordResetToken, PendingEmailChange, RefreshToken, RefreshTokenEntry,
+            event::{
+                CreateEmailVerificationToken, CreateMfaChallenge, CreatePasswordResetToken,
+                CreateRefreshToken, CreateToken,
+            },
+        },
         id::UserId,
+        oidc::OidcAuthorizationRequest,
+        session::Session,
     },
     repository::auth::AuthRepository,
 };
-use shared::error::AppResult;
+use shared::{
+    error::{AppError, AppResult},
+    i18n::{Message, MessageKey},
+};
 use std::sync::Arc;
 
+// 最終利用日時はリクエストのたびには書き込まず、この間隔ごとに更新する
+const LAST_SEEN_UPDATE_INTERVAL_SECS: i64 = 60;
+
 pub struct AuthRepositoryImpl {
     kv: Arc<RedisClient>,
     ttl: u64,
@@ -22,21 +44,152 @@ impl AuthRepositoryImpl {
     pub fn new(kv: Arc<RedisClient>, ttl: u64) -> Self {
         Self { kv, ttl }
     }
+
+    // セッションと、そのセッションに紐づくアクセストークン・リフレッシュトークンの系列を削除する
+    async fn remove_session(
+        &self,
+        user_id: UserId,
+        session_key: &SessionKey,
+        session: SessionValue,
+    ) -> AppResult<()> {
+        let key: AuthorizationKey = AccessToken(session.access_token).into();
+        self.kv.delete(&key).await?;
+        if let Some(family_id) = session.family_id {
+            self.kv.delete(&RefreshFamilyKey::new(&family_id)).await?;
+        }
+        self.kv.delete(session_key).await?;
+        self.kv
+            .remove_member(&UserSessionsKey::new(user_id), &session_key.session_id())
+            .await
+    }
 }
 
 #[async_trait]
 impl AuthRepository for AuthRepositoryImpl {
+    async fn create_refresh_token(
+        &self,
+        event: CreateRefreshToken,
+        ttl: u64,
+    ) -> AppResult<RefreshToken> {
+        let (key, value, family_key, user_id) = from_refresh_token(event);
+        self.kv.set_ex(&key, &value, ttl).await?;
+        self.kv.set_ex(&family_key, &user_id, ttl).await?;
+        Ok(key.into())
+    }
+
     async fn create_token(&self, event: CreateToken) -> AppResult<AccessToken> {
-        let (key, value) = from(event);
+        let user_id = event.user_id;
+        let (key, value, session_key, session) = from(event);
         self.kv.set_ex(&key, &value, self.ttl).await?;
+        self.kv.set_ex(&session_key, &session, self.ttl).await?;
+        self.kv
+            .add_member(
+                &UserSessionsKey::new(user_id),
+                &session_key.session_id(),
+                self.ttl,
+            )
+            .await?;
         Ok(key.into())
     }
 
+    async fn delete_all_sessions(&self, user_id: UserId) -> AppResult<()> {
+        let sessions_key = UserSessionsKey::new(user_id);
+        for session_id in self.kv.members(&sessions_key).await? {
+            let session_key = SessionKey::from(&session_id);
+            match self.kv.get(&session_key).await? {
+                Some(session) => self.remove_session(user_id, &session_key, session).await?,
+                None => self.kv.delete(&session_key).await?,
+            }
+        }
+        self.kv.delete(&sessions_key).await
+    }
+
+    async fn delete_session(&self, user_id: UserId, session_id: &str) -> AppResult<()> {
+        let session_key = SessionKey::new(session_id);
+        let Some(session) = self
+            .kv
+            .get(&session_key)
+            .await?
+            .filter(|s| s.user_id == user_id)
+        else {
+            return Err(AppError::EntityNotFound(
+                Message::new(MessageKey::SessionNotFound).arg(session_id),
+            ));
+        };
+        self.remove_session(user_id, &session_key, session).await
+    }
+
     async fn delete_token(&self, access_token: AccessToken) -> AppResult<()> {
+        let session_key = SessionKey::from(&access_token);
+        if let Some(session) = self.kv.get(&session_key).await? {
+            return self
+                .remove_session(session.user_id, &session_key, session)
+                .await;
+        }
         let key: AuthorizationKey = access_token.into();
         self.kv.delete(&key).await
     }
 
+    async fn extend_token(&self, access_token: &AccessToken) -> AppResult<()> {
+        let session_key = SessionKey::from(access_token);
+        let Some(mut session) = self.kv.get(&session_key).await? else {
+            return Ok(());
+        };
+        let now = Utc::now();
+        if now - session.last_seen_at < Duration::seconds(LAST_SEEN_UPDATE_INTERVAL_SECS) {
+            return Ok(());
+        }
+        session.last_seen_at = now;
+        let key: AuthorizationKey = access_token.into();
+        self.kv.set_ex(&session_key, &session, self.ttl).await?;
+        self.kv.expire(&key, self.ttl).await?;
+        self.kv
+            .expire(&UserSessionsKey::new(session.user_id), self.ttl)
+            .await
+    }
+
+    async fn find_refresh_token(
+        &self,
+        refresh_token: &RefreshToken,
+    ) -> AppResult<Option<RefreshTokenEntry>> {
+        let key: RefreshTokenKey = refresh_token.into();
+        let Some(value) = self.kv.get(&key).await? else {
+            return Ok(None);
+        };
+        if self
+            .kv
+            .get(&RefreshFamilyKey::new(&value.family_id))
+            .await?
+            .is_none()
+        {
+            return Ok(None);
+        }
+        Ok(Some(value.into()))
+    }
+
+    async fn find_sessions(
+        &self,
+        user_id: UserId,
+        current: &AccessToken,
+    ) -> AppResult<Vec<Session>> {
+        let sessions_key = UserSessionsKey::new(user_id);
+        let current_key = SessionKey::from(current).session_id();
+        let mut sessions = Vec::new();
+        for session_id in self.kv.members(&sessions_key).await? {
+            let session_key = SessionKey::from(&session_id);
+            match self.kv.get(&session_key).await? {
+                Some(session) => {
+                    let is_current = session_id == current_key;
+                    sessions.push(session.into_session(&session_key, is_current));
+                }
+                // 有効期限切れで消えたセッションは索引からも取り除く
+                None => self.kv.remove_member(&sessions_key, &session_id).await?,
+            }
+        }
+        sessions.sort_by_key(|s| std::cmp::Reverse(s.last_seen_at()));
+        Ok(sessions)
+    }
+
     async fn fetch_user_id_from_token(
         &self,
         access_token: &AccessToken,
@@ -47,4 +200,171 @@ impl AuthRepository for AuthRepositoryImpl {
             .await
             .map(|x| x.map(AuthorizedUserId::into_inner))
     }
+
+    async fn mark_refresh_token_rotated(&self, refresh_token: &RefreshToken) -> AppResult<()> {
+        let key: RefreshTokenKey = refresh_token.into();
+        if let Some(mut value) = self.kv.get(&key).await? {
+            value.rotated = true;
+            self.kv.set_keep_ttl(&key, &value).await?;
+        }
+        Ok(())
+    }
+
+    async fn revoke_refresh_family(&self, user_id: UserId, family_id: &str) -> AppResult<()> {
+        let sessions_key = UserSessionsKey::new(user_id);
+        for session_id in self.kv.members(&sessions_key).await? {
+            let session_key = SessionKey::from(&session_id);
+            if let Some(session) = self.kv.get(&session_key).await?
+                && session.family_id.as_deref() == Some(family_id)
+            {
+                self.remove_session(user_id, &session_key, session).await?;
+            }
+        }
+        self.kv.delete(&RefreshFamilyKey::new(family_id)).await
+    }
+
+    async fn find_login_lockout(&self, key: &LoginAttemptKey) -> AppResult<Option<DateTime<Utc>>> {
+        let locked_until = self.kv.get(&LoginLockoutKey::from(key)).await?;
+        Ok(locked_until
+            .map(|LockedUntil(until)| until)
+            .filter(|until| *until > Utc::now()))
+    }
+
+    async fn record_login_failure(&self, key: &LoginAttemptKey, window: u64) -> AppResult<u32> {
+        let failures = self.kv.incr(&LoginFailuresKey::from(key), window).await?;
+        Ok(failures.try_into().unwrap_or(u32::MAX))
+    }
+
+    async fn lock_login(&self, key: &LoginAttemptKey, until: DateTime<Utc>) -> AppResult<()> {
+        let ttl = (until - Utc::now()).num_seconds().max(1) as u64;
+        self.kv
+            .set_ex(&LoginLockoutKey::from(key), &LockedUntil(until), ttl)
+            .await
+    }
+
+    async fn clear_login_failures(&self, key: &LoginAttemptKey) -> AppResult<()> {
+        if let LoginAttemptKey::Email(email) = key {
+            self.kv.delete(&LoginFailureIpsKey::new(email)).await?;
+        }
+        self.kv.delete(&LoginFailuresKey::from(key)).await?;
+        self.kv.delete(&LoginLockoutKey::from(key)).await
+    }
+
+    async fn record_login_failure_ip(
+        &self,
+        email: &str,
+        ip_address: &str,
+        window: u64,
+    ) -> AppResult<()> {
+        self.kv
+            .add_member(
+                &LoginFailureIpsKey::new(email),
+                &LoginFailureIp(ip_address.to_string()),
+                window,
+            )
+            .await
+    }
+
+    async fn find_login_failure_ips(&self, email: &str) -> AppResult<Vec<String>> {
+        let ips = self.kv.members(&LoginFailureIpsKey::new(email)).await?;
+        Ok(ips.into_iter().map(|LoginFailureIp(ip)| ip).collect())
+    }
+
+    async fn create_mfa_challenge(
+        &self,
+        event: CreateMfaChallenge,
+        ttl: u64,
+    ) -> AppResult<MfaChallengeToken> {
+        let (key, value) = from_mfa_challenge(event);
+        self.kv.set_ex(&key, &value, ttl).await?;
+        Ok(key.into())
+    }
+
+    async fn consume_mfa_challenge(
+        &self,
+        challenge_token: &MfaChallengeToken,
+    ) -> AppResult<Option<UserId>> {
+        let key: MfaChallengeKey = challenge_token.into();
+        self.kv
+            .get_del(&key)
+            .await
+            .map(|x| x.map(AuthorizedUserId::into_inner))
+    }
+
+    async fn create_oidc_request(
+        &self,
+        request: &OidcAuthorizationRequest,
+        ttl: u64,
+    ) -> AppResult<()> {
+        let (key, value) = from_oidc_request(request);
+        self.kv.set_ex(&key, &value, ttl).await
+    }
+
+    async fn consume_oidc_request(
+        &self,
+        state: &str,
+    ) -> AppResult<Option<OidcAuthorizationRequest>> {
+        let key = OidcRequestKey::new(state);
+        let value = self.kv.get_del(&key).await?;
+        Ok(value.map(|value| OidcRequestKey::new(state).into_request(value)))
+    }
+
+    async fn create_password_reset_token(
+        &self,
+        event: CreatePasswordResetToken,
+        ttl: u64,
+    ) -> AppResult<PasswordResetToken> {
+        let (key, value) = from_password_reset(event);
+        self.kv.set_ex(&key, &value, ttl).await?;
+        Ok(key.into())
+    }
+
+    async fn find_password_reset_token(
+        &self,
+        reset_token: &PasswordResetToken,
+    ) -> AppResult<Option<UserId>> {
+        let key: PasswordResetKey = reset_token.into();
+        self.kv
+            .get(&key)
+            .await
+            .map(|x| x.map(AuthorizedUserId::into_inner))
+    }
+
+    async fn consume_password_reset_token(
+        &self,
+        reset_token: &PasswordResetToken,
+    ) -> AppResult<Option<UserId>> {
+        let key: PasswordResetKey = reset_token.into();
+        self.kv
+            .get_del(&key)
+            .await
+            .map(|x| x.map(AuthorizedUserId::into_inner))
+    }
+
+    async fn create_email_verification_token(
+        &self,
+        event: CreateEmailVerificationToken,
+        ttl: u64,
+    ) -> AppResult<EmailVerificationToken> {
+        let (key, value) = from_email_verification(event);
+        self.kv.set_ex(&key, &value, ttl).await?;
+        Ok(key.into())
+    }
+
+    async fn consume_email_verification_token(
+        &self,
+        verification_token: &EmailVerificationToken,
+    ) -> AppResult<Option<PendingEmailChange>> {
+        let key: EmailVerificationKey = verification_token.into();
+        self.kv
+            .get_del(&key)
+            .await?
+            .map(PendingEmailChange::try_from)
+            .transpose()
+    }
+
+    // Redis のキーは有効期限付きで保存しているため、削除するものはない
+    async fn delete_expired(&self) -> AppResult<()> {
+        Ok(())
+    }
 }
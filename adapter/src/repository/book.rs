@@ -1,20 +1,23 @@
 use crate::database::{
-    ConnectionSource,
-    model::book::{BookCheckoutRow, BookRow, PaginatedBookRow},
+    ConnectionSource, like_pattern,
+    model::book::{BookChangeLogRow, BookCheckoutRow, BookRow, PaginatedBookRow},
 };
 use async_trait::async_trait;
 use kernel::{
     model::{
         book::{
-            Book, BookListOptions, Checkout,
-            event::{CreateBook, DeleteBook, UpdateBook},
+            Book, BookChangeAction, BookChangeLog, BookListOptions, Checkout,
+            event::{CreateBook, DeleteBook, TransferAllBooks, UpdateBook, UpdateBookOwner},
         },
         id::{BookId, UserId},
         list::PaginatedList,
     },
     repository::book::BookRepository,
 };
-use shared::error::{AppError, AppResult};
+use shared::{
+    error::{AppError, AppResult},
+    i18n::{Message, MessageKey},
+};
 use std::collections::HashMap;
 
 pub struct BookRepositoryImpl<'t, 'm> {
@@ -56,25 +59,40 @@ impl<'t, 'm> BookRepository for BookRepositoryImpl<'t, 'm> {
             r#"
                 DELETE FROM books
                 WHERE book_id = $1
-                AND user_id = $2
             "#,
-            event.book_id as _,
-            event.requested_user as _
+            event.book_id as _
         )
         .execute(&mut *conn)
         .await
         .map_err(AppError::SpecificOperationError)?;
 
         if res.rows_affected() < 1 {
-            return Err(AppError::EntityNotFound("specified book not found".into()));
+            return Err(AppError::EntityNotFound(
+                Message::new(MessageKey::BookNotFound).arg(event.book_id),
+            ));
         }
+        drop(conn);
 
-        Ok(())
+        self.insert_change_log(
+            event.book_id,
+            BookChangeAction::Delete,
+            event.requested_user,
+        )
+        .await
     }
 
     async fn find_all(&self, options: BookListOptions) -> AppResult<PaginatedList<Book>> {
         let mut conn = self.source.acquire().await?;
-        let BookListOptions { limit, offset } = options;
+        let BookListOptions {
+            limit,
+            offset,
+            query,
+            author,
+            isbn,
+            owner,
+            available_only,
+            checked_out_by,
+        } = options;
         let rows: Vec<PaginatedBookRow> = sqlx::query_as!(
             PaginatedBookRow,
             r#"
@@ -82,12 +100,43 @@ impl<'t, 'm> BookRepository for BookRepositoryImpl<'t, 'm> {
                     COUNT(*) OVER() AS "total!",
                     b.book_id AS id
                 FROM books AS b
-                ORDER BY b.created_at DESC
+                WHERE (
+                    $3::text IS NULL
+                    OR b.search_vector @@ websearch_to_tsquery('simple', $3)
+                    OR b.title ILIKE $9 ESCAPE '\'
+                )
+                AND ($4::text IS NULL OR b.author ILIKE $4 ESCAPE '\')
+                AND ($5::text IS NULL OR REPLACE(b.isbn, '-', '') = REPLACE($5, '-', ''))
+                AND ($6::uuid IS NULL OR b.user_id = $6)
+                AND (
+                    NOT $7
+                    OR NOT EXISTS (SELECT 1 FROM checkouts AS c WHERE c.book_id = b.book_id)
+                )
+                AND (
+                    $8::uuid IS NULL
+                    OR EXISTS (
+                        SELECT 1 FROM checkouts AS c
+                        WHERE c.book_id = b.book_id AND c.user_id = $8
+                    )
+                )
+                ORDER BY
+                    CASE
+                        WHEN $3::text IS NULL THEN 0
+                        ELSE ts_rank(b.search_vector, websearch_to_tsquery('simple', $3))
+                    END DESC,
+                    b.created_at DESC
                 LIMIT $1
                 OFFSET $2
             "#,
             limit,
             offset,
+            query,
+            author.as_deref().map(like_pattern),
+            isbn,
+            owner as _,
+            available_only,
+            checked_out_by as _,
+            query.as_deref().map(like_pattern),
         )
         .fetch_all(&mut *conn)
         .await
@@ -110,13 +159,15 @@ impl<'t, 'm> BookRepository for BookRepositoryImpl<'t, 'm> {
                 FROM books AS b
                     INNER JOIN users AS u USING(user_id)
                 WHERE b.book_id IN (SELECT * FROM UNNEST($1::uuid[]))
-                ORDER BY b.created_at DESC
+                ORDER BY array_position($1::uuid[], b.book_id)
             "#,
             &book_ids as _,
         )
         .fetch_all(&mut *conn)
         .await
         .map_err(AppError::SpecificOperationError)?;
+        // トランザクション内では接続を共有するため、貸出情報の取得前に解放する
+        drop(conn);
 
         let book_ids = rows.iter().map(|book| book.book_id).collect::<Vec<_>>();
         let mut checkouts = self.find_checkouts(&book_ids).await?;
@@ -158,6 +209,7 @@ impl<'t, 'm> BookRepository for BookRepositoryImpl<'t, 'm> {
         .fetch_optional(&mut *conn)
         .await
         .map_err(AppError::SpecificOperationError)?;
+        drop(conn);
 
         match row {
             Some(r) => {
@@ -168,6 +220,79 @@ impl<'t, 'm> BookRepository for BookRepositoryImpl<'t, 'm> {
         }
     }
 
+    async fn find_change_logs(&self, book_id: BookId) -> AppResult<Vec<BookChangeLog>> {
+        let mut conn = self.source.acquire().await?;
+        sqlx::query_as!(
+            BookChangeLogRow,
+            r#"
+                SELECT
+                    action,
+                    performed_by AS "performed_by: UserId",
+                    performed_at
+                FROM book_change_logs
+                WHERE book_id = $1
+                ORDER BY performed_at ASC, book_change_log_id ASC
+            "#,
+            book_id as _
+        )
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .into_iter()
+        .map(BookChangeLog::try_from)
+        .collect()
+    }
+
+    async fn transfer_all(&self, event: TransferAllBooks) -> AppResult<u64> {
+        let mut conn = self.source.acquire().await?;
+        // 無効化されたユーザーの蔵書も移管できるよう、無効化の有無は問わない
+        let exists = sqlx::query_scalar!(
+            r#"
+                SELECT EXISTS (
+                    SELECT 1 FROM users
+                    WHERE user_id = $1
+                ) AS "exists!"
+            "#,
+            event.from_user as _
+        )
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if !exists {
+            return Err(AppError::EntityNotFound(
+                Message::new(MessageKey::UserNotFound).arg(event.from_user),
+            ));
+        }
+
+        let book_ids = sqlx::query_scalar!(
+            r#"
+                UPDATE books
+                SET user_id = $1
+                WHERE user_id = $2
+                RETURNING book_id
+            "#,
+            event.to_user as _,
+            event.from_user as _
+        )
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        sqlx::query!(
+            r#"
+                INSERT INTO book_change_logs(book_id, action, performed_by)
+                SELECT book_id, $2, $3 FROM UNNEST($1::uuid[]) AS book_id
+            "#,
+            &book_ids,
+            BookChangeAction::Transfer.as_ref(),
+            event.requested_user as _
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(book_ids.len() as u64)
+    }
+
     async fn update(&self, event: UpdateBook) -> AppResult<()> {
         let mut conn = self.source.acquire().await?;
         let res = sqlx::query!(
@@ -179,27 +304,85 @@ impl<'t, 'm> BookRepository for BookRepositoryImpl<'t, 'm> {
                     isbn = $3,
                     description = $4
                 WHERE book_id = $5
-                AND user_id = $6
             "#,
             event.title.as_ref(),
             event.author.as_ref(),
             event.isbn.as_ref(),
             event.description.as_ref(),
-            event.book_id as _,
-            event.requested_user as _
+            event.book_id as _
         )
         .execute(&mut *conn)
         .await
         .map_err(AppError::SpecificOperationError)?;
         if res.rows_affected() < 1 {
-            return Err(AppError::EntityNotFound("specified book not found".into()));
+            return Err(AppError::EntityNotFound(
+                Message::new(MessageKey::BookNotFound).arg(event.book_id),
+            ));
         }
+        drop(conn);
 
-        Ok(())
+        self.insert_change_log(
+            event.book_id,
+            BookChangeAction::Update,
+            event.requested_user,
+        )
+        .await
+    }
+
+    async fn update_owner(&self, event: UpdateBookOwner) -> AppResult<()> {
+        let mut conn = self.source.acquire().await?;
+        let res = sqlx::query!(
+            r#"
+                UPDATE books
+                SET user_id = $1
+                WHERE book_id = $2
+            "#,
+            event.new_owner as _,
+            event.book_id as _
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if res.rows_affected() < 1 {
+            return Err(AppError::EntityNotFound(
+                Message::new(MessageKey::BookNotFound).arg(event.book_id),
+            ));
+        }
+        drop(conn);
+
+        self.insert_change_log(
+            event.book_id,
+            BookChangeAction::Transfer,
+            event.requested_user,
+        )
+        .await
     }
 }
 
 impl<'t, 'm> BookRepositoryImpl<'t, 'm> {
+    // 所有者以外による変更もあるため、誰が行ったかを記録しておく
+    async fn insert_change_log(
+        &self,
+        book_id: BookId,
+        action: BookChangeAction,
+        performed_by: UserId,
+    ) -> AppResult<()> {
+        let mut conn = self.source.acquire().await?;
+        sqlx::query!(
+            r#"
+                INSERT INTO book_change_logs(book_id, action, performed_by)
+                VALUES ($1, $2, $3)
+            "#,
+            book_id as _,
+            action.as_ref(),
+            performed_by as _
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(())
+    }
+
     async fn find_checkouts(&self, book_ids: &[BookId]) -> AppResult<HashMap<BookId, Checkout>> {
         let mut conn = self.source.acquire().await?;
         let res = sqlx::query_as!(
@@ -210,7 +393,8 @@ impl<'t, 'm> BookRepositoryImpl<'t, 'm> {
                     c.book_id,
                     u.user_id,
                     u.name AS user_name,
-                    c.checked_out_at
+                    c.checked_out_at,
+                    c.due_at
                 FROM checkouts AS c
                     INNER JOIN users AS u USING(user_id)
                 WHERE book_id = ANY($1)
@@ -243,16 +427,23 @@ mod tests {
         model::{
             book::{
                 Book, BookListOptions,
-                event::{CreateBook, UpdateBook},
+                event::{CreateBook, TransferAllBooks, UpdateBook, UpdateBookOwner},
             },
             checkout::event::{CreateCheckout, UpdateReturned},
             id::{BookId, UserId},
-            user::{BookOwner, event::CreateUser},
+            role::Role,
+            user::{
+                BookOwner,
+                event::{CreateUser, UpdateUserRole},
+            },
         },
         repository::user::UserRepository,
-        use_case::checkout::{CheckoutUseCase, CheckoutUseCaseImpl},
+        use_case::{
+            book::{BookUseCase, BookUseCaseImpl},
+            checkout::{CheckoutUseCase, CheckoutUseCaseImpl},
+        },
     };
-    use shared::config::RedisConfig;
+    use shared::config::{CheckoutConfig, RedisConfig};
     use std::{str::FromStr, sync::Arc};
 
     #[sqlx::test]
@@ -263,11 +454,14 @@ mod tests {
         let user_repo = UserRepositoryImpl::new(source.clone());
         let repo = BookRepositoryImpl::new(source.clone());
         let user = user_repo
-            .create(CreateUser {
-                name: "Test User".parse().unwrap(),
-                email: "test@example.com".parse().unwrap(),
-                password: "test_password".into(),
-            })
+            .create(
+                CreateUser {
+                    name: "Test User".parse().unwrap(),
+                    email: "test@example.com".parse().unwrap(),
+                    password: "test_password".into(),
+                },
+                "test_password_hash".into(),
+            )
             .await?;
         let book = CreateBook {
             title: "Test Title".parse().unwrap(),
@@ -280,6 +474,7 @@ mod tests {
         let options = BookListOptions {
             limit: 20,
             offset: 0,
+            ..Default::default()
         };
         let res = repo.find_all(options).await?;
         assert_eq!(res.items.len(), 1);
@@ -354,6 +549,7 @@ mod tests {
             .find_all(BookListOptions {
                 limit: 10,
                 offset: 0,
+                ..Default::default()
             })
             .await?;
         assert_eq!(res.total, LEN);
@@ -365,6 +561,7 @@ mod tests {
             .find_all(BookListOptions {
                 limit: 10,
                 offset: 10,
+                ..Default::default()
             })
             .await?;
         assert_eq!(res.total, LEN);
@@ -376,6 +573,7 @@ mod tests {
             .find_all(BookListOptions {
                 limit: 10,
                 offset: 100,
+                ..Default::default()
             })
             .await?;
         assert_eq!(res.total, 0);
@@ -386,17 +584,120 @@ mod tests {
         Ok(())
     }
 
+    #[sqlx::test(fixtures("common", "book_list"))]
+    async fn test_list_search(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = BookRepositoryImpl::new(pool.clone());
+        let owner_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+        let book_id = BookId::from_str("51E949EE-1B64-4CD7-A49A-7A57BDADE4DF")?;
+        sqlx::query!(
+            r#"INSERT INTO checkouts (book_id, user_id, due_at) VALUES ($1, $2, now());"#,
+            book_id as _,
+            owner_id as _,
+        )
+        .execute(&pool)
+        .await?;
+
+        let res = repo
+            .find_all(BookListOptions {
+                limit: 10,
+                offset: 0,
+                query: Some("title010".into()),
+                ..Default::default()
+            })
+            .await?;
+        assert_eq!(res.total, 1);
+        assert_eq!(res.items[0].title().as_ref(), "title010");
+
+        let res = repo
+            .find_all(BookListOptions {
+                limit: 10,
+                offset: 0,
+                author: Some("AUTHOR00".into()),
+                ..Default::default()
+            })
+            .await?;
+        assert_eq!(res.total, 9);
+        assert_eq!(res.items[0].author().as_ref(), "author009");
+
+        // 検索語に含まれる LIKE のワイルドカードは文字どおりに扱う
+        for (query, author) in [(Some("title_10"), None), (None, Some("author%"))] {
+            let res = repo
+                .find_all(BookListOptions {
+                    limit: 10,
+                    offset: 0,
+                    query: query.map(Into::into),
+                    author: author.map(Into::into),
+                    ..Default::default()
+                })
+                .await?;
+            assert_eq!(res.total, 0);
+        }
+
+        let res = repo
+            .find_all(BookListOptions {
+                limit: 10,
+                offset: 0,
+                isbn: Some("isbn-025".into()),
+                ..Default::default()
+            })
+            .await?;
+        assert_eq!(res.total, 1);
+        assert_eq!(res.items[0].isbn().as_ref(), "isbn025");
+
+        let res = repo
+            .find_all(BookListOptions {
+                limit: 10,
+                offset: 0,
+                owner: Some(UserId::new()),
+                ..Default::default()
+            })
+            .await?;
+        assert_eq!(res.total, 0);
+
+        let res = repo
+            .find_all(BookListOptions {
+                limit: 10,
+                offset: 0,
+                available_only: true,
+                ..Default::default()
+            })
+            .await?;
+        assert_eq!(res.total, 49);
+
+        let res = repo
+            .find_all(BookListOptions {
+                limit: 10,
+                offset: 0,
+                checked_out_by: Some(owner_id),
+                ..Default::default()
+            })
+            .await?;
+        assert_eq!(res.total, 1);
+        assert_eq!(res.items[0].id(), book_id);
+        assert!(res.items[0].checkout().is_some());
+
+        Ok(())
+    }
+
     #[sqlx::test(fixtures("common", "book_checkout"))]
     async fn test_book_checkout(source: sqlx::PgPool) -> anyhow::Result<()> {
         let book_repo = BookRepositoryImpl::new(source.clone());
-        let checkout_use_case = CheckoutUseCaseImpl::new(Arc::new(UnitOfWorkScopeImpl::new(
-            Arc::new(ConnectionPool::from(source.clone())),
-            Arc::new(RedisClient::new(&RedisConfig {
-                host: std::env::var("REDIS_HOST")?,
-                port: std::env::var("REDIS_PORT")?.parse::<u16>()?,
-            })?),
-            std::env::var("AUTH_TOKEN_TTL")?.parse::<u64>()?,
-        )));
+        let checkout_use_case = CheckoutUseCaseImpl::new(
+            Arc::new(UnitOfWorkScopeImpl::new(
+                Arc::new(ConnectionPool::from(source.clone())),
+                Arc::new(RedisClient::new(&RedisConfig {
+                    host: std::env::var("REDIS_HOST")?,
+                    port: std::env::var("REDIS_PORT")?.parse::<u16>()?,
+                })?),
+                std::env::var("AUTH_TOKEN_TTL")?.parse::<u64>()?,
+            )),
+            CheckoutConfig {
+                loan_period_days: 14,
+                hold_pickup_days: 3,
+                max_renewals: 2,
+                max_loans: 5,
+            },
+        );
 
         let user_id1 = UserId::from_str("9582f9de-0fd1-4892-b20c-70139a7eb95b").unwrap();
         let user_id2 = UserId::from_str("050afe56-c3da-4448-8e4d-6f44007d2ca5").unwrap();
@@ -405,6 +706,7 @@ mod tests {
             .find_all(BookListOptions {
                 limit: 20,
                 offset: 0,
+                ..Default::default()
             })
             .await?
             .into_inner()
@@ -469,4 +771,239 @@ mod tests {
 
         Ok(())
     }
+
+    #[sqlx::test(fixtures("common", "book_checkout"))]
+    async fn test_transfer_books(source: sqlx::PgPool) -> anyhow::Result<()> {
+        let book_repo = BookRepositoryImpl::new(source.clone());
+        let scope = Arc::new(UnitOfWorkScopeImpl::new(
+            Arc::new(ConnectionPool::from(source.clone())),
+            Arc::new(RedisClient::new(&RedisConfig {
+                host: std::env::var("REDIS_HOST")?,
+                port: std::env::var("REDIS_PORT")?.parse::<u16>()?,
+            })?),
+            std::env::var("AUTH_TOKEN_TTL")?.parse::<u64>()?,
+        ));
+        let book_use_case = BookUseCaseImpl::new(scope.clone());
+        let checkout_use_case = CheckoutUseCaseImpl::new(
+            scope,
+            CheckoutConfig {
+                loan_period_days: 14,
+                hold_pickup_days: 3,
+                max_renewals: 2,
+                max_loans: 5,
+            },
+        );
+
+        let admin_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+        let user_id1 = UserId::from_str("9582f9de-0fd1-4892-b20c-70139a7eb95b")?;
+        let user_id2 = UserId::from_str("050afe56-c3da-4448-8e4d-6f44007d2ca5")?;
+        let book_id = BookId::from_str("9890736e-a4e4-461a-a77d-eac3517ef11b")?;
+
+        checkout_use_case
+            .checkout_book(CreateCheckout {
+                book_id,
+                checked_out_by: user_id1,
+                checked_out_at: Utc::now(),
+            })
+            .await?;
+        let checkout_id = book_repo
+            .find_by_id(book_id)
+            .await?
+            .and_then(|b| b.checkout().map(|co| co.id()))
+            .unwrap();
+
+        // 所有者でないユーザーは所有者を変更できない
+        let res = book_use_case
+            .update_book_owner(UpdateBookOwner {
+                book_id,
+                new_owner: user_id2,
+                requested_user: user_id2,
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::ForbiddenOperation)));
+
+        // 現在の所有者を新しい所有者には指定できない
+        let res = book_use_case
+            .update_book_owner(UpdateBookOwner {
+                book_id,
+                new_owner: admin_id,
+                requested_user: admin_id,
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        // 移管元と移管先が同じユーザーの場合や、移管元のユーザーが存在しない場合は移管できない
+        let res = book_use_case
+            .transfer_all_books(TransferAllBooks {
+                from_user: admin_id,
+                to_user: admin_id,
+                requested_user: admin_id,
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+        let res = book_use_case
+            .transfer_all_books(TransferAllBooks {
+                from_user: UserId::new(),
+                to_user: user_id2,
+                requested_user: admin_id,
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+
+        // 存在しないユーザーには移管できない
+        let res = book_use_case
+            .transfer_all_books(TransferAllBooks {
+                from_user: admin_id,
+                to_user: UserId::new(),
+                requested_user: admin_id,
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+
+        let transferred = book_use_case
+            .transfer_all_books(TransferAllBooks {
+                from_user: admin_id,
+                to_user: user_id2,
+                requested_user: admin_id,
+            })
+            .await?;
+        assert_eq!(transferred, 1);
+
+        // 貸出中の状態は移管後もそのまま残る
+        let book = book_repo.find_by_id(book_id).await?.unwrap();
+        assert_eq!(book.owner().id(), user_id2);
+        let checkout = book.checkout().unwrap();
+        assert_eq!(checkout.id(), checkout_id);
+        assert_eq!(checkout.checked_out_by().id(), user_id1);
+
+        book_use_case
+            .update_book_owner(UpdateBookOwner {
+                book_id,
+                new_owner: user_id1,
+                requested_user: user_id2,
+            })
+            .await?;
+        let book = book_repo.find_by_id(book_id).await?.unwrap();
+        assert_eq!(book.owner().id(), user_id1);
+
+        let performed_by = sqlx::query_scalar!(
+            r#"
+                SELECT performed_by AS "performed_by!: UserId" FROM book_change_logs
+                WHERE book_id = $1 AND action = 'transfer'
+                ORDER BY performed_at
+            "#,
+            book_id as _
+        )
+        .fetch_all(&source)
+        .await?;
+        assert_eq!(performed_by, [admin_id, user_id2]);
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "book"))]
+    async fn test_book_change_by_non_owner(source: sqlx::PgPool) -> anyhow::Result<()> {
+        let user_repo = UserRepositoryImpl::new(source.clone());
+        let book_repo = BookRepositoryImpl::new(source.clone());
+        let book_use_case = BookUseCaseImpl::new(Arc::new(UnitOfWorkScopeImpl::new(
+            Arc::new(ConnectionPool::from(source.clone())),
+            Arc::new(RedisClient::new(&RedisConfig {
+                host: std::env::var("REDIS_HOST")?,
+                port: std::env::var("REDIS_PORT")?.parse::<u16>()?,
+            })?),
+            std::env::var("AUTH_TOKEN_TTL")?.parse::<u64>()?,
+        )));
+
+        let mut users = Vec::new();
+        for (email, role) in [
+            ("user@example.com", Role::User),
+            ("librarian@example.com", Role::Librarian),
+        ] {
+            let user = user_repo
+                .create(
+                    CreateUser {
+                        name: "Test User".parse()?,
+                        email: email.parse()?,
+                        password: "test_password".into(),
+                    },
+                    "test_password_hash".into(),
+                )
+                .await?;
+            user_repo
+                .update_role(UpdateUserRole {
+                    user_id: user.id(),
+                    role,
+                })
+                .await?;
+            users.push(user.id());
+        }
+        let (user_id, librarian_id) = (users[0], users[1]);
+
+        let book_id = BookId::from_str("9890736e-a4e4-461a-a77d-eac3517ef11b")?;
+        let book = book_repo.find_by_id(book_id).await?.unwrap();
+        let update_book = |requested_user| UpdateBook {
+            book_id: book.id(),
+            title: "タイトルの誤字を修正".parse().unwrap(),
+            author: book.author().clone(),
+            isbn: book.isbn().clone(),
+            description: book.description().clone(),
+            requested_user,
+        };
+
+        // 所有者でも司書でもないユーザーは変更・削除できない
+        let res = book_use_case.update_book(update_book(user_id)).await;
+        assert!(matches!(res, Err(AppError::ForbiddenOperation)));
+        let res = book_use_case
+            .delete_book(DeleteBook {
+                book_id,
+                requested_user: user_id,
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::ForbiddenOperation)));
+
+        let res = book_use_case
+            .delete_book(DeleteBook {
+                book_id: BookId::new(),
+                requested_user: librarian_id,
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+
+        book_use_case.update_book(update_book(librarian_id)).await?;
+        let updated = book_repo.find_by_id(book_id).await?.unwrap();
+        assert_eq!(updated.title().as_ref(), "タイトルの誤字を修正");
+        book_use_case
+            .delete_book(DeleteBook {
+                book_id,
+                requested_user: librarian_id,
+            })
+            .await?;
+        assert!(book_repo.find_by_id(book_id).await?.is_none());
+
+        // 削除後も変更履歴は残る
+        let logs = book_repo.find_change_logs(book_id).await?;
+        assert_eq!(
+            logs.iter().map(|log| log.action()).collect::<Vec<_>>(),
+            [BookChangeAction::Update, BookChangeAction::Delete]
+        );
+        assert!(
+            logs.iter()
+                .all(|log| log.performed_by() == Some(librarian_id))
+        );
+
+        // 定義されていない操作の種類は保存できない
+        let res = sqlx::query!(
+            r#"
+                INSERT INTO book_change_logs(book_id, action, performed_by)
+                VALUES ($1, 'archive', $2)
+            "#,
+            book_id as _,
+            librarian_id as _
+        )
+        .execute(&source)
+        .await;
+        assert!(res.is_err());
+
+        Ok(())
+    }
 }
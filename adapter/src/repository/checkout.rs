@@ -7,13 +7,14 @@ use kernel::{
     model::{
         checkout::{
             Checkout, CheckoutState,
-            event::{CreateCheckout, UpdateReturned},
+            event::{CreateCheckout, UpdateRenewed, UpdateReturned},
         },
         id::{BookId, CheckoutId, UserId},
     },
     repository::checkout::CheckoutRepository,
 };
 use shared::error::{AppError, AppResult};
+use sqlx::types::chrono::{DateTime, Utc};
 
 pub struct CheckoutRepositoryImpl<'t, 'm> {
     source: ConnectionSource<'t, 'm>,
@@ -58,7 +59,11 @@ impl<'t, 'm> CheckoutRepository for CheckoutRepositoryImpl<'t, 'm> {
                 SELECT
                     b.book_id,
                     c.checkout_id AS "checkout_id?: CheckoutId",
-                    c.user_id AS "user_id?: UserId"
+                    c.user_id AS "user_id?: UserId",
+                    (
+                        SELECT COUNT(*) FROM checkout_renewals AS r
+                        WHERE r.checkout_id = c.checkout_id
+                    ) AS "renewal_count!"
                 FROM books AS b
                     LEFT OUTER JOIN checkouts AS c USING(book_id)
                 WHERE book_id = $1;
@@ -84,6 +89,11 @@ impl<'t, 'm> CheckoutRepository for CheckoutRepositoryImpl<'t, 'm> {
                     rc.book_id,
                     rc.user_id,
                     rc.checked_out_at,
+                    rc.due_at,
+                    (
+                        SELECT COUNT(*) FROM checkout_renewals AS r
+                        WHERE r.checkout_id = rc.checkout_id
+                    ) AS "renewal_count!",
                     rc.returned_at,
                     b.title,
                     b.author,
@@ -109,6 +119,38 @@ impl<'t, 'm> CheckoutRepository for CheckoutRepositoryImpl<'t, 'm> {
         Ok(checkout_histories)
     }
 
+    async fn find_overdue_all(&self, now: DateTime<Utc>) -> AppResult<Vec<Checkout>> {
+        let mut conn = self.source.acquire().await?;
+        sqlx::query_as!(
+            CheckoutRow,
+            r#"
+                SELECT
+                    c.checkout_id,
+                    c.book_id,
+                    c.user_id,
+                    c.checked_out_at,
+                    c.due_at,
+                    (
+                        SELECT COUNT(*) FROM checkout_renewals AS r
+                        WHERE r.checkout_id = c.checkout_id
+                    ) AS "renewal_count!",
+                    b.title,
+                    b.author,
+                    b.isbn
+                FROM checkouts AS c
+                    INNER JOIN books AS b USING(book_id)
+                WHERE c.due_at < $1
+                ORDER BY c.due_at ASC
+                ;
+            "#,
+            now,
+        )
+        .fetch_all(&mut *conn)
+        .await
+        .map(|rows| rows.into_iter().map(Checkout::try_from).collect())
+        .map_err(AppError::SpecificOperationError)?
+    }
+
     async fn find_unreturned_all(&self) -> AppResult<Vec<Checkout>> {
         let mut conn = self.source.acquire().await?;
         sqlx::query_as!(
@@ -119,6 +161,11 @@ impl<'t, 'm> CheckoutRepository for CheckoutRepositoryImpl<'t, 'm> {
                     c.book_id,
                     c.user_id,
                     c.checked_out_at,
+                    c.due_at,
+                    (
+                        SELECT COUNT(*) FROM checkout_renewals AS r
+                        WHERE r.checkout_id = c.checkout_id
+                    ) AS "renewal_count!",
                     b.title,
                     b.author,
                     b.isbn
@@ -134,6 +181,40 @@ impl<'t, 'm> CheckoutRepository for CheckoutRepositoryImpl<'t, 'm> {
         .map_err(AppError::SpecificOperationError)?
     }
 
+    async fn find_unreturned_by_checkout_id(
+        &self,
+        checkout_id: CheckoutId,
+    ) -> AppResult<Option<Checkout>> {
+        let mut conn = self.source.acquire().await?;
+        sqlx::query_as!(
+            CheckoutRow,
+            r#"
+                SELECT
+                    c.checkout_id,
+                    c.book_id,
+                    c.user_id,
+                    c.checked_out_at,
+                    c.due_at,
+                    (
+                        SELECT COUNT(*) FROM checkout_renewals AS r
+                        WHERE r.checkout_id = c.checkout_id
+                    ) AS "renewal_count!",
+                    b.title,
+                    b.author,
+                    b.isbn
+                FROM checkouts AS c
+                    INNER JOIN books AS b USING(book_id)
+                WHERE c.checkout_id = $1
+            "#,
+            checkout_id as _,
+        )
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .map(Checkout::try_from)
+        .transpose()
+    }
+
     async fn find_unreturned_by_user_id(&self, user_id: UserId) -> AppResult<Vec<Checkout>> {
         let mut conn = self.source.acquire().await?;
         sqlx::query_as!(
@@ -144,6 +225,11 @@ impl<'t, 'm> CheckoutRepository for CheckoutRepositoryImpl<'t, 'm> {
                     c.book_id,
                     c.user_id,
                     c.checked_out_at,
+                    c.due_at,
+                    (
+                        SELECT COUNT(*) FROM checkout_renewals AS r
+                        WHERE r.checkout_id = c.checkout_id
+                    ) AS "renewal_count!",
                     b.title,
                     b.author,
                     b.isbn
@@ -161,20 +247,25 @@ impl<'t, 'm> CheckoutRepository for CheckoutRepositoryImpl<'t, 'm> {
         .map_err(AppError::SpecificOperationError)?
     }
 
-    async fn insert_checkout(&self, event: &CreateCheckout) -> AppResult<()> {
+    async fn insert_checkout(
+        &self,
+        event: &CreateCheckout,
+        due_at: DateTime<Utc>,
+    ) -> AppResult<()> {
         let mut conn = self.source.acquire().await?;
         let checkout_id = CheckoutId::new();
         let res = sqlx::query!(
             r#"
                 INSERT INTO checkouts
-                (checkout_id, book_id, user_id, checked_out_at)
-                VALUES ($1, $2, $3, $4)
+                (checkout_id, book_id, user_id, checked_out_at, due_at)
+                VALUES ($1, $2, $3, $4, $5)
                 ;
             "#,
             checkout_id as _,
             event.book_id as _,
             event.checked_out_by as _,
             event.checked_out_at,
+            due_at,
         )
         .execute(&mut *conn)
         .await
@@ -189,13 +280,44 @@ impl<'t, 'm> CheckoutRepository for CheckoutRepositoryImpl<'t, 'm> {
         Ok(())
     }
 
+    async fn insert_renewed_checkout(
+        &self,
+        event: &UpdateRenewed,
+        due_at: DateTime<Utc>,
+    ) -> AppResult<()> {
+        let mut conn = self.source.acquire().await?;
+        let res = sqlx::query!(
+            r#"
+                INSERT INTO checkout_renewals
+                (checkout_id, user_id, renewed_at, due_at)
+                VALUES ($1, $2, $3, $4)
+                ;
+            "#,
+            event.checkout_id as _,
+            event.renewed_by as _,
+            event.renewed_at,
+            due_at,
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        if res.rows_affected() < 1 {
+            return Err(AppError::NoRowsAffectedError(
+                "No renewal record has been created".into(),
+            ));
+        }
+
+        Ok(())
+    }
+
     async fn insert_returned_checkout(&self, event: &UpdateReturned) -> AppResult<()> {
         let mut conn = self.source.acquire().await?;
         let res = sqlx::query!(
             r#"
                 INSERT INTO returned_checkouts
-                (checkout_id, book_id, user_id, checked_out_at, returned_at)
-                SELECT checkout_id, book_id, user_id, checked_out_at, $2
+                (checkout_id, book_id, user_id, checked_out_at, due_at, returned_at)
+                SELECT checkout_id, book_id, user_id, checked_out_at, due_at, $2
                 FROM checkouts
                 WHERE checkout_id = $1
                 ;
@@ -215,6 +337,31 @@ impl<'t, 'm> CheckoutRepository for CheckoutRepositoryImpl<'t, 'm> {
 
         Ok(())
     }
+
+    async fn update_due_at(&self, checkout_id: CheckoutId, due_at: DateTime<Utc>) -> AppResult<()> {
+        let mut conn = self.source.acquire().await?;
+        let res = sqlx::query!(
+            r#"
+                UPDATE checkouts
+                SET due_at = $2
+                WHERE checkout_id = $1
+                ;
+            "#,
+            checkout_id as _,
+            due_at,
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        if res.rows_affected() < 1 {
+            return Err(AppError::NoRowsAffectedError(
+                "No checkout record has been updated".into(),
+            ));
+        }
+
+        Ok(())
+    }
 }
 
 impl<'t, 'm> CheckoutRepositoryImpl<'t, 'm> {
@@ -228,6 +375,11 @@ impl<'t, 'm> CheckoutRepositoryImpl<'t, 'm> {
                     c.book_id,
                     c.user_id,
                     c.checked_out_at,
+                    c.due_at,
+                    (
+                        SELECT COUNT(*) FROM checkout_renewals AS r
+                        WHERE r.checkout_id = c.checkout_id
+                    ) AS "renewal_count!",
                     b.title,
                     b.author,
                     b.isbn
@@ -250,12 +402,22 @@ impl<'t, 'm> CheckoutRepositoryImpl<'t, 'm> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{database::ConnectionPool, redis::RedisClient, unit_of_work::UnitOfWorkScopeImpl};
+    use crate::{
+        database::ConnectionPool, redis::RedisClient,
+        repository::loan_policy::LoanPolicyRepositoryImpl, unit_of_work::UnitOfWorkScopeImpl,
+    };
     use chrono::Utc;
-    use kernel::use_case::checkout::{CheckoutUseCase, CheckoutUseCaseImpl};
-    use shared::config::RedisConfig;
+    use kernel::{
+        model::{hold::event::CreateHold, loan_policy::event::UpdateLoanPolicy, role::Role},
+        repository::loan_policy::LoanPolicyRepository,
+        use_case::checkout::{CheckoutUseCase, CheckoutUseCaseImpl},
+    };
+    use shared::config::{CheckoutConfig, RedisConfig};
     use std::{str::FromStr, sync::Arc};
 
+    const LOAN_PERIOD_DAYS: i64 = 14;
+    const MAX_RENEWALS: i64 = 2;
+
     fn init_repo(
         pool: sqlx::PgPool,
     ) -> (
@@ -266,20 +428,28 @@ mod tests {
         BookId,
     ) {
         let repo = CheckoutRepositoryImpl::new(pool.clone());
-        let use_case = CheckoutUseCaseImpl::new(Arc::new(UnitOfWorkScopeImpl::new(
-            Arc::new(ConnectionPool::from(pool.clone())),
-            Arc::new(
-                RedisClient::new(&RedisConfig {
-                    host: std::env::var("REDIS_HOST").unwrap(),
-                    port: std::env::var("REDIS_PORT").unwrap().parse::<u16>().unwrap(),
-                })
-                .unwrap(),
-            ),
-            std::env::var("AUTH_TOKEN_TTL")
-                .unwrap()
-                .parse::<u64>()
-                .unwrap(),
-        )));
+        let use_case = CheckoutUseCaseImpl::new(
+            Arc::new(UnitOfWorkScopeImpl::new(
+                Arc::new(ConnectionPool::from(pool.clone())),
+                Arc::new(
+                    RedisClient::new(&RedisConfig {
+                        host: std::env::var("REDIS_HOST").unwrap(),
+                        port: std::env::var("REDIS_PORT").unwrap().parse::<u16>().unwrap(),
+                    })
+                    .unwrap(),
+                ),
+                std::env::var("AUTH_TOKEN_TTL")
+                    .unwrap()
+                    .parse::<u64>()
+                    .unwrap(),
+            )),
+            CheckoutConfig {
+                loan_period_days: LOAN_PERIOD_DAYS,
+                hold_pickup_days: 3,
+                max_renewals: MAX_RENEWALS,
+                max_loans: 5,
+            },
+        );
 
         let user_id1 = UserId::from_str("9582f9de-0fd1-4892-b20c-70139a7eb95b").unwrap();
         let user_id2 = UserId::from_str("050afe56-c3da-4448-8e4d-6f44007d2ca5").unwrap();
@@ -353,7 +523,7 @@ mod tests {
                     returned_at: Utc::now(),
                 })
                 .await;
-            assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+            assert!(matches!(res, Err(AppError::EntityNotFound(_))));
 
             let res = use_case
                 .return_book(UpdateReturned {
@@ -363,7 +533,7 @@ mod tests {
                     returned_at: Utc::now(),
                 })
                 .await;
-            assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+            assert!(matches!(res, Err(AppError::ReturnForbidden(_))));
 
             use_case
                 .return_book(UpdateReturned {
@@ -482,4 +652,273 @@ mod tests {
 
         Ok(())
     }
+
+    #[sqlx::test(fixtures("common", "checkout"))]
+    async fn test_checkout_due_at(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let (repo, use_case, user_id1, _, book_id1) = init_repo(pool);
+
+        let checked_out_at = Utc::now() - chrono::Duration::days(LOAN_PERIOD_DAYS + 1);
+        use_case
+            .checkout_book(CreateCheckout {
+                book_id: book_id1,
+                checked_out_by: user_id1,
+                checked_out_at,
+            })
+            .await?;
+
+        let co = repo.find_unreturned_by_book_id(book_id1).await?.unwrap();
+        let expected_due_at = checked_out_at + chrono::Duration::days(LOAN_PERIOD_DAYS);
+        assert!((co.due_at() - expected_due_at).num_milliseconds().abs() <= 1);
+        assert!(co.is_overdue(Utc::now()));
+
+        let res = repo.find_overdue_all(Utc::now()).await?;
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].id(), co.id());
+
+        let res = repo.find_overdue_all(checked_out_at).await?;
+        assert!(res.is_empty());
+
+        use_case
+            .return_book(UpdateReturned {
+                checkout_id: co.id(),
+                book_id: book_id1,
+                returned_by: user_id1,
+                returned_at: Utc::now(),
+            })
+            .await?;
+
+        let res = repo.find_overdue_all(Utc::now()).await?;
+        assert!(res.is_empty());
+
+        let res = repo.find_history_by_book_id(book_id1).await?;
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].due_at(), co.due_at());
+        assert!(res[0].is_overdue(Utc::now()));
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "checkout"))]
+    async fn test_checkout_renewal(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let (repo, use_case, user_id1, user_id2, book_id1) = init_repo(pool);
+
+        use_case
+            .checkout_book(CreateCheckout {
+                book_id: book_id1,
+                checked_out_by: user_id1,
+                checked_out_at: Utc::now(),
+            })
+            .await?;
+        let co = repo.find_unreturned_by_book_id(book_id1).await?.unwrap();
+        assert_eq!(co.renewal_count(), 0);
+
+        // 借りている本人以外は延長できない
+        let res = use_case
+            .renew_book(UpdateRenewed {
+                checkout_id: co.id(),
+                book_id: book_id1,
+                renewed_by: user_id2,
+                renewed_at: Utc::now(),
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        let renewed_at = Utc::now() + chrono::Duration::days(7);
+        use_case
+            .renew_book(UpdateRenewed {
+                checkout_id: co.id(),
+                book_id: book_id1,
+                renewed_by: user_id1,
+                renewed_at,
+            })
+            .await?;
+        let renewed = repo.find_unreturned_by_book_id(book_id1).await?.unwrap();
+        assert_eq!(renewed.id(), co.id());
+        assert_eq!(renewed.renewal_count(), 1);
+        let expected_due_at = renewed_at + chrono::Duration::days(LOAN_PERIOD_DAYS);
+        assert!(
+            (renewed.due_at() - expected_due_at)
+                .num_milliseconds()
+                .abs()
+                <= 1
+        );
+
+        use_case
+            .renew_book(UpdateRenewed {
+                checkout_id: co.id(),
+                book_id: book_id1,
+                renewed_by: user_id1,
+                renewed_at: Utc::now(),
+            })
+            .await?;
+
+        // 延長回数の上限に達すると延長できない
+        let res = use_case
+            .renew_book(UpdateRenewed {
+                checkout_id: co.id(),
+                book_id: book_id1,
+                renewed_by: user_id1,
+                renewed_at: Utc::now(),
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        use_case
+            .return_book(UpdateReturned {
+                checkout_id: co.id(),
+                book_id: book_id1,
+                returned_by: user_id1,
+                returned_at: Utc::now(),
+            })
+            .await?;
+        let res = repo.find_history_by_book_id(book_id1).await?;
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].renewal_count(), MAX_RENEWALS);
+
+        // 予約がある場合は延長できない
+        use_case
+            .checkout_book(CreateCheckout {
+                book_id: book_id1,
+                checked_out_by: user_id1,
+                checked_out_at: Utc::now(),
+            })
+            .await?;
+        let co = repo.find_unreturned_by_book_id(book_id1).await?.unwrap();
+        assert_eq!(co.renewal_count(), 0);
+        use_case
+            .place_hold(CreateHold {
+                book_id: book_id1,
+                held_by: user_id2,
+                created_at: Utc::now(),
+            })
+            .await?;
+        let res = use_case
+            .renew_book(UpdateRenewed {
+                checkout_id: co.id(),
+                book_id: book_id1,
+                renewed_by: user_id1,
+                renewed_at: Utc::now(),
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "checkout"))]
+    async fn test_checkout_loan_policy(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let loan_policy_repo = LoanPolicyRepositoryImpl::new(pool.clone());
+        let (repo, use_case, user_id1, _, book_id1) = init_repo(pool);
+
+        // 貸出上限に達している場合は借りられない
+        loan_policy_repo
+            .update(&UpdateLoanPolicy {
+                role: Role::User,
+                max_loans: 0,
+                loan_period_days: 7,
+                max_renewals: 0,
+            })
+            .await?;
+        let res = use_case
+            .checkout_book(CreateCheckout {
+                book_id: book_id1,
+                checked_out_by: user_id1,
+                checked_out_at: Utc::now(),
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        loan_policy_repo
+            .update(&UpdateLoanPolicy {
+                role: Role::User,
+                max_loans: 1,
+                loan_period_days: 7,
+                max_renewals: 0,
+            })
+            .await?;
+        let checked_out_at = Utc::now();
+        use_case
+            .checkout_book(CreateCheckout {
+                book_id: book_id1,
+                checked_out_by: user_id1,
+                checked_out_at,
+            })
+            .await?;
+        let co = repo.find_unreturned_by_book_id(book_id1).await?.unwrap();
+        let expected_due_at = checked_out_at + chrono::Duration::days(7);
+        assert!((co.due_at() - expected_due_at).num_milliseconds().abs() <= 1);
+
+        let res = use_case
+            .renew_book(UpdateRenewed {
+                checkout_id: co.id(),
+                book_id: book_id1,
+                renewed_by: user_id1,
+                renewed_at: Utc::now(),
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        let policies = use_case.show_loan_policies().await?;
+        assert_eq!(policies.len(), 3);
+        let admin = policies.iter().find(|p| p.role() == &Role::Admin).unwrap();
+        assert_eq!(admin.max_loans(), 5);
+        assert_eq!(admin.loan_period_days(), LOAN_PERIOD_DAYS);
+        let user = policies.iter().find(|p| p.role() == &Role::User).unwrap();
+        assert_eq!(user.max_loans(), 1);
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "book", "checkout"))]
+    async fn test_return_identity(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let (repo, use_case, user_id1, user_id2, book_id1) = init_repo(pool);
+        let owner_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c").unwrap();
+        let book_id2 = BookId::from_str("f397b83a-dd2a-4a01-9e77-db1eea7de5b6").unwrap();
+
+        use_case
+            .checkout_book(CreateCheckout {
+                book_id: book_id2,
+                checked_out_by: user_id1,
+                checked_out_at: Utc::now(),
+            })
+            .await?;
+        let co = repo.find_unreturned_by_book_id(book_id2).await?.unwrap();
+
+        // パスの書籍IDと貸出の書籍が一致しない場合は返却できない
+        let res = use_case
+            .return_book(UpdateReturned {
+                checkout_id: co.id(),
+                book_id: book_id1,
+                returned_by: user_id1,
+                returned_at: Utc::now(),
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::CheckoutBookMismatch(_))));
+
+        let res = use_case
+            .return_book(UpdateReturned {
+                checkout_id: co.id(),
+                book_id: book_id2,
+                returned_by: user_id2,
+                returned_at: Utc::now(),
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::ReturnForbidden(_))));
+
+        // 書籍の所有者は借り手に代わって返却できる
+        use_case
+            .return_book(UpdateReturned {
+                checkout_id: co.id(),
+                book_id: book_id2,
+                returned_by: owner_id,
+                returned_at: Utc::now(),
+            })
+            .await?;
+
+        let res = repo.find_history_by_book_id(book_id2).await?;
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].checked_out_by(), user_id1);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,551 @@
+use crate::database::{ConnectionSource, model::hold::HoldRow};
+use async_trait::async_trait;
+use kernel::{
+    model::{
+        hold::{Hold, event::CreateHold},
+        id::{BookId, HoldId, UserId},
+    },
+    repository::hold::HoldRepository,
+};
+use shared::error::{AppError, AppResult};
+use sqlx::types::chrono::{DateTime, Utc};
+
+pub struct HoldRepositoryImpl<'t, 'm> {
+    source: ConnectionSource<'t, 'm>,
+}
+
+impl<'t, 'm> HoldRepositoryImpl<'t, 'm> {
+    pub fn new(source: impl Into<ConnectionSource<'t, 'm>>) -> Self {
+        Self {
+            source: source.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl<'t, 'm> HoldRepository for HoldRepositoryImpl<'t, 'm> {
+    async fn delete(&self, hold_id: HoldId) -> AppResult<()> {
+        let mut conn = self.source.acquire().await?;
+        let res = sqlx::query!(
+            r#"
+                DELETE FROM holds WHERE hold_id = $1;
+            "#,
+            hold_id as _,
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        if res.rows_affected() < 1 {
+            return Err(AppError::NoRowsAffectedError(
+                "No hold record has been deleted".into(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    async fn delete_by_user_id(&self, user_id: UserId) -> AppResult<()> {
+        let mut conn = self.source.acquire().await?;
+        sqlx::query!(
+            r#"
+                DELETE FROM holds WHERE user_id = $1;
+            "#,
+            user_id as _,
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        Ok(())
+    }
+
+    async fn delete_expired(&self, book_id: BookId, now: DateTime<Utc>) -> AppResult<()> {
+        let mut conn = self.source.acquire().await?;
+        sqlx::query!(
+            r#"
+                DELETE FROM holds WHERE book_id = $1 AND expires_at <= $2;
+            "#,
+            book_id as _,
+            now,
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        Ok(())
+    }
+
+    async fn find_by_book_id(&self, book_id: BookId) -> AppResult<Vec<Hold>> {
+        let mut conn = self.source.acquire().await?;
+        sqlx::query_as!(
+            HoldRow,
+            r#"
+                SELECT
+                    h.hold_id,
+                    h.book_id,
+                    h.user_id,
+                    h.position AS "position!",
+                    h.created_at,
+                    h.ready_at,
+                    h.expires_at,
+                    b.title,
+                    b.author,
+                    b.isbn
+                FROM (
+                    SELECT
+                        ho.*,
+                        ROW_NUMBER() OVER (
+                            PARTITION BY ho.book_id ORDER BY ho.created_at, ho.hold_id
+                        ) AS position
+                    FROM holds AS ho
+                        INNER JOIN users AS u USING(user_id)
+                    WHERE (ho.expires_at IS NULL OR ho.expires_at > CURRENT_TIMESTAMP)
+                    AND u.deactivated_at IS NULL
+                ) AS h
+                    INNER JOIN books AS b USING(book_id)
+                WHERE h.book_id = $1
+                ORDER BY h.position ASC
+                ;
+            "#,
+            book_id as _,
+        )
+        .fetch_all(&mut *conn)
+        .await
+        .map(|rows| rows.into_iter().map(Hold::try_from).collect())
+        .map_err(AppError::SpecificOperationError)?
+    }
+
+    async fn find_by_user_id(&self, user_id: UserId) -> AppResult<Vec<Hold>> {
+        let mut conn = self.source.acquire().await?;
+        sqlx::query_as!(
+            HoldRow,
+            r#"
+                SELECT
+                    h.hold_id,
+                    h.book_id,
+                    h.user_id,
+                    h.position AS "position!",
+                    h.created_at,
+                    h.ready_at,
+                    h.expires_at,
+                    b.title,
+                    b.author,
+                    b.isbn
+                FROM (
+                    SELECT
+                        ho.*,
+                        ROW_NUMBER() OVER (
+                            PARTITION BY ho.book_id ORDER BY ho.created_at, ho.hold_id
+                        ) AS position
+                    FROM holds AS ho
+                        INNER JOIN users AS u USING(user_id)
+                    WHERE (ho.expires_at IS NULL OR ho.expires_at > CURRENT_TIMESTAMP)
+                    AND u.deactivated_at IS NULL
+                ) AS h
+                    INNER JOIN books AS b USING(book_id)
+                WHERE h.user_id = $1
+                ORDER BY h.created_at ASC
+                ;
+            "#,
+            user_id as _,
+        )
+        .fetch_all(&mut *conn)
+        .await
+        .map(|rows| rows.into_iter().map(Hold::try_from).collect())
+        .map_err(AppError::SpecificOperationError)?
+    }
+
+    async fn insert(&self, event: &CreateHold) -> AppResult<()> {
+        let mut conn = self.source.acquire().await?;
+        let hold_id = HoldId::new();
+        let res = sqlx::query!(
+            r#"
+                INSERT INTO holds
+                (hold_id, book_id, user_id, created_at)
+                VALUES ($1, $2, $3, $4)
+                ;
+            "#,
+            hold_id as _,
+            event.book_id as _,
+            event.held_by as _,
+            event.created_at,
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        if res.rows_affected() < 1 {
+            return Err(AppError::NoRowsAffectedError(
+                "No hold record has been created".into(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    async fn update_ready(
+        &self,
+        hold_id: HoldId,
+        ready_at: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+    ) -> AppResult<()> {
+        let mut conn = self.source.acquire().await?;
+        let res = sqlx::query!(
+            r#"
+                UPDATE holds
+                SET ready_at = $2, expires_at = $3
+                WHERE hold_id = $1
+                ;
+            "#,
+            hold_id as _,
+            ready_at,
+            expires_at,
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        if res.rows_affected() < 1 {
+            return Err(AppError::NoRowsAffectedError(
+                "No hold record has been updated".into(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        database::ConnectionPool,
+        jwt::JwtKeys,
+        redis::RedisClient,
+        repository::{checkout::CheckoutRepositoryImpl, user::UserRepositoryImpl},
+        unit_of_work::UnitOfWorkScopeImpl,
+    };
+    use chrono::Utc;
+    use kernel::{
+        mail::MockMailSender,
+        model::{
+            checkout::event::{CreateCheckout, UpdateReturned},
+            hold::event::DeleteHold,
+            user::event::DeactivateUser,
+        },
+        password::MockPasswordHasher,
+        repository::{checkout::CheckoutRepository, user::UserRepository},
+        use_case::{
+            checkout::{CheckoutUseCase, CheckoutUseCaseImpl},
+            user::{UserUseCase, UserUseCaseImpl},
+        },
+    };
+    use shared::config::{CheckoutConfig, JwtAlgorithm, JwtConfig, RedisConfig};
+    use std::{str::FromStr, sync::Arc};
+
+    const HOLD_PICKUP_DAYS: i64 = 3;
+
+    fn init_use_case(pool: sqlx::PgPool) -> CheckoutUseCaseImpl {
+        CheckoutUseCaseImpl::new(
+            Arc::new(UnitOfWorkScopeImpl::new(
+                Arc::new(ConnectionPool::from(pool)),
+                Arc::new(
+                    RedisClient::new(&RedisConfig {
+                        host: std::env::var("REDIS_HOST").unwrap(),
+                        port: std::env::var("REDIS_PORT").unwrap().parse::<u16>().unwrap(),
+                    })
+                    .unwrap(),
+                ),
+                std::env::var("AUTH_TOKEN_TTL")
+                    .unwrap()
+                    .parse::<u64>()
+                    .unwrap(),
+            )),
+            CheckoutConfig {
+                loan_period_days: 14,
+                hold_pickup_days: HOLD_PICKUP_DAYS,
+                max_renewals: 2,
+                max_loans: 5,
+            },
+        )
+    }
+
+    #[sqlx::test(fixtures("common", "checkout"))]
+    async fn test_hold_queue(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = HoldRepositoryImpl::new(pool.clone());
+        let checkout_repo = CheckoutRepositoryImpl::new(pool.clone());
+        let use_case = init_use_case(pool);
+
+        let user_id1 = UserId::from_str("9582f9de-0fd1-4892-b20c-70139a7eb95b").unwrap();
+        let user_id2 = UserId::from_str("050afe56-c3da-4448-8e4d-6f44007d2ca5").unwrap();
+        let user_id3 = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c").unwrap();
+        let book_id1 = BookId::from_str("9890736e-a4e4-461a-a77d-eac3517ef11b").unwrap();
+
+        // 貸出可能な書籍は予約できない
+        let res = use_case
+            .place_hold(CreateHold {
+                book_id: book_id1,
+                held_by: user_id2,
+                created_at: Utc::now(),
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        use_case
+            .checkout_book(CreateCheckout {
+                book_id: book_id1,
+                checked_out_by: user_id1,
+                checked_out_at: Utc::now(),
+            })
+            .await?;
+
+        // 借りている本人は予約できない
+        let res = use_case
+            .place_hold(CreateHold {
+                book_id: book_id1,
+                held_by: user_id1,
+                created_at: Utc::now(),
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        for held_by in [user_id2, user_id3] {
+            use_case
+                .place_hold(CreateHold {
+                    book_id: book_id1,
+                    held_by,
+                    created_at: Utc::now(),
+                })
+                .await?;
+        }
+
+        let res = use_case
+            .place_hold(CreateHold {
+                book_id: book_id1,
+                held_by: user_id2,
+                created_at: Utc::now(),
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        let holds = repo.find_by_book_id(book_id1).await?;
+        assert_eq!(holds.len(), 2);
+        assert_eq!(holds[0].held_by(), user_id2);
+        assert_eq!(holds[0].position(), 1);
+        assert_eq!(holds[1].held_by(), user_id3);
+        assert_eq!(holds[1].position(), 2);
+        assert!(holds.iter().all(|h| !h.is_ready()));
+
+        let res = repo.find_by_user_id(user_id3).await?;
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].position(), 2);
+
+        // 返却すると先頭の予約が取り置き状態になる
+        let co = checkout_repo.find_unreturned_by_user_id(user_id1).await?;
+        let returned_at = Utc::now();
+        use_case
+            .return_book(UpdateReturned {
+                checkout_id: co[0].id(),
+                book_id: book_id1,
+                returned_by: user_id1,
+                returned_at,
+            })
+            .await?;
+
+        let holds = repo.find_by_book_id(book_id1).await?;
+        assert!(holds[0].is_ready());
+        assert!(!holds[1].is_ready());
+        let expected_expires_at = returned_at + chrono::Duration::days(HOLD_PICKUP_DAYS);
+        let expires_at = holds[0].expires_at().unwrap();
+        assert!((expires_at - expected_expires_at).num_milliseconds().abs() <= 1);
+
+        // 取り置き中の書籍は予約者以外は借りられない
+        let res = use_case
+            .checkout_book(CreateCheckout {
+                book_id: book_id1,
+                checked_out_by: user_id3,
+                checked_out_at: Utc::now(),
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+
+        // 予約者以外は予約を取り消せない
+        let res = use_case
+            .cancel_hold(DeleteHold {
+                hold_id: holds[0].id(),
+                book_id: book_id1,
+                requested_user: user_id3,
+                requested_at: Utc::now(),
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::ForbiddenOperation)));
+
+        use_case
+            .checkout_book(CreateCheckout {
+                book_id: book_id1,
+                checked_out_by: user_id2,
+                checked_out_at: Utc::now(),
+            })
+            .await?;
+
+        let holds = repo.find_by_book_id(book_id1).await?;
+        assert_eq!(holds.len(), 1);
+        assert_eq!(holds[0].held_by(), user_id3);
+        assert_eq!(holds[0].position(), 1);
+        assert!(!holds[0].is_ready());
+
+        use_case
+            .cancel_hold(DeleteHold {
+                hold_id: holds[0].id(),
+                book_id: book_id1,
+                requested_user: user_id3,
+                requested_at: Utc::now(),
+            })
+            .await?;
+        assert!(repo.find_by_book_id(book_id1).await?.is_empty());
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "checkout"))]
+    async fn test_hold_expired(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = HoldRepositoryImpl::new(pool.clone());
+        let checkout_repo = CheckoutRepositoryImpl::new(pool.clone());
+        let use_case = init_use_case(pool);
+
+        let user_id1 = UserId::from_str("9582f9de-0fd1-4892-b20c-70139a7eb95b").unwrap();
+        let user_id2 = UserId::from_str("050afe56-c3da-4448-8e4d-6f44007d2ca5").unwrap();
+        let user_id3 = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c").unwrap();
+        let book_id1 = BookId::from_str("9890736e-a4e4-461a-a77d-eac3517ef11b").unwrap();
+
+        use_case
+            .checkout_book(CreateCheckout {
+                book_id: book_id1,
+                checked_out_by: user_id1,
+                checked_out_at: Utc::now(),
+            })
+            .await?;
+        for held_by in [user_id2, user_id3] {
+            use_case
+                .place_hold(CreateHold {
+                    book_id: book_id1,
+                    held_by,
+                    created_at: Utc::now(),
+                })
+                .await?;
+        }
+
+        let co = checkout_repo.find_unreturned_by_user_id(user_id1).await?;
+        use_case
+            .return_book(UpdateReturned {
+                checkout_id: co[0].id(),
+                book_id: book_id1,
+                returned_by: user_id1,
+                returned_at: Utc::now(),
+            })
+            .await?;
+
+        // 受け取り期限を過ぎると次の予約者に取り置きが移る
+        let after_pickup = Utc::now() + chrono::Duration::days(HOLD_PICKUP_DAYS + 1);
+        use_case
+            .checkout_book(CreateCheckout {
+                book_id: book_id1,
+                checked_out_by: user_id3,
+                checked_out_at: after_pickup,
+            })
+            .await?;
+
+        assert!(repo.find_by_book_id(book_id1).await?.is_empty());
+        let co = checkout_repo.find_unreturned_by_user_id(user_id3).await?;
+        assert_eq!(co.len(), 1);
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common", "checkout"))]
+    async fn test_hold_of_deactivated_user(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = HoldRepositoryImpl::new(pool.clone());
+        let user_repo = UserRepositoryImpl::new(pool.clone());
+        let checkout_repo = CheckoutRepositoryImpl::new(pool.clone());
+        let use_case = init_use_case(pool.clone());
+        // セッションの失効も PostgreSQL で完結するよう、JWT のバックエンドを使う
+        let user_use_case = UserUseCaseImpl::new(
+            Arc::new(UnitOfWorkScopeImpl::new_with_jwt_keys(
+                Arc::new(ConnectionPool::from(pool.clone())),
+                Arc::new(JwtKeys::new(&JwtConfig {
+                    algorithm: JwtAlgorithm::HS256,
+                    signing_key: "secret".into(),
+                    verifying_key: "secret".into(),
+                })?),
+                3600,
+            )),
+            Arc::new(MockPasswordHasher::new()),
+            Arc::new(MockMailSender::new()),
+            3600,
+        );
+
+        let user_id1 = UserId::from_str("9582f9de-0fd1-4892-b20c-70139a7eb95b").unwrap();
+        let user_id2 = UserId::from_str("050afe56-c3da-4448-8e4d-6f44007d2ca5").unwrap();
+        let user_id3 = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c").unwrap();
+        let book_id1 = BookId::from_str("9890736e-a4e4-461a-a77d-eac3517ef11b").unwrap();
+
+        use_case
+            .checkout_book(CreateCheckout {
+                book_id: book_id1,
+                checked_out_by: user_id1,
+                checked_out_at: Utc::now(),
+            })
+            .await?;
+        for held_by in [user_id2, user_id3] {
+            use_case
+                .place_hold(CreateHold {
+                    book_id: book_id1,
+                    held_by,
+                    created_at: Utc::now(),
+                })
+                .await?;
+        }
+
+        // 無効化すると予約も取り消される
+        user_use_case
+            .deactivate_user(DeactivateUser { user_id: user_id2 })
+            .await?;
+        let remaining = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!" FROM holds WHERE user_id = $1"#,
+            user_id2 as _
+        )
+        .fetch_one(&pool)
+        .await?;
+        assert_eq!(remaining, 0);
+
+        // 無効化されたユーザーの予約が残っていても、順番待ちには含めない
+        repo.insert(&CreateHold {
+            book_id: book_id1,
+            held_by: user_id2,
+            created_at: Utc::now() - chrono::Duration::days(1),
+        })
+        .await?;
+        assert!(user_repo.find_current_user(user_id2).await?.is_none());
+        let holds = repo.find_by_book_id(book_id1).await?;
+        assert_eq!(holds.len(), 1);
+        assert_eq!(holds[0].held_by(), user_id3);
+        assert_eq!(holds[0].position(), 1);
+        assert!(repo.find_by_user_id(user_id2).await?.is_empty());
+
+        let co = checkout_repo.find_unreturned_by_user_id(user_id1).await?;
+        use_case
+            .return_book(UpdateReturned {
+                checkout_id: co[0].id(),
+                book_id: book_id1,
+                returned_by: user_id1,
+                returned_at: Utc::now(),
+            })
+            .await?;
+        let holds = repo.find_by_book_id(book_id1).await?;
+        assert_eq!(holds[0].held_by(), user_id3);
+        assert!(holds[0].is_ready());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,1145 @@
+use crate::{
+    database::{
+        ConnectionSource,
+        model::auth::{AuthSessionRow, RefreshTokenRow, login_attempt_key},
+    },
+    jwt::{Claims, JwtKeys, PendingRevocations},
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use kernel::{
+    model::{
+        auth::{
+            AccessToken, EmailVerificationToken, LoginAttemptKey, MfaChallengeToken,
+            PasswordResetToken, PendingEmailChange, RefreshToken, RefreshTokenEntry,
+            event::{
+                CreateEmailVerificationToken, CreateMfaChallenge, CreatePasswordResetToken,
+                CreateRefreshToken, CreateToken,
+            },
+        },
+        id::UserId,
+        oidc::OidcAuthorizationRequest,
+        session::Session,
+    },
+    repository::auth::AuthRepository,
+};
+use sha2::{Digest, Sha256};
+use shared::{
+    error::{AppError, AppResult},
+    i18n::{Message, MessageKey},
+};
+use std::sync::Arc;
+
+/// 署名付きの JWT をアクセストークンとして発行する AuthRepository の実装。
+/// トークンの検証は署名とメモリ上の失効リストの照合だけで行い、Redis を必要としない。
+pub struct JwtAuthRepositoryImpl<'t, 'm> {
+    source: ConnectionSource<'t, 'm>,
+    keys: Arc<JwtKeys>,
+    ttl: u64,
+    // トランザクションの中で使う場合に、コミットまで失効を預けておく先
+    pending: Option<&'m PendingRevocations>,
+}
+
+impl<'t, 'm> JwtAuthRepositoryImpl<'t, 'm> {
+    pub fn new(source: impl Into<ConnectionSource<'t, 'm>>, keys: Arc<JwtKeys>, ttl: u64) -> Self {
+        Self {
+            source: source.into(),
+            keys,
+            ttl,
+            pending: None,
+        }
+    }
+
+    pub fn with_pending(mut self, pending: &'m PendingRevocations) -> Self {
+        self.pending = Some(pending);
+        self
+    }
+
+    // 条件に合うセッションを削除し、発行済みのトークンを失効リストに載せる。
+    // 削除したセッションの系列のリフレッシュトークンも使えなくする。
+    // メモリ上の失効リストには、次の同期を待たずに反映する。
+    // トランザクションの中ではコミットするまで反映しない
+    async fn revoke_sessions(
+        &self,
+        user_id: UserId,
+        session_id: Option<&str>,
+        family_id: Option<&str>,
+    ) -> AppResult<usize> {
+        let mut conn = self.source.acquire().await?;
+        let removed = sqlx::query!(
+            r#"
+                WITH removed AS (
+                    DELETE FROM auth_sessions
+                    WHERE user_id = $1
+                    AND ($2::VARCHAR IS NULL OR session_id = $2)
+                    AND ($3::VARCHAR IS NULL OR family_id = $3)
+                    RETURNING session_id, user_id, family_id, expires_at
+                ),
+                revoked AS (
+                    INSERT INTO revoked_access_tokens (session_id, user_id, expires_at)
+                    SELECT session_id, user_id, expires_at FROM removed
+                    WHERE expires_at > CURRENT_TIMESTAMP(3)
+                    ON CONFLICT DO NOTHING
+                ),
+                families AS (
+                    DELETE FROM refresh_token_families
+                    WHERE family_id IN (SELECT family_id FROM removed)
+                )
+                SELECT session_id, expires_at FROM removed
+            "#,
+            user_id as _,
+            session_id,
+            family_id,
+        )
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        let count = removed.len();
+        for row in removed {
+            match self.pending {
+                Some(pending) => pending.push(row.session_id, row.expires_at),
+                None => self.keys.revoked.insert(row.session_id, row.expires_at),
+            }
+        }
+        Ok(count)
+    }
+}
+
+#[async_trait]
+impl<'t, 'm> AuthRepository for JwtAuthRepositoryImpl<'t, 'm> {
+    async fn create_refresh_token(
+        &self,
+        event: CreateRefreshToken,
+        ttl: u64,
+    ) -> AppResult<RefreshToken> {
+        let mut conn = self.source.acquire().await?;
+        let expires_at = Utc::now() + Duration::seconds(ttl as i64);
+        sqlx::query!(
+            r#"
+                DELETE FROM refresh_tokens
+                WHERE user_id = $1
+                AND expires_at <= CURRENT_TIMESTAMP(3)
+            "#,
+            event.user_id as _,
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        sqlx::query!(
+            r#"
+                INSERT INTO refresh_token_families (family_id, user_id, expires_at)
+                VALUES ($1, $2, $3)
+                ON CONFLICT (family_id) DO UPDATE SET expires_at = EXCLUDED.expires_at
+            "#,
+            event.family_id,
+            event.user_id as _,
+            expires_at,
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        sqlx::query!(
+            r#"
+                INSERT INTO refresh_tokens (token_hash, user_id, family_id, expires_at)
+                VALUES ($1, $2, $3, $4)
+            "#,
+            hash_token(&event.refresh_token),
+            event.user_id as _,
+            event.family_id,
+            expires_at,
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(RefreshToken(event.refresh_token))
+    }
+
+    async fn create_token(&self, event: CreateToken) -> AppResult<AccessToken> {
+        let mut conn = self.source.acquire().await?;
+        let now = Utc::now();
+        let expires_at = now + Duration::seconds(self.ttl as i64);
+        sqlx::query!(
+            r#"
+                DELETE FROM auth_sessions
+                WHERE user_id = $1
+                AND expires_at <= CURRENT_TIMESTAMP(3)
+            "#,
+            event.user_id as _,
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        sqlx::query!(
+            r#"
+                INSERT INTO auth_sessions
+                (session_id, user_id, family_id, device, ip_address, created_at, expires_at)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+            event.access_token,
+            event.user_id as _,
+            event.family_id,
+            event.client.device,
+            event.client.ip_address,
+            now,
+            expires_at,
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        // CreateToken で生成した乱数はトークンの ID として使う
+        let claims = Claims {
+            sub: event.user_id,
+            jti: event.access_token,
+            fam: event.family_id,
+            iat: now.timestamp(),
+            exp: expires_at.timestamp(),
+        };
+        self.keys.encode(&claims).map(AccessToken)
+    }
+
+    async fn delete_all_sessions(&self, user_id: UserId) -> AppResult<()> {
+        self.revoke_sessions(user_id, None, None).await?;
+        let mut conn = self.source.acquire().await?;
+        sqlx::query!(
+            r#"
+                DELETE FROM refresh_token_families
+                WHERE user_id = $1
+            "#,
+            user_id as _,
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(())
+    }
+
+    async fn delete_session(&self, user_id: UserId, session_id: &str) -> AppResult<()> {
+        if self
+            .revoke_sessions(user_id, Some(session_id), None)
+            .await?
+            < 1
+        {
+            return Err(AppError::EntityNotFound(
+                Message::new(MessageKey::SessionNotFound).arg(session_id),
+            ));
+        }
+        Ok(())
+    }
+
+    async fn delete_token(&self, access_token: AccessToken) -> AppResult<()> {
+        let Some(claims) = self.keys.decode(&access_token.0) else {
+            return Ok(());
+        };
+        self.revoke_sessions(claims.sub, Some(&claims.jti), None)
+            .await?;
+        Ok(())
+    }
+
+    // 署名済みのトークンは有効期限を延長できないため、リフレッシュトークンで再発行する
+    async fn extend_token(&self, _access_token: &AccessToken) -> AppResult<()> {
+        Ok(())
+    }
+
+    async fn find_refresh_token(
+        &self,
+        refresh_token: &RefreshToken,
+    ) -> AppResult<Option<RefreshTokenEntry>> {
+        let mut conn = self.source.acquire().await?;
+        let row = sqlx::query_as!(
+            RefreshTokenRow,
+            r#"
+                SELECT
+                    rt.user_id,
+                    rt.family_id,
+                    rt.rotated
+                FROM refresh_tokens AS rt
+                INNER JOIN refresh_token_families AS f ON f.family_id = rt.family_id
+                WHERE rt.token_hash = $1
+                AND rt.expires_at > CURRENT_TIMESTAMP(3)
+                AND f.expires_at > CURRENT_TIMESTAMP(3)
+            "#,
+            hash_token(&refresh_token.0),
+        )
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(row.map(RefreshTokenEntry::from))
+    }
+
+    async fn find_sessions(
+        &self,
+        user_id: UserId,
+        current: &AccessToken,
+    ) -> AppResult<Vec<Session>> {
+        let current_id = self.keys.decode(&current.0).map(|claims| claims.jti);
+        let mut conn = self.source.acquire().await?;
+        let rows = sqlx::query_as!(
+            AuthSessionRow,
+            r#"
+                SELECT
+                    session_id,
+                    device,
+                    ip_address,
+                    created_at
+                FROM auth_sessions
+                WHERE user_id = $1
+                AND expires_at > CURRENT_TIMESTAMP(3)
+                ORDER BY created_at DESC
+            "#,
+            user_id as _,
+        )
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let is_current = current_id.as_deref() == Some(row.session_id.as_str());
+                row.into_session(is_current)
+            })
+            .collect())
+    }
+
+    async fn fetch_user_id_from_token(
+        &self,
+        access_token: &AccessToken,
+    ) -> AppResult<Option<UserId>> {
+        let Some(claims) = self.keys.decode(&access_token.0) else {
+            return Ok(None);
+        };
+        // 失効リストはメモリ上の写しと照合し、一定の間隔でだけデータベースから読み直す
+        if self.keys.revoked.needs_sync() {
+            let mut conn = self.source.acquire().await?;
+            let rows = sqlx::query!(
+                r#"
+                    SELECT session_id, expires_at FROM revoked_access_tokens
+                    WHERE expires_at > CURRENT_TIMESTAMP(3)
+                "#,
+            )
+            .fetch_all(&mut *conn)
+            .await
+            .map_err(AppError::SpecificOperationError)?;
+            self.keys
+                .revoked
+                .sync(rows.into_iter().map(|row| (row.session_id, row.expires_at)));
+        }
+        let revoked = self.keys.revoked.contains(&claims.jti);
+        Ok((!revoked).then_some(claims.sub))
+    }
+
+    async fn mark_refresh_token_rotated(&self, refresh_token: &RefreshToken) -> AppResult<()> {
+        let mut conn = self.source.acquire().await?;
+        sqlx::query!(
+            r#"
+                UPDATE refresh_tokens
+                SET rotated = TRUE
+                WHERE token_hash = $1
+            "#,
+            hash_token(&refresh_token.0),
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(())
+    }
+
+    async fn revoke_refresh_family(&self, user_id: UserId, family_id: &str) -> AppResult<()> {
+        self.revoke_sessions(user_id, None, Some(family_id)).await?;
+        let mut conn = self.source.acquire().await?;
+        sqlx::query!(
+            r#"
+                DELETE FROM refresh_token_families
+                WHERE family_id = $1
+                AND user_id = $2
+            "#,
+            family_id,
+            user_id as _,
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(())
+    }
+
+    async fn find_login_lockout(&self, key: &LoginAttemptKey) -> AppResult<Option<DateTime<Utc>>> {
+        let mut conn = self.source.acquire().await?;
+        let locked_until = sqlx::query_scalar!(
+            r#"
+                SELECT locked_until AS "locked_until!" FROM login_failures
+                WHERE attempt_key = $1
+                AND locked_until > CURRENT_TIMESTAMP(3)
+            "#,
+            login_attempt_key(key),
+        )
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(locked_until)
+    }
+
+    async fn record_login_failure(&self, key: &LoginAttemptKey, window: u64) -> AppResult<u32> {
+        let mut conn = self.source.acquire().await?;
+        let failures = sqlx::query_scalar!(
+            r#"
+                INSERT INTO login_failures (attempt_key, failure_count, expires_at)
+                VALUES ($1, 1, $2)
+                ON CONFLICT (attempt_key) DO UPDATE SET
+                    failure_count = CASE
+                        WHEN login_failures.expires_at > CURRENT_TIMESTAMP(3)
+                        THEN login_failures.failure_count + 1
+                        ELSE 1
+                    END,
+                    locked_until = CASE
+                        WHEN login_failures.expires_at > CURRENT_TIMESTAMP(3)
+                        THEN login_failures.locked_until
+                    END,
+                    expires_at = EXCLUDED.expires_at
+                RETURNING failure_count
+            "#,
+            login_attempt_key(key),
+            Utc::now() + Duration::seconds(window as i64),
+        )
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(failures.try_into().unwrap_or(u32::MAX))
+    }
+
+    async fn lock_login(&self, key: &LoginAttemptKey, until: DateTime<Utc>) -> AppResult<()> {
+        let mut conn = self.source.acquire().await?;
+        sqlx::query!(
+            r#"
+                UPDATE login_failures
+                SET locked_until = $2
+                WHERE attempt_key = $1
+            "#,
+            login_attempt_key(key),
+            until,
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(())
+    }
+
+    async fn clear_login_failures(&self, key: &LoginAttemptKey) -> AppResult<()> {
+        let mut conn = self.source.acquire().await?;
+        if let LoginAttemptKey::Email(email) = key {
+            sqlx::query!(
+                r#"
+                    DELETE FROM login_failure_ips
+                    WHERE email = $1
+                "#,
+                email,
+            )
+            .execute(&mut *conn)
+            .await
+            .map_err(AppError::SpecificOperationError)?;
+        }
+        sqlx::query!(
+            r#"
+                DELETE FROM login_failures
+                WHERE attempt_key = $1
+            "#,
+            login_attempt_key(key),
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(())
+    }
+
+    async fn record_login_failure_ip(
+        &self,
+        email: &str,
+        ip_address: &str,
+        window: u64,
+    ) -> AppResult<()> {
+        let mut conn = self.source.acquire().await?;
+        sqlx::query!(
+            r#"
+                INSERT INTO login_failure_ips (email, ip_address, expires_at)
+                VALUES ($1, $2, $3)
+                ON CONFLICT (email, ip_address) DO UPDATE SET
+                    expires_at = EXCLUDED.expires_at
+            "#,
+            email,
+            ip_address,
+            Utc::now() + Duration::seconds(window as i64),
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(())
+    }
+
+    async fn find_login_failure_ips(&self, email: &str) -> AppResult<Vec<String>> {
+        let mut conn = self.source.acquire().await?;
+        sqlx::query_scalar!(
+            r#"
+                SELECT ip_address FROM login_failure_ips
+                WHERE email = $1
+                AND expires_at > CURRENT_TIMESTAMP(3)
+            "#,
+            email,
+        )
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)
+    }
+
+    async fn create_mfa_challenge(
+        &self,
+        event: CreateMfaChallenge,
+        ttl: u64,
+    ) -> AppResult<MfaChallengeToken> {
+        let mut conn = self.source.acquire().await?;
+        sqlx::query!(
+            r#"
+                INSERT INTO mfa_challenges (token_hash, user_id, expires_at)
+                VALUES ($1, $2, $3)
+            "#,
+            hash_token(&event.challenge_token),
+            event.user_id as _,
+            Utc::now() + Duration::seconds(ttl as i64),
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(MfaChallengeToken(event.challenge_token))
+    }
+
+    async fn consume_mfa_challenge(
+        &self,
+        challenge_token: &MfaChallengeToken,
+    ) -> AppResult<Option<UserId>> {
+        let mut conn = self.source.acquire().await?;
+        let row = sqlx::query!(
+            r#"
+                DELETE FROM mfa_challenges
+                WHERE token_hash = $1
+                RETURNING user_id, expires_at
+            "#,
+            hash_token(&challenge_token.0),
+        )
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(row
+            .filter(|row| row.expires_at > Utc::now())
+            .map(|row| UserId::from(row.user_id)))
+    }
+
+    async fn create_oidc_request(
+        &self,
+        request: &OidcAuthorizationRequest,
+        ttl: u64,
+    ) -> AppResult<()> {
+        let mut conn = self.source.acquire().await?;
+        sqlx::query!(
+            r#"
+                INSERT INTO oidc_requests (state_hash, nonce, code_verifier, expires_at)
+                VALUES ($1, $2, $3, $4)
+            "#,
+            hash_token(&request.state),
+            request.nonce,
+            request.code_verifier,
+            Utc::now() + Duration::seconds(ttl as i64),
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(())
+    }
+
+    async fn consume_oidc_request(
+        &self,
+        state: &str,
+    ) -> AppResult<Option<OidcAuthorizationRequest>> {
+        let mut conn = self.source.acquire().await?;
+        let row = sqlx::query!(
+            r#"
+                DELETE FROM oidc_requests
+                WHERE state_hash = $1
+                RETURNING nonce, code_verifier, expires_at
+            "#,
+            hash_token(state),
+        )
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(row
+            .filter(|row| row.expires_at > Utc::now())
+            .map(|row| OidcAuthorizationRequest {
+                state: state.to_string(),
+                nonce: row.nonce,
+                code_verifier: row.code_verifier,
+            }))
+    }
+
+    async fn create_password_reset_token(
+        &self,
+        event: CreatePasswordResetToken,
+        ttl: u64,
+    ) -> AppResult<PasswordResetToken> {
+        let mut conn = self.source.acquire().await?;
+        sqlx::query!(
+            r#"
+                INSERT INTO password_reset_tokens (token_hash, user_id, expires_at)
+                VALUES ($1, $2, $3)
+            "#,
+            hash_token(&event.reset_token),
+            event.user_id as _,
+            Utc::now() + Duration::seconds(ttl as i64),
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(PasswordResetToken(event.reset_token))
+    }
+
+    async fn find_password_reset_token(
+        &self,
+        reset_token: &PasswordResetToken,
+    ) -> AppResult<Option<UserId>> {
+        let mut conn = self.source.acquire().await?;
+        let row = sqlx::query!(
+            r#"
+                SELECT user_id, expires_at FROM password_reset_tokens
+                WHERE token_hash = $1
+            "#,
+            hash_token(&reset_token.0),
+        )
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(row
+            .filter(|row| row.expires_at > Utc::now())
+            .map(|row| UserId::from(row.user_id)))
+    }
+
+    async fn consume_password_reset_token(
+        &self,
+        reset_token: &PasswordResetToken,
+    ) -> AppResult<Option<UserId>> {
+        let mut conn = self.source.acquire().await?;
+        let row = sqlx::query!(
+            r#"
+                DELETE FROM password_reset_tokens
+                WHERE token_hash = $1
+                RETURNING user_id, expires_at
+            "#,
+            hash_token(&reset_token.0),
+        )
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(row
+            .filter(|row| row.expires_at > Utc::now())
+            .map(|row| UserId::from(row.user_id)))
+    }
+
+    async fn create_email_verification_token(
+        &self,
+        event: CreateEmailVerificationToken,
+        ttl: u64,
+    ) -> AppResult<EmailVerificationToken> {
+        let mut conn = self.source.acquire().await?;
+        sqlx::query!(
+            r#"
+                INSERT INTO email_verification_tokens (token_hash, user_id, email, expires_at)
+                VALUES ($1, $2, $3, $4)
+            "#,
+            hash_token(&event.verification_token),
+            event.user_id as _,
+            event.email.as_ref(),
+            Utc::now() + Duration::seconds(ttl as i64),
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(EmailVerificationToken(event.verification_token))
+    }
+
+    async fn consume_email_verification_token(
+        &self,
+        verification_token: &EmailVerificationToken,
+    ) -> AppResult<Option<PendingEmailChange>> {
+        let mut conn = self.source.acquire().await?;
+        let row = sqlx::query!(
+            r#"
+                DELETE FROM email_verification_tokens
+                WHERE token_hash = $1
+                RETURNING user_id, email, expires_at
+            "#,
+            hash_token(&verification_token.0),
+        )
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        row.filter(|row| row.expires_at > Utc::now())
+            .map(|row| {
+                Ok(PendingEmailChange {
+                    user_id: UserId::from(row.user_id),
+                    email: row.email.parse()?,
+                })
+            })
+            .transpose()
+    }
+
+    async fn delete_expired(&self) -> AppResult<()> {
+        let mut conn = self.source.acquire().await?;
+        sqlx::query!(
+            r#"
+                WITH sessions AS (
+                    DELETE FROM auth_sessions
+                    WHERE expires_at <= CURRENT_TIMESTAMP(3)
+                ),
+                revoked AS (
+                    DELETE FROM revoked_access_tokens
+                    WHERE expires_at <= CURRENT_TIMESTAMP(3)
+                ),
+                families AS (
+                    DELETE FROM refresh_token_families
+                    WHERE expires_at <= CURRENT_TIMESTAMP(3)
+                ),
+                refresh AS (
+                    DELETE FROM refresh_tokens
+                    WHERE expires_at <= CURRENT_TIMESTAMP(3)
+                ),
+                password_resets AS (
+                    DELETE FROM password_reset_tokens
+                    WHERE expires_at <= CURRENT_TIMESTAMP(3)
+                ),
+                email_verifications AS (
+                    DELETE FROM email_verification_tokens
+                    WHERE expires_at <= CURRENT_TIMESTAMP(3)
+                ),
+                mfa AS (
+                    DELETE FROM mfa_challenges
+                    WHERE expires_at <= CURRENT_TIMESTAMP(3)
+                ),
+                oidc AS (
+                    DELETE FROM oidc_requests
+                    WHERE expires_at <= CURRENT_TIMESTAMP(3)
+                ),
+                failure_ips AS (
+                    DELETE FROM login_failure_ips
+                    WHERE expires_at <= CURRENT_TIMESTAMP(3)
+                )
+                DELETE FROM login_failures
+                WHERE expires_at <= CURRENT_TIMESTAMP(3)
+                AND (locked_until IS NULL OR locked_until <= CURRENT_TIMESTAMP(3))
+            "#,
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(())
+    }
+}
+
+// リフレッシュトークンやパスワード再設定用のトークンなどは、ハッシュにして保存する
+fn hash_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::user::UserRepositoryImpl;
+    use crate::{database::ConnectionPool, unit_of_work::UnitOfWorkScopeImpl};
+    use kernel::{
+        mail::MockMailSender,
+        model::{oidc::OidcClaims, session::SessionClient, user::event::DeactivateUser},
+        oidc::MockOidcProvider,
+        password::MockPasswordHasher,
+        repository::user::UserRepository,
+        unit_of_work::auth::AuthUnitOfWorkScope,
+        use_case::auth::{AuthUseCase, AuthUseCaseImpl},
+    };
+    use shared::config::{JwtAlgorithm, JwtConfig, LoginLockoutConfig, MfaConfig};
+    use std::str::FromStr;
+
+    fn keys(secret: &str) -> Arc<JwtKeys> {
+        Arc::new(
+            JwtKeys::new(&JwtConfig {
+                algorithm: JwtAlgorithm::HS256,
+                signing_key: secret.into(),
+                verifying_key: secret.into(),
+            })
+            .unwrap(),
+        )
+    }
+
+    fn init_auth_use_case(pool: sqlx::PgPool, hasher: MockPasswordHasher) -> AuthUseCaseImpl {
+        AuthUseCaseImpl::new(
+            Arc::new(UnitOfWorkScopeImpl::new_with_jwt_keys(
+                Arc::new(ConnectionPool::from(pool)),
+                keys("secret"),
+                3600,
+            )),
+            Arc::new(MockMailSender::new()),
+            Arc::new(hasher),
+            3600,
+            3600,
+            LoginLockoutConfig {
+                email_threshold: 5,
+                ip_threshold: 20,
+                base_secs: 60,
+                max_secs: 3600,
+            },
+            MfaConfig {
+                issuer: "book-manager".into(),
+                challenge_ttl: 300,
+                required_for_admin: false,
+            },
+        )
+    }
+
+    #[sqlx::test(fixtures("common"))]
+    async fn test_access_token(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = JwtAuthRepositoryImpl::new(pool.clone(), keys("secret"), 3600);
+        let user_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+
+        let event = CreateToken::new(user_id, SessionClient::new(Some("curl".into()), None));
+        let session_id = event.access_token.clone();
+        let token = repo.create_token(event).await?;
+        assert_eq!(repo.fetch_user_id_from_token(&token).await?, Some(user_id));
+
+        // 別の鍵で署名されたトークンや、改ざんされたトークンは受け付けない
+        let other = JwtAuthRepositoryImpl::new(pool.clone(), keys("other"), 3600);
+        assert!(other.fetch_user_id_from_token(&token).await?.is_none());
+        let tampered = AccessToken(format!("{}x", token.0));
+        assert!(repo.fetch_user_id_from_token(&tampered).await?.is_none());
+
+        let sessions = repo.find_sessions(user_id, &token).await?;
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].id(), session_id);
+        assert!(sessions[0].is_current());
+        assert_eq!(sessions[0].client().device.as_deref(), Some("curl"));
+
+        repo.delete_token(AccessToken(token.0.clone())).await?;
+        assert!(repo.fetch_user_id_from_token(&token).await?.is_none());
+        assert!(repo.find_sessions(user_id, &token).await?.is_empty());
+
+        // 別のプロセスでも、データベースと同期した時点で失効が反映される
+        let another = JwtAuthRepositoryImpl::new(pool.clone(), keys("secret"), 3600);
+        assert!(another.fetch_user_id_from_token(&token).await?.is_none());
+
+        let token = repo
+            .create_token(CreateToken::new(user_id, SessionClient::default()))
+            .await?;
+        repo.delete_all_sessions(user_id).await?;
+        assert!(repo.fetch_user_id_from_token(&token).await?.is_none());
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common"))]
+    async fn test_revocation_applied_after_commit(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let keys = keys("secret");
+        let scope = UnitOfWorkScopeImpl::new_with_jwt_keys(
+            Arc::new(ConnectionPool::from(pool.clone())),
+            keys.clone(),
+            3600,
+        );
+        let repo = JwtAuthRepositoryImpl::new(pool, keys.clone(), 3600);
+        let user_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+        let event = CreateToken::new(user_id, SessionClient::default());
+        let session_id = event.access_token.clone();
+        repo.create_token(event).await?;
+
+        // ロールバックした失効は、メモリ上の失効リストに残らない
+        let uow = scope.begin().await?;
+        uow.auth_repository().delete_all_sessions(user_id).await?;
+        assert!(!keys.revoked.contains(&session_id));
+        uow.rollback().await?;
+        assert!(!keys.revoked.contains(&session_id));
+
+        let uow = scope.begin().await?;
+        uow.auth_repository().delete_all_sessions(user_id).await?;
+        assert!(!keys.revoked.contains(&session_id));
+        uow.commit().await?;
+        assert!(keys.revoked.contains(&session_id));
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common"))]
+    async fn test_delete_expired(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = JwtAuthRepositoryImpl::new(pool.clone(), keys("secret"), 3600);
+        let user_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+
+        let token = repo
+            .create_token(CreateToken::new(user_id, SessionClient::default()))
+            .await?;
+        repo.delete_token(token).await?;
+        repo.create_token(CreateToken::new(user_id, SessionClient::default()))
+            .await?;
+        sqlx::query!(
+            r#"
+                INSERT INTO auth_sessions (session_id, user_id, family_id, expires_at)
+                VALUES ('expired', $1, 'expired', CURRENT_TIMESTAMP(3) - INTERVAL '1 minute')
+            "#,
+            user_id as _,
+        )
+        .execute(&pool)
+        .await?;
+        sqlx::query!(
+            r#"
+                UPDATE revoked_access_tokens
+                SET expires_at = CURRENT_TIMESTAMP(3) - INTERVAL '1 minute'
+            "#,
+        )
+        .execute(&pool)
+        .await?;
+
+        repo.delete_expired().await?;
+
+        // 有効期限内のセッションだけが残る
+        let sessions = sqlx::query_scalar!(r#"SELECT COUNT(*) AS "count!" FROM auth_sessions"#)
+            .fetch_one(&pool)
+            .await?;
+        assert_eq!(sessions, 1);
+        let revoked =
+            sqlx::query_scalar!(r#"SELECT COUNT(*) AS "count!" FROM revoked_access_tokens"#)
+                .fetch_one(&pool)
+                .await?;
+        assert_eq!(revoked, 0);
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common"))]
+    async fn test_refresh_token(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = JwtAuthRepositoryImpl::new(pool, keys("secret"), 3600);
+        let user_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+
+        let event = CreateToken::new(user_id, SessionClient::default());
+        let family_id = event.family_id.clone();
+        let access_token = repo.create_token(event).await?;
+        let refresh_token = repo
+            .create_refresh_token(CreateRefreshToken::new(user_id, family_id.clone()), 3600)
+            .await?;
+
+        let entry = repo.find_refresh_token(&refresh_token).await?.unwrap();
+        assert_eq!(entry.user_id, user_id);
+        assert_eq!(entry.family_id, family_id);
+        assert!(!entry.rotated);
+
+        repo.mark_refresh_token_rotated(&refresh_token).await?;
+        assert!(
+            repo.find_refresh_token(&refresh_token)
+                .await?
+                .unwrap()
+                .rotated
+        );
+
+        repo.revoke_refresh_family(user_id, &family_id).await?;
+        assert!(repo.find_refresh_token(&refresh_token).await?.is_none());
+        assert!(
+            repo.fetch_user_id_from_token(&access_token)
+                .await?
+                .is_none()
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common"))]
+    async fn test_login_unknown_email(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        // 登録されていないメールアドレスでも、ダミーのハッシュとパスワードを照合する
+        let mut hasher = MockPasswordHasher::new();
+        hasher
+            .expect_hash()
+            .times(1)
+            .returning(|_| Ok("dummy-hash".into()));
+        hasher
+            .expect_verify()
+            .withf(|password, hash| password == "Pa55w0rd" && hash == "dummy-hash")
+            .times(2)
+            .returning(|_, _| Ok(false));
+        let use_case = init_auth_use_case(pool, hasher);
+
+        // ダミーのハッシュは最初の一度だけ作る
+        for _ in 0..2 {
+            let res = use_case
+                .login("unknown@example.com", "Pa55w0rd", SessionClient::default())
+                .await;
+            assert!(matches!(res, Err(AppError::UnauthenticatedError)));
+        }
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_login_failures(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = JwtAuthRepositoryImpl::new(pool, keys("secret"), 3600);
+        let key = LoginAttemptKey::Email("user@example.com".into());
+
+        assert_eq!(repo.record_login_failure(&key, 3600).await?, 1);
+        assert_eq!(repo.record_login_failure(&key, 3600).await?, 2);
+        assert!(repo.find_login_lockout(&key).await?.is_none());
+
+        let until = Utc::now() + Duration::seconds(60);
+        repo.lock_login(&key, until).await?;
+        let locked_until = repo.find_login_lockout(&key).await?.unwrap();
+        assert_eq!(locked_until.timestamp(), until.timestamp());
+        // 他のキーには影響しない
+        let other = LoginAttemptKey::IpAddress("203.0.113.5".into());
+        assert!(repo.find_login_lockout(&other).await?.is_none());
+
+        repo.record_login_failure_ip("user@example.com", "203.0.113.5", 3600)
+            .await?;
+        repo.record_login_failure_ip("user@example.com", "203.0.113.5", 3600)
+            .await?;
+        assert_eq!(
+            repo.find_login_failure_ips("user@example.com").await?,
+            ["203.0.113.5"]
+        );
+        assert!(
+            repo.find_login_failure_ips("other@example.com")
+                .await?
+                .is_empty()
+        );
+
+        repo.clear_login_failures(&key).await?;
+        assert!(repo.find_login_lockout(&key).await?.is_none());
+        assert_eq!(repo.record_login_failure(&key, 3600).await?, 1);
+        assert!(
+            repo.find_login_failure_ips("user@example.com")
+                .await?
+                .is_empty()
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common"))]
+    async fn test_password_reset_token(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = JwtAuthRepositoryImpl::new(pool, keys("secret"), 3600);
+        let user_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+
+        let reset_token = repo
+            .create_password_reset_token(CreatePasswordResetToken::new(user_id), 3600)
+            .await?;
+        // 取得しただけではトークンを使ったことにならない
+        assert_eq!(
+            repo.find_password_reset_token(&reset_token).await?,
+            Some(user_id)
+        );
+        assert_eq!(
+            repo.consume_password_reset_token(&reset_token).await?,
+            Some(user_id)
+        );
+        assert!(
+            repo.consume_password_reset_token(&reset_token)
+                .await?
+                .is_none()
+        );
+        assert!(
+            repo.find_password_reset_token(&reset_token)
+                .await?
+                .is_none()
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common"))]
+    async fn test_email_verification_token(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = JwtAuthRepositoryImpl::new(pool, keys("secret"), 3600);
+        let user_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+
+        let event = CreateEmailVerificationToken::new(user_id, "new@example.com".parse()?);
+        let verification_token = repo.create_email_verification_token(event, 3600).await?;
+        assert_eq!(
+            repo.consume_email_verification_token(&verification_token)
+                .await?,
+            Some(PendingEmailChange {
+                user_id,
+                email: "new@example.com".parse()?,
+            })
+        );
+        assert!(
+            repo.consume_email_verification_token(&verification_token)
+                .await?
+                .is_none()
+        );
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common"))]
+    async fn test_mfa_challenge(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = JwtAuthRepositoryImpl::new(pool, keys("secret"), 3600);
+        let user_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+
+        let challenge = repo
+            .create_mfa_challenge(CreateMfaChallenge::new(user_id), 300)
+            .await?;
+        assert_eq!(repo.consume_mfa_challenge(&challenge).await?, Some(user_id));
+        assert!(repo.consume_mfa_challenge(&challenge).await?.is_none());
+
+        // 有効期限を過ぎたチャレンジは使えない
+        let expired = repo
+            .create_mfa_challenge(CreateMfaChallenge::new(user_id), 0)
+            .await?;
+        assert!(repo.consume_mfa_challenge(&expired).await?.is_none());
+
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_oidc_request(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = JwtAuthRepositoryImpl::new(pool, keys("secret"), 3600);
+
+        let request = OidcAuthorizationRequest::new();
+        repo.create_oidc_request(&request, 600).await?;
+        assert!(repo.consume_oidc_request("unknown").await?.is_none());
+        assert_eq!(
+            repo.consume_oidc_request(&request.state).await?,
+            Some(request.clone())
+        );
+        assert!(repo.consume_oidc_request(&request.state).await?.is_none());
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common"))]
+    async fn test_oidc_login_with_deactivated_email(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let email = "eleazar.fig@example.com";
+        UserRepositoryImpl::new(pool.clone())
+            .deactivate(DeactivateUser {
+                user_id: UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?,
+            })
+            .await?;
+        let request = OidcAuthorizationRequest::new();
+        JwtAuthRepositoryImpl::new(pool.clone(), keys("secret"), 3600)
+            .create_oidc_request(&request, 600)
+            .await?;
+
+        let mut hasher = MockPasswordHasher::new();
+        hasher.expect_hash().returning(|_| Ok("dummy-hash".into()));
+        let mut provider = MockOidcProvider::new();
+        provider.expect_exchange_code().returning(move |_, _| {
+            Ok(OidcClaims {
+                subject: "subject".into(),
+                email: email.into(),
+                email_verified: Some(true),
+                name: None,
+            })
+        });
+        let use_case = init_auth_use_case(pool, hasher).with_oidc(Arc::new(provider), true);
+
+        // 無効化されたユーザーのメールアドレスでは、ユーザーを作り直さずにログインを拒否する
+        let res = use_case
+            .oidc_login(&request.state, "code", SessionClient::default())
+            .await;
+        assert!(matches!(res, Err(AppError::UnauthenticatedError)));
+
+        Ok(())
+    }
+}
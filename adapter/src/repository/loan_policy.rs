@@ -0,0 +1,142 @@
+use crate::database::{ConnectionSource, model::loan_policy::LoanPolicyRow};
+use async_trait::async_trait;
+use kernel::{
+    model::{
+        loan_policy::{LoanPolicy, event::UpdateLoanPolicy},
+        role::Role,
+    },
+    repository::loan_policy::LoanPolicyRepository,
+};
+use shared::error::{AppError, AppResult};
+
+pub struct LoanPolicyRepositoryImpl<'t, 'm> {
+    source: ConnectionSource<'t, 'm>,
+}
+
+impl<'t, 'm> LoanPolicyRepositoryImpl<'t, 'm> {
+    pub fn new(source: impl Into<ConnectionSource<'t, 'm>>) -> Self {
+        Self {
+            source: source.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl<'t, 'm> LoanPolicyRepository for LoanPolicyRepositoryImpl<'t, 'm> {
+    async fn find_all(&self) -> AppResult<Vec<LoanPolicy>> {
+        let mut conn = self.source.acquire().await?;
+        sqlx::query_as!(
+            LoanPolicyRow,
+            r#"
+                SELECT
+                    r.name AS role_name,
+                    lp.max_loans,
+                    lp.loan_period_days,
+                    lp.max_renewals
+                FROM loan_policies AS lp
+                    INNER JOIN roles AS r USING(role_id)
+                ORDER BY r.name ASC
+                ;
+            "#,
+        )
+        .fetch_all(&mut *conn)
+        .await
+        .map(|rows| rows.into_iter().map(LoanPolicy::try_from).collect())
+        .map_err(AppError::SpecificOperationError)?
+    }
+
+    async fn find_by_role(&self, role: &Role) -> AppResult<Option<LoanPolicy>> {
+        let mut conn = self.source.acquire().await?;
+        sqlx::query_as!(
+            LoanPolicyRow,
+            r#"
+                SELECT
+                    r.name AS role_name,
+                    lp.max_loans,
+                    lp.loan_period_days,
+                    lp.max_renewals
+                FROM loan_policies AS lp
+                    INNER JOIN roles AS r USING(role_id)
+                WHERE r.name = $1
+                ;
+            "#,
+            role.as_ref(),
+        )
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .map(LoanPolicy::try_from)
+        .transpose()
+    }
+
+    async fn update(&self, event: &UpdateLoanPolicy) -> AppResult<()> {
+        let mut conn = self.source.acquire().await?;
+        let res = sqlx::query!(
+            r#"
+                INSERT INTO loan_policies
+                (role_id, max_loans, loan_period_days, max_renewals)
+                SELECT role_id, $2, $3, $4 FROM roles WHERE name = $1
+                ON CONFLICT (role_id) DO UPDATE SET
+                    max_loans = EXCLUDED.max_loans,
+                    loan_period_days = EXCLUDED.loan_period_days,
+                    max_renewals = EXCLUDED.max_renewals
+                ;
+            "#,
+            event.role.as_ref(),
+            event.max_loans,
+            event.loan_period_days,
+            event.max_renewals,
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        if res.rows_affected() < 1 {
+            return Err(AppError::NoRowsAffectedError(
+                "No loan policy record has been updated".into(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[sqlx::test(fixtures("common"))]
+    async fn test_update_loan_policy(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = LoanPolicyRepositoryImpl::new(pool);
+
+        assert!(repo.find_all().await?.is_empty());
+        assert!(repo.find_by_role(&Role::User).await?.is_none());
+
+        repo.update(&UpdateLoanPolicy {
+            role: Role::User,
+            max_loans: 3,
+            loan_period_days: 7,
+            max_renewals: 1,
+        })
+        .await?;
+        repo.update(&UpdateLoanPolicy {
+            role: Role::User,
+            max_loans: 4,
+            loan_period_days: 10,
+            max_renewals: 0,
+        })
+        .await?;
+
+        let res = repo.find_all().await?;
+        assert_eq!(res.len(), 1);
+
+        let policy = repo.find_by_role(&Role::User).await?.unwrap();
+        assert_eq!(policy.role(), &Role::User);
+        assert_eq!(policy.max_loans(), 4);
+        assert_eq!(policy.loan_period_days(), 10);
+        assert_eq!(policy.max_renewals(), 0);
+        assert!(repo.find_by_role(&Role::Admin).await?.is_none());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,247 @@
+use crate::database::{ConnectionSource, model::mfa::UserMfaRow};
+use async_trait::async_trait;
+use kernel::{
+    model::{
+        id::UserId,
+        mfa::{
+            UserMfa,
+            event::{EnableMfa, StartMfaEnrollment},
+        },
+    },
+    repository::mfa::MfaRepository,
+};
+use sha2::{Digest, Sha256};
+use shared::error::{AppError, AppResult};
+
+pub struct MfaRepositoryImpl<'t, 'm> {
+    source: ConnectionSource<'t, 'm>,
+}
+
+impl<'t, 'm> MfaRepositoryImpl<'t, 'm> {
+    pub fn new(source: impl Into<ConnectionSource<'t, 'm>>) -> Self {
+        Self {
+            source: source.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl<'t, 'm> MfaRepository for MfaRepositoryImpl<'t, 'm> {
+    async fn find_by_user_id(&self, user_id: UserId) -> AppResult<Option<UserMfa>> {
+        let mut conn = self.source.acquire().await?;
+        let row = sqlx::query_as!(
+            UserMfaRow,
+            r#"
+                SELECT user_id, secret, enabled_at
+                FROM user_mfa
+                WHERE user_id = $1
+            "#,
+            user_id as _,
+        )
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(row.map(UserMfa::from))
+    }
+
+    async fn start_enrollment(&self, event: StartMfaEnrollment) -> AppResult<()> {
+        let mut conn = self.source.acquire().await?;
+        // 有効な二要素認証は上書きしない
+        let res = sqlx::query!(
+            r#"
+                INSERT INTO user_mfa (user_id, secret)
+                VALUES ($1, $2)
+                ON CONFLICT (user_id) DO UPDATE
+                SET secret = EXCLUDED.secret
+                WHERE user_mfa.enabled_at IS NULL
+            "#,
+            event.user_id as _,
+            event.secret,
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if res.rows_affected() < 1 {
+            return Err(AppError::NoRowsAffectedError(
+                "No user_mfa record has been updated".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    async fn enable(&self, event: EnableMfa) -> AppResult<()> {
+        let mut conn = self.source.acquire().await?;
+        let res = sqlx::query!(
+            r#"
+                UPDATE user_mfa
+                SET enabled_at = CURRENT_TIMESTAMP(3)
+                WHERE user_id = $1
+            "#,
+            event.user_id as _,
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if res.rows_affected() < 1 {
+            return Err(AppError::NoRowsAffectedError(
+                "No user_mfa record has been updated".into(),
+            ));
+        }
+
+        sqlx::query!(
+            r#"
+                DELETE FROM mfa_recovery_codes
+                WHERE user_id = $1
+            "#,
+            event.user_id as _,
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+
+        let code_hashes: Vec<String> = event
+            .recovery_codes
+            .iter()
+            .map(|code| hash_code(event.user_id, code))
+            .collect();
+        sqlx::query!(
+            r#"
+                INSERT INTO mfa_recovery_codes (user_id, code_hash)
+                SELECT $1, code_hash FROM UNNEST($2::VARCHAR[]) AS code_hash
+            "#,
+            event.user_id as _,
+            &code_hashes,
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(())
+    }
+
+    async fn disable(&self, user_id: UserId) -> AppResult<()> {
+        let mut conn = self.source.acquire().await?;
+        // リカバリーコードは外部キーの ON DELETE CASCADE でまとめて削除される
+        sqlx::query!(
+            r#"
+                DELETE FROM user_mfa
+                WHERE user_id = $1
+            "#,
+            user_id as _,
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(())
+    }
+
+    async fn accept_time_step(&self, user_id: UserId, step: i64) -> AppResult<bool> {
+        let mut conn = self.source.acquire().await?;
+        // 比較と更新を 1 文で行い、同じコードの同時利用も片方だけが成功するようにする
+        let res = sqlx::query!(
+            r#"
+                UPDATE user_mfa
+                SET last_used_step = $2
+                WHERE user_id = $1
+                AND (last_used_step IS NULL OR last_used_step < $2)
+            "#,
+            user_id as _,
+            step,
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(res.rows_affected() > 0)
+    }
+
+    async fn consume_recovery_code(&self, user_id: UserId, code: &str) -> AppResult<bool> {
+        let mut conn = self.source.acquire().await?;
+        let res = sqlx::query!(
+            r#"
+                UPDATE mfa_recovery_codes
+                SET used_at = CURRENT_TIMESTAMP(3)
+                WHERE user_id = $1
+                AND code_hash = $2
+                AND used_at IS NULL
+            "#,
+            user_id as _,
+            hash_code(user_id, code),
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(res.rows_affected() > 0)
+    }
+}
+
+// 同じコードでもユーザーごとに異なるハッシュになるよう、ユーザー ID を含めてハッシュにする
+fn hash_code(user_id: UserId, code: &str) -> String {
+    format!(
+        "{:x}",
+        Sha256::digest(format!("{user_id}:{code}").as_bytes())
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[sqlx::test(fixtures("common"))]
+    async fn test_mfa(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = MfaRepositoryImpl::new(pool);
+        let user_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+        assert!(repo.find_by_user_id(user_id).await?.is_none());
+
+        repo.start_enrollment(StartMfaEnrollment {
+            user_id,
+            secret: "FIRSTSECRET".into(),
+        })
+        .await?;
+        // 登録の完了前であれば共有秘密を置き換えられる
+        repo.start_enrollment(StartMfaEnrollment {
+            user_id,
+            secret: "SECONDSECRET".into(),
+        })
+        .await?;
+        let mfa = repo.find_by_user_id(user_id).await?.unwrap();
+        assert_eq!(mfa.secret, "SECONDSECRET");
+        assert!(!mfa.enabled);
+
+        repo.enable(EnableMfa {
+            user_id,
+            recovery_codes: vec!["aaaaa-bbbbb".into(), "ccccc-ddddd".into()],
+        })
+        .await?;
+        assert!(repo.find_by_user_id(user_id).await?.unwrap().enabled);
+        let res = repo
+            .start_enrollment(StartMfaEnrollment {
+                user_id,
+                secret: "THIRDSECRET".into(),
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::NoRowsAffectedError(_))));
+
+        // 確認コードのタイムステップは、最後に受け付けたものより後でなければならない
+        assert!(repo.accept_time_step(user_id, 100).await?);
+        assert!(!repo.accept_time_step(user_id, 100).await?);
+        assert!(!repo.accept_time_step(user_id, 99).await?);
+        assert!(repo.accept_time_step(user_id, 101).await?);
+        assert!(!repo.accept_time_step(UserId::new(), 102).await?);
+
+        // リカバリーコードは一度しか使えない
+        assert!(repo.consume_recovery_code(user_id, "aaaaa-bbbbb").await?);
+        assert!(!repo.consume_recovery_code(user_id, "aaaaa-bbbbb").await?);
+        assert!(!repo.consume_recovery_code(user_id, "unknown").await?);
+        assert!(
+            !repo
+                .consume_recovery_code(UserId::new(), "ccccc-ddddd")
+                .await?
+        );
+
+        repo.disable(user_id).await?;
+        assert!(repo.find_by_user_id(user_id).await?.is_none());
+        assert!(!repo.consume_recovery_code(user_id, "ccccc-ddddd").await?);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,220 @@
+use crate::database::{ConnectionSource, model::personal_access_token::PersonalAccessTokenRow};
+use async_trait::async_trait;
+use kernel::{
+    model::{
+        auth::AccessToken,
+        id::UserId,
+        personal_access_token::{
+            PersonalAccessToken,
+            event::{CreatePersonalAccessToken, DeletePersonalAccessToken},
+        },
+    },
+    repository::personal_access_token::PersonalAccessTokenRepository,
+};
+use sha2::{Digest, Sha256};
+use shared::{
+    error::{AppError, AppResult},
+    i18n::{Message, MessageKey},
+};
+
+pub struct PersonalAccessTokenRepositoryImpl<'t, 'm> {
+    source: ConnectionSource<'t, 'm>,
+}
+
+impl<'t, 'm> PersonalAccessTokenRepositoryImpl<'t, 'm> {
+    pub fn new(source: impl Into<ConnectionSource<'t, 'm>>) -> Self {
+        Self {
+            source: source.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl<'t, 'm> PersonalAccessTokenRepository for PersonalAccessTokenRepositoryImpl<'t, 'm> {
+    async fn create(&self, event: &CreatePersonalAccessToken) -> AppResult<PersonalAccessToken> {
+        let mut conn = self.source.acquire().await?;
+        let scopes: Vec<String> = event.scopes.iter().map(|s| s.as_ref().into()).collect();
+        sqlx::query_as!(
+            PersonalAccessTokenRow,
+            r#"
+                INSERT INTO personal_access_tokens
+                (user_id, name, token_hash, scopes, expires_at)
+                VALUES ($1, $2, $3, $4, $5)
+                RETURNING
+                    token_id,
+                    user_id,
+                    name,
+                    scopes,
+                    expires_at,
+                    last_used_at,
+                    created_at
+            "#,
+            event.user_id as _,
+            event.name,
+            hash_secret(&event.secret),
+            &scopes,
+            event.expires_at,
+        )
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .try_into()
+    }
+
+    async fn delete(&self, event: DeletePersonalAccessToken) -> AppResult<()> {
+        let mut conn = self.source.acquire().await?;
+        let res = sqlx::query!(
+            r#"
+                DELETE FROM personal_access_tokens
+                WHERE token_id = $1
+                AND user_id = $2
+            "#,
+            event.token_id as _,
+            event.requested_user as _,
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if res.rows_affected() < 1 {
+            return Err(AppError::EntityNotFound(
+                Message::new(MessageKey::PersonalAccessTokenNotFound).arg(event.token_id),
+            ));
+        }
+        Ok(())
+    }
+
+    async fn find_by_user_id(&self, user_id: UserId) -> AppResult<Vec<PersonalAccessToken>> {
+        let mut conn = self.source.acquire().await?;
+        sqlx::query_as!(
+            PersonalAccessTokenRow,
+            r#"
+                SELECT
+                    token_id,
+                    user_id,
+                    name,
+                    scopes,
+                    expires_at,
+                    last_used_at,
+                    created_at
+                FROM personal_access_tokens
+                WHERE user_id = $1
+                ORDER BY created_at ASC
+            "#,
+            user_id as _,
+        )
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .into_iter()
+        .map(PersonalAccessToken::try_from)
+        .collect()
+    }
+
+    async fn find_active_by_secret(
+        &self,
+        access_token: &AccessToken,
+    ) -> AppResult<Option<PersonalAccessToken>> {
+        let mut conn = self.source.acquire().await?;
+        sqlx::query_as!(
+            PersonalAccessTokenRow,
+            r#"
+                UPDATE personal_access_tokens
+                SET last_used_at = CURRENT_TIMESTAMP(3)
+                WHERE token_hash = $1
+                AND (expires_at IS NULL OR expires_at > CURRENT_TIMESTAMP(3))
+                RETURNING
+                    token_id,
+                    user_id,
+                    name,
+                    scopes,
+                    expires_at,
+                    last_used_at,
+                    created_at
+            "#,
+            hash_secret(&access_token.0),
+        )
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?
+        .map(PersonalAccessToken::try_from)
+        .transpose()
+    }
+}
+
+// トークンは十分な長さの乱数のため、ソルトなしの SHA-256 で検索可能な形にして保存する
+fn hash_secret(secret: &str) -> String {
+    format!("{:x}", Sha256::digest(secret.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+    use kernel::model::personal_access_token::TokenScope;
+    use std::str::FromStr;
+
+    #[sqlx::test(fixtures("common"))]
+    async fn test_personal_access_token(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = PersonalAccessTokenRepositoryImpl::new(pool);
+        let user_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+
+        let event = CreatePersonalAccessToken::new(
+            user_id,
+            "inventory".into(),
+            vec![TokenScope::Read],
+            None,
+        );
+        let token = repo.create(&event).await?;
+        assert_eq!(token.name(), "inventory");
+        assert_eq!(token.scopes(), &[TokenScope::Read]);
+        assert!(token.last_used_at().is_none());
+
+        let found = repo
+            .find_active_by_secret(&AccessToken(event.secret.clone()))
+            .await?
+            .unwrap();
+        assert_eq!(found.id(), token.id());
+        assert!(found.last_used_at().is_some());
+        assert!(
+            repo.find_active_by_secret(&AccessToken("pat_unknown".into()))
+                .await?
+                .is_none()
+        );
+
+        let expired = CreatePersonalAccessToken::new(
+            user_id,
+            "expired".into(),
+            vec![TokenScope::Read, TokenScope::Write],
+            Some(Utc::now() - Duration::days(1)),
+        );
+        repo.create(&expired).await?;
+        assert!(
+            repo.find_active_by_secret(&AccessToken(expired.secret.clone()))
+                .await?
+                .is_none()
+        );
+        assert_eq!(repo.find_by_user_id(user_id).await?.len(), 2);
+
+        // 他のユーザーのトークンは削除できない
+        let res = repo
+            .delete(DeletePersonalAccessToken {
+                token_id: token.id(),
+                requested_user: UserId::new(),
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+
+        repo.delete(DeletePersonalAccessToken {
+            token_id: token.id(),
+            requested_user: user_id,
+        })
+        .await?;
+        assert!(
+            repo.find_active_by_secret(&AccessToken(event.secret))
+                .await?
+                .is_none()
+        );
+
+        Ok(())
+    }
+}
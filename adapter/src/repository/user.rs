@@ -1,20 +1,28 @@
 use crate::database::{
-    ConnectionSource,
-    model::user::{UserItem, UserRow},
+    ConnectionSource, like_pattern,
+    model::user::{PaginatedUserRow, UserRow},
 };
 use async_trait::async_trait;
 use kernel::{
     model::{
         id::UserId,
+        list::PaginatedList,
         role::Role,
         user::{
-            User,
-            event::{CreateUser, DeleteUser, UpdateUserPassword, UpdateUserRole},
+            User, UserListOptions,
+            event::{
+                CreateUser, DeactivateUser, DeleteUser, UpdatePasswordHash, UpdateUserEmail,
+                UpdateUserName, UpdateUserRole,
+            },
         },
+        value::UserEmail,
     },
     repository::user::UserRepository,
 };
-use shared::error::{AppError, AppResult};
+use shared::{
+    error::{AppError, AppResult},
+    i18n::{Message, MessageKey},
+};
 
 pub struct UserRepositoryImpl<'t, 'm> {
     source: ConnectionSource<'t, 'm>,
@@ -30,10 +38,26 @@ impl<'t, 'm> UserRepositoryImpl<'t, 'm> {
 
 #[async_trait]
 impl<'t, 'm> UserRepository for UserRepositoryImpl<'t, 'm> {
-    async fn create(&self, event: CreateUser) -> AppResult<User> {
+    async fn count_by_role(&self, role: &Role) -> AppResult<i64> {
+        let mut conn = self.source.acquire().await?;
+        let res = sqlx::query!(
+            r#"
+                SELECT COUNT(*) AS "count!"
+                FROM users AS u
+                    INNER JOIN roles AS r USING(role_id)
+                WHERE r.name = $1 AND u.deactivated_at IS NULL
+            "#,
+            role.as_ref()
+        )
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        Ok(res.count)
+    }
+
+    async fn create(&self, event: CreateUser, password_hash: String) -> AppResult<User> {
         let mut conn = self.source.acquire().await?;
         let user_id = UserId::new();
-        let hashed_password = hash_password(&event.password)?;
         let role = Role::User;
         let res = sqlx::query!(
             r#"
@@ -43,12 +67,12 @@ impl<'t, 'm> UserRepository for UserRepositoryImpl<'t, 'm> {
             user_id as _,
             event.name.as_ref(),
             event.email.as_ref(),
-            hashed_password,
+            password_hash,
             role.as_ref()
         )
         .execute(&mut *conn)
         .await
-        .map_err(AppError::SpecificOperationError)?;
+        .map_err(|e| map_email_conflict(e, &event.email))?;
         if res.rows_affected() < 1 {
             return Err(AppError::NoRowsAffectedError(
                 "No user has been created".into(),
@@ -57,6 +81,26 @@ impl<'t, 'm> UserRepository for UserRepositoryImpl<'t, 'm> {
         Ok(User::new(user_id, event.name, event.email, role))
     }
 
+    async fn deactivate(&self, event: DeactivateUser) -> AppResult<()> {
+        let mut conn = self.source.acquire().await?;
+        let res = sqlx::query!(
+            r#"
+                UPDATE users SET deactivated_at = CURRENT_TIMESTAMP(3)
+                WHERE user_id = $1 AND deactivated_at IS NULL
+            "#,
+            event.user_id as _
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        if res.rows_affected() < 1 {
+            return Err(AppError::EntityNotFound(
+                Message::new(MessageKey::UserNotFound).arg(event.user_id),
+            ));
+        }
+        Ok(())
+    }
+
     async fn delete(&self, event: DeleteUser) -> AppResult<()> {
         let mut conn = self.source.acquire().await?;
         let res = sqlx::query!(
@@ -70,17 +114,29 @@ impl<'t, 'm> UserRepository for UserRepositoryImpl<'t, 'm> {
         .await
         .map_err(AppError::SpecificOperationError)?;
         if res.rows_affected() < 1 {
-            return Err(AppError::EntityNotFound("Specified user not found".into()));
+            return Err(AppError::EntityNotFound(
+                Message::new(MessageKey::UserNotFound).arg(event.user_id),
+            ));
         }
         Ok(())
     }
 
-    async fn find_all(&self) -> AppResult<Vec<User>> {
+    async fn find_all(&self, options: UserListOptions) -> AppResult<PaginatedList<User>> {
         let mut conn = self.source.acquire().await?;
-        let users = sqlx::query_as!(
-            UserRow,
+        let UserListOptions {
+            limit,
+            offset,
+            query,
+            role,
+            status,
+            sort,
+            order,
+        } = options;
+        let rows: Vec<PaginatedUserRow> = sqlx::query_as!(
+            PaginatedUserRow,
             r#"
                 SELECT
+                    COUNT(*) OVER() AS "total!",
                     u.user_id,
                     u.name,
                     u.email,
@@ -89,16 +145,76 @@ impl<'t, 'm> UserRepository for UserRepositoryImpl<'t, 'm> {
                     u.updated_at
                 FROM users AS u
                     INNER JOIN roles AS r USING(role_id)
-                ORDER BY u.created_at DESC;
+                WHERE (
+                    $7 = 'all'
+                    OR ($7 = 'active' AND u.deactivated_at IS NULL)
+                    OR ($7 = 'deactivated' AND u.deactivated_at IS NOT NULL)
+                )
+                AND (
+                    $3::text IS NULL
+                    OR u.name ILIKE $3 ESCAPE '\'
+                    OR u.email ILIKE $3 ESCAPE '\'
+                )
+                AND ($4::text IS NULL OR r.name = $4)
+                ORDER BY
+                    CASE WHEN $5 = 'name' AND $6 = 'asc' THEN u.name END ASC,
+                    CASE WHEN $5 = 'name' AND $6 = 'desc' THEN u.name END DESC,
+                    CASE WHEN $5 = 'email' AND $6 = 'asc' THEN u.email END ASC,
+                    CASE WHEN $5 = 'email' AND $6 = 'desc' THEN u.email END DESC,
+                    CASE WHEN $6 = 'asc' THEN u.created_at END ASC,
+                    u.created_at DESC,
+                    u.user_id
+                LIMIT $1
+                OFFSET $2
             "#,
+            limit,
+            offset,
+            query.as_deref().map(like_pattern),
+            role.as_ref().map(AsRef::<str>::as_ref),
+            sort.as_ref(),
+            order.as_ref(),
+            status.as_ref(),
         )
         .fetch_all(&mut *conn)
         .await
-        .map_err(AppError::SpecificOperationError)?
-        .into_iter()
-        .filter_map(|row| User::try_from(row).ok())
-        .collect();
-        Ok(users)
+        .map_err(AppError::SpecificOperationError)?;
+
+        let total = rows.first().map(|r| r.total).unwrap_or_default();
+        let items = rows
+            .into_iter()
+            .map(|row| User::try_from(UserRow::from(row)))
+            .collect::<AppResult<_>>()?;
+
+        Ok(PaginatedList {
+            total,
+            limit,
+            offset,
+            items,
+        })
+    }
+
+    async fn find_by_email(&self, email: &str) -> AppResult<Option<User>> {
+        let mut conn = self.source.acquire().await?;
+        let row = sqlx::query_as!(
+            UserRow,
+            r#"
+                SELECT
+                    u.user_id,
+                    u.name,
+                    u.email,
+                    r.name as role_name,
+                    u.created_at,
+                    u.updated_at
+                FROM users AS u
+                    INNER JOIN roles AS r USING(role_id)
+                WHERE u.email = $1 AND u.deactivated_at IS NULL
+            "#,
+            email,
+        )
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(AppError::SpecificOperationError)?;
+        row.map(User::try_from).transpose()
     }
 
     async fn find_current_user(&self, current_user_id: UserId) -> AppResult<Option<User>> {
@@ -115,7 +231,7 @@ impl<'t, 'm> UserRepository for UserRepositoryImpl<'t, 'm> {
                     u.updated_at
                 FROM users AS u
                     INNER JOIN roles AS r USING(role_id)
-                WHERE u.user_id = $1
+                WHERE u.user_id = $1 AND u.deactivated_at IS NULL
             "#,
             current_user_id as _,
         )
@@ -128,52 +244,77 @@ impl<'t, 'm> UserRepository for UserRepositoryImpl<'t, 'm> {
         }
     }
 
-    async fn find_password_hash_by_email(&self, email: &str) -> AppResult<(UserId, String)> {
+    async fn find_password_hash_by_user_id(&self, user_id: UserId) -> AppResult<String> {
         let mut conn = self.source.acquire().await?;
-        let UserItem {
-            user_id,
-            password_hash,
-        } = sqlx::query_as!(
-            UserItem,
+        let res = sqlx::query!(
             r#"
-                SELECT user_id, password_hash FROM users
-                WHERE email = $1;
+                SELECT password_hash FROM users WHERE user_id = $1;
             "#,
-            email,
+            user_id as _
         )
         .fetch_one(&mut *conn)
         .await
         .map_err(AppError::SpecificOperationError)?;
-        Ok((user_id, password_hash))
+        Ok(res.password_hash)
     }
 
-    async fn find_password_hash_by_user_id(&self, user_id: UserId) -> AppResult<String> {
+    async fn update_email(&self, event: UpdateUserEmail) -> AppResult<()> {
         let mut conn = self.source.acquire().await?;
         let res = sqlx::query!(
             r#"
-                SELECT password_hash FROM users WHERE user_id = $1;
+                UPDATE users SET email = $2 WHERE user_id = $1;
             "#,
-            user_id as _
+            event.user_id as _,
+            event.email.as_ref()
         )
-        .fetch_one(&mut *conn)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| map_email_conflict(e, &event.email))?;
+        if res.rows_affected() < 1 {
+            return Err(AppError::EntityNotFound(
+                Message::new(MessageKey::UserNotFound).arg(event.user_id),
+            ));
+        }
+        Ok(())
+    }
+
+    async fn update_name(&self, event: UpdateUserName) -> AppResult<()> {
+        let mut conn = self.source.acquire().await?;
+        let res = sqlx::query!(
+            r#"
+                UPDATE users SET name = $2 WHERE user_id = $1;
+            "#,
+            event.user_id as _,
+            event.name.as_ref()
+        )
+        .execute(&mut *conn)
         .await
         .map_err(AppError::SpecificOperationError)?;
-        Ok(res.password_hash)
+        if res.rows_affected() < 1 {
+            return Err(AppError::EntityNotFound(
+                Message::new(MessageKey::UserNotFound).arg(event.user_id),
+            ));
+        }
+        Ok(())
     }
 
-    async fn update_password(&self, event: UpdateUserPassword) -> AppResult<()> {
+    async fn update_password_hash(&self, event: UpdatePasswordHash) -> AppResult<()> {
         let mut conn = self.source.acquire().await?;
-        let password_hash = hash_password(&event.new_password)?;
-        sqlx::query!(
+        let res = sqlx::query!(
             r#"
                 UPDATE users SET password_hash = $2 WHERE user_id = $1;
             "#,
             event.user_id as _,
-            password_hash,
+            event.password_hash
         )
         .execute(&mut *conn)
         .await
         .map_err(AppError::SpecificOperationError)?;
+        if res.rows_affected() < 1 {
+            return Err(AppError::EntityNotFound(
+                Message::new(MessageKey::UserNotFound).arg(event.user_id),
+            ));
+        }
         Ok(())
     }
 
@@ -194,31 +335,78 @@ impl<'t, 'm> UserRepository for UserRepositoryImpl<'t, 'm> {
         .await
         .map_err(AppError::SpecificOperationError)?;
         if res.rows_affected() < 1 {
-            return Err(AppError::EntityNotFound("Specified user not found".into()));
+            return Err(AppError::EntityNotFound(
+                Message::new(MessageKey::UserNotFound).arg(event.user_id),
+            ));
         }
         Ok(())
     }
 }
 
-fn hash_password(password: &str) -> AppResult<String> {
-    bcrypt::hash(password, bcrypt::DEFAULT_COST).map_err(AppError::from)
+// users.email の一意制約に違反した場合は、他のユーザーが使っているアドレスとして扱う
+fn map_email_conflict(error: sqlx::Error, email: &UserEmail) -> AppError {
+    match &error {
+        sqlx::Error::Database(e) if e.is_unique_violation() => {
+            AppError::Conflict(Message::new(MessageKey::EmailAlreadyInUse).arg(email))
+        }
+        _ => AppError::SpecificOperationError(error),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::UserRepositoryImpl;
+    use crate::{
+        database::ConnectionPool, jwt::JwtKeys, repository::jwt_auth::JwtAuthRepositoryImpl,
+        unit_of_work::UnitOfWorkScopeImpl,
+    };
+    use chrono::{Duration, Utc};
     use kernel::{
+        mail::MockMailSender,
         model::{
+            auth::LoginAttemptKey,
             id::UserId,
+            list::SortOrder,
             role::Role,
             user::{
-                User,
-                event::{CreateUser, DeleteUser, UpdateUserPassword, UpdateUserRole},
+                User, UserListOptions, UserSortKey, UserStatus,
+                event::{
+                    CreateUser, DeactivateUser, DeleteUser, UpdatePasswordHash, UpdateUserRole,
+                },
             },
         },
-        repository::user::UserRepository,
+        password::MockPasswordHasher,
+        repository::{auth::AuthRepository, user::UserRepository},
+        use_case::user::{UserUseCase, UserUseCaseImpl},
     };
-    use std::str::FromStr;
+    use shared::{
+        config::{JwtAlgorithm, JwtConfig},
+        error::AppError,
+    };
+    use std::{str::FromStr, sync::Arc};
+
+    // セッションの失効も PostgreSQL で完結するよう、JWT のバックエンドを使う
+    fn init_use_case(pool: sqlx::PgPool) -> UserUseCaseImpl {
+        let keys = JwtKeys::new(&JwtConfig {
+            algorithm: JwtAlgorithm::HS256,
+            signing_key: "secret".into(),
+            verifying_key: "secret".into(),
+        })
+        .unwrap();
+        UserUseCaseImpl::new(
+            Arc::new(UnitOfWorkScopeImpl::new_with_jwt_keys(
+                Arc::new(ConnectionPool::from(pool)),
+                Arc::new(keys),
+                std::env::var("AUTH_TOKEN_TTL")
+                    .unwrap()
+                    .parse::<u64>()
+                    .unwrap(),
+            )),
+            Arc::new(MockPasswordHasher::new()),
+            Arc::new(MockMailSender::new()),
+            3600,
+        )
+    }
 
     #[sqlx::test(fixtures("common"))]
     async fn test_find_current_user(pool: sqlx::PgPool) -> anyhow::Result<()> {
@@ -248,15 +436,18 @@ mod tests {
             email: "test@example.com".parse().unwrap(),
             password: "dummy".into(),
         };
-        let user = repo.create(event).await?;
+        let user = repo.create(event, "dummy_hash".into()).await?;
+        assert_eq!(
+            repo.find_password_hash_by_user_id(user.id()).await?,
+            "dummy_hash"
+        );
 
         {
-            let event = UpdateUserPassword {
+            let event = UpdatePasswordHash {
                 user_id: user.id(),
-                current_password: "dummy".into(),
-                new_password: "new_password".into(),
+                password_hash: "new_hash".into(),
             };
-            repo.update_password(event).await?;
+            repo.update_password_hash(event).await?;
 
             let event = UpdateUserRole {
                 user_id: user.id(),
@@ -268,8 +459,13 @@ mod tests {
         let user_found = repo.find_current_user(user.id()).await?;
         assert_eq!(user_found.unwrap().id(), user.id());
 
-        let users = repo.find_all().await?;
-        assert!(!users.is_empty());
+        let users = repo
+            .find_all(UserListOptions {
+                limit: 10,
+                ..Default::default()
+            })
+            .await?;
+        assert!(!users.items.is_empty());
 
         {
             let event = DeleteUser { user_id: user.id() };
@@ -281,4 +477,295 @@ mod tests {
 
         Ok(())
     }
+
+    #[sqlx::test(fixtures("common"))]
+    async fn test_update_password_hash(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = UserRepositoryImpl::new(pool);
+
+        let user = repo
+            .find_by_email("eleazar.fig@example.com")
+            .await?
+            .unwrap();
+        assert!(repo.find_by_email("nobody@example.com").await?.is_none());
+
+        repo.update_password_hash(UpdatePasswordHash {
+            user_id: user.id(),
+            password_hash: "reset_hash".into(),
+        })
+        .await?;
+        let password_hash = repo.find_password_hash_by_user_id(user.id()).await?;
+        assert_eq!(password_hash, "reset_hash");
+
+        let res = repo
+            .update_password_hash(UpdatePasswordHash {
+                user_id: UserId::new(),
+                password_hash: "reset_hash".into(),
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common"))]
+    async fn test_deactivate_user(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = UserRepositoryImpl::new(pool);
+
+        let event = CreateUser {
+            name: "Test".parse().unwrap(),
+            email: "test@example.com".parse().unwrap(),
+            password: "dummy".into(),
+        };
+        let user = repo.create(event, "dummy_hash".into()).await?;
+
+        repo.deactivate(DeactivateUser { user_id: user.id() })
+            .await?;
+        assert!(repo.find_current_user(user.id()).await?.is_none());
+        assert!(repo.find_by_email("test@example.com").await?.is_none());
+        let users = repo
+            .find_all(UserListOptions {
+                limit: 10,
+                ..Default::default()
+            })
+            .await?;
+        assert!(users.items.iter().all(|u| u.id() != user.id()));
+
+        // 状態を指定すれば、無効化済みのユーザーも一覧に含められる
+        for status in [UserStatus::Deactivated, UserStatus::All] {
+            let users = repo
+                .find_all(UserListOptions {
+                    limit: 10,
+                    status,
+                    ..Default::default()
+                })
+                .await?;
+            assert!(users.items.iter().any(|u| u.id() == user.id()));
+        }
+        let users = repo
+            .find_all(UserListOptions {
+                limit: 10,
+                status: UserStatus::Deactivated,
+                ..Default::default()
+            })
+            .await?;
+        assert_eq!(users.total, 1);
+
+        // 無効化済みのユーザーは再度無効化できない
+        let res = repo.deactivate(DeactivateUser { user_id: user.id() }).await;
+        assert!(matches!(res, Err(AppError::EntityNotFound(_))));
+
+        repo.delete(DeleteUser { user_id: user.id() }).await?;
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common"))]
+    async fn test_find_all_users(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = UserRepositoryImpl::new(pool);
+
+        for (name, email) in [
+            ("Alice Smith", "alice@example.com"),
+            ("Bob Jones", "bob@example.org"),
+        ] {
+            let event = CreateUser {
+                name: name.parse()?,
+                email: email.parse()?,
+                password: "dummy".into(),
+            };
+            repo.create(event, "dummy_hash".into()).await?;
+        }
+
+        // 名前の昇順で 2 件ずつ取得する
+        let users = repo
+            .find_all(UserListOptions {
+                limit: 2,
+                sort: UserSortKey::Name,
+                order: SortOrder::Asc,
+                ..Default::default()
+            })
+            .await?;
+        assert_eq!(users.total, 3);
+        let names = users
+            .items
+            .iter()
+            .map(|u| u.name().as_ref().as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["Alice Smith", "Bob Jones"]);
+
+        // メールアドレスの一部でも検索できる
+        let users = repo
+            .find_all(UserListOptions {
+                limit: 10,
+                query: Some("EXAMPLE.ORG".into()),
+                ..Default::default()
+            })
+            .await?;
+        assert_eq!(users.total, 1);
+        assert_eq!(users.items[0].name().as_ref(), "Bob Jones");
+
+        // 検索語に含まれる LIKE のワイルドカードは文字どおりに扱う
+        for query in ["%", "_", "\\"] {
+            let users = repo
+                .find_all(UserListOptions {
+                    limit: 10,
+                    query: Some(query.into()),
+                    ..Default::default()
+                })
+                .await?;
+            assert_eq!(users.total, 0);
+        }
+
+        let users = repo
+            .find_all(UserListOptions {
+                limit: 10,
+                role: Some(Role::Admin),
+                ..Default::default()
+            })
+            .await?;
+        assert_eq!(users.total, 1);
+        assert_eq!(users.items[0].role(), &Role::Admin);
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common"))]
+    async fn test_keep_last_admin(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let repo = UserRepositoryImpl::new(pool.clone());
+        let use_case = init_use_case(pool);
+        let admin_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+
+        // 管理者が一人しかいない間は、降格も無効化もできない
+        let res = use_case
+            .change_role(UpdateUserRole {
+                user_id: admin_id,
+                role: Role::Librarian,
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+        let res = use_case
+            .deactivate_user(DeactivateUser { user_id: admin_id })
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+        assert_eq!(repo.count_by_role(&Role::Admin).await?, 1);
+
+        // 他のユーザーを管理者にすれば、元の管理者は降格できる
+        let event = CreateUser {
+            name: "Test".parse().unwrap(),
+            email: "test@example.com".parse().unwrap(),
+            password: "dummy".into(),
+        };
+        let user = repo.create(event, "dummy_hash".into()).await?;
+        use_case
+            .change_role(UpdateUserRole {
+                user_id: user.id(),
+                role: Role::Admin,
+            })
+            .await?;
+        use_case
+            .change_role(UpdateUserRole {
+                user_id: admin_id,
+                role: Role::User,
+            })
+            .await?;
+
+        let res = use_case
+            .change_role(UpdateUserRole {
+                user_id: user.id(),
+                role: Role::User,
+            })
+            .await;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+        assert_eq!(repo.count_by_role(&Role::Admin).await?, 1);
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common"))]
+    async fn test_change_role_retries_serialization_failure(
+        pool: sqlx::PgPool,
+    ) -> anyhow::Result<()> {
+        let repo = UserRepositoryImpl::new(pool.clone());
+        let use_case = init_use_case(pool.clone());
+        let admin_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+        let event = CreateUser {
+            name: "Test".parse().unwrap(),
+            email: "test@example.com".parse().unwrap(),
+            password: "dummy".into(),
+        };
+        let user = repo.create(event, "dummy_hash".into()).await?;
+        use_case
+            .change_role(UpdateUserRole {
+                user_id: user.id(),
+                role: Role::Admin,
+            })
+            .await?;
+
+        // 別のトランザクションが一方の管理者を降格し、もう一方の行も更新したまま確定を遅らせる
+        let mut tx = pool.begin().await?;
+        sqlx::query("SET TRANSACTION ISOLATION LEVEL SERIALIZABLE")
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            "UPDATE users SET role_id = (SELECT role_id FROM roles WHERE name = 'User') WHERE user_id = $1",
+        )
+        .bind(admin_id)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query("UPDATE users SET updated_at = CURRENT_TIMESTAMP(3) WHERE user_id = $1")
+            .bind(user.id())
+            .execute(&mut *tx)
+            .await?;
+
+        let user_id = user.id();
+        let demotion = tokio::spawn(async move {
+            use_case
+                .change_role(UpdateUserRole {
+                    user_id,
+                    role: Role::User,
+                })
+                .await
+        });
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        tx.commit().await?;
+
+        // 競合して失敗した降格をやり直し、最後の管理者の降格として拒否する
+        let res = demotion.await?;
+        assert!(matches!(res, Err(AppError::UnprocessableEntity(_))));
+        assert_eq!(repo.count_by_role(&Role::Admin).await?, 1);
+
+        Ok(())
+    }
+
+    #[sqlx::test(fixtures("common"))]
+    async fn test_unlock_user(pool: sqlx::PgPool) -> anyhow::Result<()> {
+        let keys = JwtKeys::new(&JwtConfig {
+            algorithm: JwtAlgorithm::HS256,
+            signing_key: "secret".into(),
+            verifying_key: "secret".into(),
+        })?;
+        let auth_repo = JwtAuthRepositoryImpl::new(pool.clone(), Arc::new(keys), 3600);
+        let use_case = init_use_case(pool);
+        let admin_id = UserId::from_str("5b4c96ac-316a-4bee-8e69-cac5eb84ff4c")?;
+
+        let email = LoginAttemptKey::Email("eleazar.fig@example.com".into());
+        let ip = LoginAttemptKey::IpAddress("203.0.113.5".into());
+        let other_ip = LoginAttemptKey::IpAddress("198.51.100.7".into());
+        let until = Utc::now() + Duration::seconds(60);
+        for key in [&email, &ip, &other_ip] {
+            auth_repo.record_login_failure(key, 3600).await?;
+            auth_repo.lock_login(key, until).await?;
+        }
+        auth_repo
+            .record_login_failure_ip("eleazar.fig@example.com", "203.0.113.5", 3600)
+            .await?;
+
+        use_case.unlock_user(admin_id).await?;
+
+        // このアカウントへの試行でロックされた接続元だけを解除する
+        assert!(auth_repo.find_login_lockout(&email).await?.is_none());
+        assert!(auth_repo.find_login_lockout(&ip).await?.is_none());
+        assert!(auth_repo.find_login_lockout(&other_ip).await?.is_some());
+
+        Ok(())
+    }
 }
@@ -1,6 +1,11 @@
-use crate::{database::ConnectionPool, redis::RedisClient};
+use crate::{
+    database::ConnectionPool,
+    jwt::{JwtKeys, PendingRevocations},
+    redis::RedisClient,
+    repository::{auth::AuthRepositoryImpl, jwt_auth::JwtAuthRepositoryImpl},
+};
 use async_trait::async_trait;
-use kernel::unit_of_work::UnitOfWork;
+use kernel::{repository::auth::AuthRepository, unit_of_work::UnitOfWork};
 use shared::error::{AppError, AppResult};
 use sqlx::{Postgres, Transaction};
 use std::sync::Arc;
@@ -19,7 +24,7 @@ macro_rules! impl_uow_scope {
                     .map_err(shared::error::AppError::TransactionError)?;
                 Ok(Box::new(crate::unit_of_work::UnitOfWorkImpl::new(
                     tx,
-                    self.kv.clone(),
+                    self.auth.clone(),
                     self.ttl,
                 )))
             }
@@ -39,7 +44,7 @@ macro_rules! impl_uow_scope {
                     .map_err(shared::error::AppError::SpecificOperationError)?;
                 Ok(Box::new(crate::unit_of_work::UnitOfWorkImpl::new(
                     tx,
-                    self.kv.clone(),
+                    self.auth.clone(),
                     self.ttl,
                 )))
             }
@@ -53,30 +58,62 @@ pub mod checkout;
 pub mod health;
 pub mod user;
 
+// 認証に関わる状態の保存先。JWT の場合は PostgreSQL に保存し、Redis は使わない
+#[derive(Clone)]
+pub enum AuthStore {
+    Redis(Arc<RedisClient>),
+    Jwt(Arc<JwtKeys>),
+}
+
 pub struct UnitOfWorkScopeImpl {
     db: Arc<ConnectionPool>,
-    kv: Arc<RedisClient>,
+    auth: AuthStore,
     ttl: u64,
 }
 
 impl UnitOfWorkScopeImpl {
     pub fn new(db: Arc<ConnectionPool>, kv: Arc<RedisClient>, ttl: u64) -> Self {
-        Self { db, kv, ttl }
+        Self {
+            db,
+            auth: AuthStore::Redis(kv),
+            ttl,
+        }
+    }
+
+    // アクセストークンを Redis ではなく JWT で発行する
+    pub fn new_with_jwt_keys(db: Arc<ConnectionPool>, keys: Arc<JwtKeys>, ttl: u64) -> Self {
+        Self {
+            db,
+            auth: AuthStore::Jwt(keys),
+            ttl,
+        }
     }
 }
 
 pub struct UnitOfWorkImpl<'a> {
     tx: Mutex<Transaction<'a, Postgres>>,
-    kv: Arc<RedisClient>,
+    auth: AuthStore,
     ttl: u64,
+    revoked: PendingRevocations,
 }
 
 impl<'a> UnitOfWorkImpl<'a> {
-    pub fn new(tx: Transaction<'a, Postgres>, kv: Arc<RedisClient>, ttl: u64) -> Self {
+    pub fn new(tx: Transaction<'a, Postgres>, auth: AuthStore, ttl: u64) -> Self {
         Self {
             tx: Mutex::new(tx),
-            kv,
+            auth,
             ttl,
+            revoked: PendingRevocations::default(),
+        }
+    }
+
+    fn auth_repository_impl(&self) -> Box<dyn AuthRepository + '_> {
+        match &self.auth {
+            AuthStore::Redis(kv) => Box::new(AuthRepositoryImpl::new(kv.clone(), self.ttl)),
+            AuthStore::Jwt(keys) => Box::new(
+                JwtAuthRepositoryImpl::new(&self.tx, keys.clone(), self.ttl)
+                    .with_pending(&self.revoked),
+            ),
         }
     }
 }
@@ -84,11 +121,17 @@ impl<'a> UnitOfWorkImpl<'a> {
 #[async_trait]
 impl<'a> UnitOfWork for UnitOfWorkImpl<'a> {
     async fn commit(self: Box<Self>) -> AppResult<()> {
-        self.tx
-            .into_inner()
+        let Self {
+            tx, auth, revoked, ..
+        } = *self;
+        tx.into_inner()
             .commit()
             .await
-            .map_err(AppError::TransactionError)
+            .map_err(AppError::TransactionError)?;
+        if let AuthStore::Jwt(keys) = auth {
+            revoked.apply(&keys.revoked);
+        }
+        Ok(())
     }
 
     async fn rollback(self: Box<Self>) -> AppResult<()> {
@@ -1,17 +1,31 @@
 use crate::{
-    repository::{auth::AuthRepositoryImpl, user::UserRepositoryImpl},
+    repository::{
+        mfa::MfaRepositoryImpl, personal_access_token::PersonalAccessTokenRepositoryImpl,
+        user::UserRepositoryImpl,
+    },
     unit_of_work::UnitOfWorkImpl,
 };
 use async_trait::async_trait;
 use kernel::{
-    repository::{auth::AuthRepository, user::UserRepository},
+    repository::{
+        auth::AuthRepository, mfa::MfaRepository,
+        personal_access_token::PersonalAccessTokenRepository, user::UserRepository,
+    },
     unit_of_work::auth::{AuthUnitOfWork, AuthUnitOfWorkScope},
 };
 
 #[async_trait]
 impl<'a> AuthUnitOfWork for UnitOfWorkImpl<'a> {
     fn auth_repository(&self) -> Box<dyn AuthRepository + '_> {
-        Box::new(AuthRepositoryImpl::new(self.kv.clone(), self.ttl))
+        self.auth_repository_impl()
+    }
+
+    fn mfa_repository(&self) -> Box<dyn MfaRepository + '_> {
+        Box::new(MfaRepositoryImpl::new(&self.tx))
+    }
+
+    fn personal_access_token_repository(&self) -> Box<dyn PersonalAccessTokenRepository + '_> {
+        Box::new(PersonalAccessTokenRepositoryImpl::new(&self.tx))
     }
 
     fn user_repository(&self) -> Box<dyn UserRepository + '_> {
@@ -1,7 +1,10 @@
-use crate::{repository::book::BookRepositoryImpl, unit_of_work::UnitOfWorkImpl};
+use crate::{
+    repository::{book::BookRepositoryImpl, user::UserRepositoryImpl},
+    unit_of_work::UnitOfWorkImpl,
+};
 use async_trait::async_trait;
 use kernel::{
-    repository::book::BookRepository,
+    repository::{book::BookRepository, user::UserRepository},
     unit_of_work::book::{BookUnitOfWork, BookUnitOfWorkScope},
 };
 
@@ -10,6 +13,10 @@ impl<'a> BookUnitOfWork for UnitOfWorkImpl<'a> {
     fn book_repository(&self) -> Box<dyn BookRepository + '_> {
         Box::new(BookRepositoryImpl::new(&self.tx))
     }
+
+    fn user_repository(&self) -> Box<dyn UserRepository + '_> {
+        Box::new(UserRepositoryImpl::new(&self.tx))
+    }
 }
 
 impl_uow_scope!(BookUnitOfWorkScope, BookUnitOfWork);
@@ -1,10 +1,16 @@
 use crate::{
-    repository::{book::BookRepositoryImpl, checkout::CheckoutRepositoryImpl},
+    repository::{
+        book::BookRepositoryImpl, checkout::CheckoutRepositoryImpl, hold::HoldRepositoryImpl,
+        loan_policy::LoanPolicyRepositoryImpl, user::UserRepositoryImpl,
+    },
     unit_of_work::UnitOfWorkImpl,
 };
 use async_trait::async_trait;
 use kernel::{
-    repository::{book::BookRepository, checkout::CheckoutRepository},
+    repository::{
+        book::BookRepository, checkout::CheckoutRepository, hold::HoldRepository,
+        loan_policy::LoanPolicyRepository, user::UserRepository,
+    },
     unit_of_work::checkout::{CheckoutUnitOfWork, CheckoutUnitOfWorkScope},
 };
 
@@ -17,6 +23,18 @@ impl<'a> CheckoutUnitOfWork for UnitOfWorkImpl<'a> {
     fn book_repository(&self) -> Box<dyn BookRepository + '_> {
         Box::new(BookRepositoryImpl::new(&self.tx))
     }
+
+    fn hold_repository(&self) -> Box<dyn HoldRepository + '_> {
+        Box::new(HoldRepositoryImpl::new(&self.tx))
+    }
+
+    fn loan_policy_repository(&self) -> Box<dyn LoanPolicyRepository + '_> {
+        Box::new(LoanPolicyRepositoryImpl::new(&self.tx))
+    }
+
+    fn user_repository(&self) -> Box<dyn UserRepository + '_> {
+        Box::new(UserRepositoryImpl::new(&self.tx))
+    }
 }
 
 impl_uow_scope!(CheckoutUnitOfWorkScope, CheckoutUnitOfWork);
@@ -1,19 +1,32 @@
 use crate::{
-    repository::{checkout::CheckoutRepositoryImpl, user::UserRepositoryImpl},
+    repository::{
+        checkout::CheckoutRepositoryImpl, hold::HoldRepositoryImpl, user::UserRepositoryImpl,
+    },
     unit_of_work::UnitOfWorkImpl,
 };
 use async_trait::async_trait;
 use kernel::{
-    repository::{checkout::CheckoutRepository, user::UserRepository},
+    repository::{
+        auth::AuthRepository, checkout::CheckoutRepository, hold::HoldRepository,
+        user::UserRepository,
+    },
     unit_of_work::user::{UserUnitOfWork, UserUnitOfWorkScope},
 };
 
 #[async_trait]
 impl<'a> UserUnitOfWork for UnitOfWorkImpl<'a> {
+    fn auth_repository(&self) -> Box<dyn AuthRepository + '_> {
+        self.auth_repository_impl()
+    }
+
     fn checkout_repository(&self) -> Box<dyn CheckoutRepository + '_> {
         Box::new(CheckoutRepositoryImpl::new(&self.tx))
     }
 
+    fn hold_repository(&self) -> Box<dyn HoldRepository + '_> {
+        Box::new(HoldRepositoryImpl::new(&self.tx))
+    }
+
     fn user_repository(&self) -> Box<dyn UserRepository + '_> {
         Box::new(UserRepositoryImpl::new(&self.tx))
     }
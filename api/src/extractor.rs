@@ -2,32 +2,49 @@ use axum::{
     Json, RequestPartsExt, async_trait,
     body::Body,
     extract::{
-        FromRequest, FromRequestParts, Query,
+        ConnectInfo, FromRequest, FromRequestParts, Query,
         rejection::{JsonRejection, QueryRejection},
     },
-    http::{Request, request::Parts},
+    http::{Method, Request, header, request::Parts},
 };
 use axum_extra::{
     TypedHeader,
     headers::{Authorization, authorization::Bearer},
 };
 use garde::Validate;
-use kernel::model::{auth::AccessToken, id::UserId, role::Role, user::User};
+use kernel::model::{
+    auth::AccessToken, id::UserId, password::PasswordContext, personal_access_token::TokenScope,
+    role::Permission, session::SessionClient, user::User,
+};
 use registry::AppRegistry;
 use serde::de::DeserializeOwned;
-use shared::error::AppError;
+use shared::{
+    config::ProxyConfig,
+    error::{AppError, AppResult},
+};
+use std::{
+    convert::Infallible,
+    marker::PhantomData,
+    net::{IpAddr, SocketAddr},
+    ops::Deref,
+};
 
 pub struct AuthorizedUser {
     pub access_token: AccessToken,
     pub user: User,
+    // パーソナルアクセストークンで認証した場合のみ、許可されたスコープを持つ
+    pub scopes: Option<Vec<TokenScope>>,
 }
 
 impl AuthorizedUser {
     pub fn id(&self) -> UserId {
         self.user.id()
     }
-    pub fn is_admin(&self) -> bool {
-        self.user.role() == &Role::Admin
+    pub fn has_permission(&self, permission: Permission) -> bool {
+        self.user.role().has_permission(permission)
+    }
+    pub fn is_personal_access_token(&self) -> bool {
+        self.scopes.is_some()
     }
 }
 
@@ -45,33 +62,258 @@ impl FromRequestParts<AppRegistry> for AuthorizedUser {
             .map_err(|_| AppError::UnauthorizedError)?;
         let access_token = AccessToken(bearer.token().to_string());
 
-        let user = registry
+        if !access_token.is_personal_access_token() {
+            let user = registry
+                .auth_use_case()
+                .find_authorized_user(&access_token)
+                .await?;
+            return Ok(Self {
+                access_token,
+                user,
+                scopes: None,
+            });
+        }
+
+        let (user, scopes) = registry
             .auth_use_case()
-            .find_authorized_user(&access_token)
+            .find_personal_access_token_user(&access_token)
             .await?;
+        // 参照系のリクエストには read、それ以外には write のスコープを要求する
+        let required = match parts.method {
+            Method::GET | Method::HEAD => TokenScope::Read,
+            _ => TokenScope::Write,
+        };
+        if !scopes.contains(&required) {
+            return Err(AppError::ForbiddenOperation);
+        }
+
+        Ok(Self {
+            access_token,
+            user,
+            scopes: Some(scopes),
+        })
+    }
+}
+
+// ハンドラーが要求する権限を型で宣言するためのマーカー
+pub trait RequiredPermission {
+    const PERMISSION: Permission;
+}
+
+macro_rules! required_permissions {
+    ($($name:ident),* $(,)?) => {
+        $(
+            pub struct $name;
+            impl RequiredPermission for $name {
+                const PERMISSION: Permission = Permission::$name;
+            }
+        )*
+    };
+}
+
+pub mod permission {
+    use super::{Permission, RequiredPermission};
+
+    required_permissions!(
+        ManageUsers,
+        ManageAllBooks,
+        CheckoutOnBehalf,
+        ViewReports,
+        ManageLoanPolicies,
+    );
+}
+
+// 認証に加えて、ロールに P の権限が付与されていることを確認する
+pub struct PermittedUser<P> {
+    user: AuthorizedUser,
+    _permission: PhantomData<P>,
+}
+
+impl<P> Deref for PermittedUser<P> {
+    type Target = AuthorizedUser;
+
+    fn deref(&self) -> &Self::Target {
+        &self.user
+    }
+}
+
+#[async_trait]
+impl<P> FromRequestParts<AppRegistry> for PermittedUser<P>
+where
+    P: RequiredPermission,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        registry: &AppRegistry,
+    ) -> Result<Self, Self::Rejection> {
+        let user = AuthorizedUser::from_request_parts(parts, registry).await?;
+        if !user.has_permission(P::PERMISSION) {
+            return Err(AppError::ForbiddenOperation);
+        }
+        Ok(Self {
+            user,
+            _permission: PhantomData,
+        })
+    }
+}
 
-        Ok(Self { access_token, user })
+// プロフィールやパスワード、セッション、二要素認証、アクセストークン自体など、
+// アカウントを管理する操作はログインしたセッションからのみ許可する
+pub struct SessionUser {
+    user: AuthorizedUser,
+}
+
+impl Deref for SessionUser {
+    type Target = AuthorizedUser;
+
+    fn deref(&self) -> &Self::Target {
+        &self.user
     }
 }
 
+#[async_trait]
+impl FromRequestParts<AppRegistry> for SessionUser {
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        registry: &AppRegistry,
+    ) -> Result<Self, Self::Rejection> {
+        let user = AuthorizedUser::from_request_parts(parts, registry).await?;
+        if user.is_personal_access_token() {
+            return Err(AppError::ForbiddenOperation);
+        }
+        Ok(Self { user })
+    }
+}
+
+// セッションへの記録やログインの試行回数の集計に使うクライアント情報
+pub struct ClientInfo(pub SessionClient);
+
+#[async_trait]
+impl FromRequestParts<AppRegistry> for ClientInfo {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        registry: &AppRegistry,
+    ) -> Result<Self, Self::Rejection> {
+        let device = parts
+            .headers
+            .get(header::USER_AGENT)
+            .and_then(|v| v.to_str().ok())
+            .map(ToString::to_string);
+        let peer = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip());
+        let forwarded_for = parts
+            .headers
+            .get("x-forwarded-for")
+            .and_then(|v| v.to_str().ok());
+        let ip_address = match (peer, forwarded_for) {
+            (Some(peer), Some(forwarded_for)) => {
+                Some(client_ip(peer, forwarded_for, &registry.proxy_config()))
+            }
+            (peer, _) => peer,
+        };
+        Ok(Self(SessionClient::new(
+            device,
+            ip_address.map(|ip| ip.to_string()),
+        )))
+    }
+}
+
+// 接続元が信頼するプロキシの場合に限り X-Forwarded-For をたどる。
+// 末尾から信頼するプロキシを読み飛ばし、最初に現れたそれ以外のアドレスを接続元とみなす
+fn client_ip(peer: IpAddr, forwarded_for: &str, proxy: &ProxyConfig) -> IpAddr {
+    if !proxy.is_trusted(peer) {
+        return peer;
+    }
+    let mut client = peer;
+    for ip in forwarded_for
+        .rsplit(',')
+        .map(|v| v.trim().parse::<IpAddr>())
+    {
+        let Ok(ip) = ip else {
+            break;
+        };
+        client = ip;
+        if !proxy.is_trusted(ip) {
+            break;
+        }
+    }
+    client
+}
+
+// 検証ルールが設定に依存する場合に、その設定をレジストリから取り出す
+pub trait ValidationContext {
+    fn from_registry(registry: &AppRegistry) -> Self;
+}
+
+impl ValidationContext for () {
+    fn from_registry(_registry: &AppRegistry) -> Self {}
+}
+
 pub struct ValidatedJson<T>(pub T);
 
 #[async_trait]
-impl<T, S> FromRequest<S> for ValidatedJson<T>
+impl<T> FromRequest<AppRegistry> for ValidatedJson<T>
 where
-    T: DeserializeOwned + Validate<Context = ()>,
-    S: Send + Sync,
-    Json<T>: FromRequest<S, Rejection = JsonRejection>,
+    T: DeserializeOwned + Validate,
+    T::Context: ValidationContext,
+    Json<T>: FromRequest<AppRegistry, Rejection = JsonRejection>,
 {
     type Rejection = AppError;
 
-    async fn from_request(req: Request<Body>, state: &S) -> Result<Self, Self::Rejection> {
-        let Json(value) = Json::<T>::from_request(req, state).await?;
-        value.validate(&())?;
+    async fn from_request(
+        req: Request<Body>,
+        registry: &AppRegistry,
+    ) -> Result<Self, Self::Rejection> {
+        let Json(value) = Json::<T>::from_request(req, registry).await?;
+        value.validate(&T::Context::from_registry(registry))?;
         Ok(ValidatedJson(value))
     }
 }
 
+// パスワードは本人の名前やメールアドレスと照合するため、パスワードを設定するユーザーが
+// 決まってから handler で PasswordContext を組み立てて検証する
+pub struct PasswordJson<T>(T);
+
+impl<T> PasswordJson<T>
+where
+    T: Validate<Context = PasswordContext>,
+{
+    // 検証前の本文。パスワードを設定するユーザーを特定するためだけに使う
+    pub fn unvalidated(&self) -> &T {
+        &self.0
+    }
+
+    pub fn validate(self, context: &PasswordContext) -> AppResult<T> {
+        self.0.validate(context)?;
+        Ok(self.0)
+    }
+}
+
+#[async_trait]
+impl<T> FromRequest<AppRegistry> for PasswordJson<T>
+where
+    T: DeserializeOwned,
+    Json<T>: FromRequest<AppRegistry, Rejection = JsonRejection>,
+{
+    type Rejection = AppError;
+
+    async fn from_request(
+        req: Request<Body>,
+        registry: &AppRegistry,
+    ) -> Result<Self, Self::Rejection> {
+        let Json(value) = Json::<T>::from_request(req, registry).await?;
+        Ok(PasswordJson(value))
+    }
+}
+
 pub struct ValidatedQuery<T>(pub T);
 
 #[async_trait]
@@ -2,4 +2,6 @@ pub mod auth;
 pub mod book;
 pub mod checkout;
 pub mod health;
+pub mod hold;
+pub mod loan_policy;
 pub mod user;
@@ -1,8 +1,16 @@
 use crate::{
-    extractor::AuthorizedUser,
-    model::auth::{AccessTokenResponse, LoginRequest},
+    extractor::{AuthorizedUser, ClientInfo, PasswordJson, ValidatedJson, ValidatedQuery},
+    model::auth::{
+        AccessTokenResponse, EmailVerificationConfirmRequest, LoginRequest, LoginResponse,
+        MfaLoginRequest, MfaLoginResponse, OidcCallbackQuery, PasswordResetConfirmRequest,
+        PasswordResetRequest, RefreshRequest,
+    },
+};
+use axum::{Json, extract::State, http::StatusCode, response::Redirect};
+use kernel::model::{
+    auth::{EmailVerificationToken, MfaChallengeToken, PasswordResetToken, RefreshToken},
+    password::PasswordContext,
 };
-use axum::{Json, extract::State, http::StatusCode};
 use registry::AppRegistry;
 use shared::error::AppResult;
 
@@ -13,29 +21,140 @@ use shared::error::AppResult;
         path="/auth/login",
         request_body = LoginRequest,
         responses(
-            (status = 200, description = "ログインに成功した場合。", body = AccessTokenResponse),
+            (status = 200, description = "ログインに成功した場合。二要素認証が必要な場合はトークンの代わりに challengeToken を返すため、/auth/login/mfa で確認コードを送信してください。", body = LoginResponse),
             (status = 400, description = "リクエストの内容に問題があった場合。"),
-            (status = 403, description = "ログイン認証が通らなかった場合。ユーザーIDないしはパスワードに誤りがある可能性があります。")
+            (status = 403, description = "ログイン認証が通らなかった場合。ユーザーIDないしはパスワードに誤りがある可能性があります。"),
+            (status = 429, description = "ログインの失敗が続き、メールアドレスまたは接続元が一時的にロックされている場合。Retry-After ヘッダーに再試行できるまでの秒数を返します。")
         )
     )
 )]
 #[tracing::instrument(
-    skip(registry, req),
+    skip(registry, client, req),
     fields(
         email_address = %req.email
     )
 )]
 pub async fn login(
     State(registry): State<AppRegistry>,
+    ClientInfo(client): ClientInfo,
     Json(req): Json<LoginRequest>,
+) -> AppResult<Json<LoginResponse>> {
+    registry
+        .auth_use_case()
+        .login(&req.email, &req.password, client)
+        .await
+        .map(LoginResponse::from)
+        .map(Json)
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        post,
+        path="/auth/login/mfa",
+        request_body = MfaLoginRequest,
+        responses(
+            (status = 200, description = "確認コードないしはリカバリーコードが正しく、ログインに成功した場合。ログイン時に二要素認証の登録を済ませた場合は recoveryCodes も返します。", body = MfaLoginResponse),
+            (status = 400, description = "リクエストの内容に問題があった場合。"),
+            (status = 403, description = "チャレンジが無効か、コードが誤っていた場合。チャレンジは一度しか使えないため、パスワードの入力からやり直してください。"),
+        )
+    )
+)]
+#[tracing::instrument(skip(registry, client, req))]
+pub async fn login_mfa(
+    State(registry): State<AppRegistry>,
+    ClientInfo(client): ClientInfo,
+    ValidatedJson(req): ValidatedJson<MfaLoginRequest>,
+) -> AppResult<Json<MfaLoginResponse>> {
+    let (user_id, access_token, refresh_token, recovery_codes) = registry
+        .auth_use_case()
+        .verify_mfa(MfaChallengeToken(req.challenge_token), &req.code, client)
+        .await?;
+    Ok(Json(MfaLoginResponse {
+        user_id,
+        access_token: access_token.0,
+        refresh_token: refresh_token.0,
+        recovery_codes,
+    }))
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        get,
+        path="/auth/oidc/login",
+        responses(
+            (status = 303, description = "ID プロバイダーの認可エンドポイントにリダイレクトする場合。"),
+            (status = 404, description = "シングルサインオンが設定されていない場合。"),
+            (status = 502, description = "ID プロバイダーのディスカバリー文書を取得できなかった場合。"),
+        )
+    )
+)]
+#[tracing::instrument(skip(registry))]
+pub async fn oidc_login(State(registry): State<AppRegistry>) -> AppResult<Redirect> {
+    let url = registry.auth_use_case().start_oidc_login().await?;
+    Ok(Redirect::to(&url))
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        get,
+        path="/auth/oidc/callback",
+        params(
+            ("code" = String, Query, description = "ID プロバイダーが発行した認可コード"),
+            ("state" = String, Query, description = "/auth/oidc/login で払い出した state"),
+        ),
+        responses(
+            (status = 200, description = "ログインに成功した場合。二要素認証が必要な場合は /auth/login と同様に challengeToken を返します。", body = LoginResponse),
+            (status = 400, description = "認可コードないしは state が含まれていなかった場合。"),
+            (status = 403, description = "state が無効か、ID トークンの検証に失敗した場合。メールアドレスに一致するユーザーがおらず、自動登録も無効な場合も含みます。"),
+            (status = 404, description = "シングルサインオンが設定されていない場合。"),
+            (status = 502, description = "ID プロバイダーとの通信に失敗した場合。"),
+        )
+    )
+)]
+#[tracing::instrument(skip(registry, client, query))]
+pub async fn oidc_callback(
+    State(registry): State<AppRegistry>,
+    ClientInfo(client): ClientInfo,
+    ValidatedQuery(query): ValidatedQuery<OidcCallbackQuery>,
+) -> AppResult<Json<LoginResponse>> {
+    registry
+        .auth_use_case()
+        .oidc_login(&query.state, &query.code, client)
+        .await
+        .map(LoginResponse::from)
+        .map(Json)
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        post,
+        path="/auth/refresh",
+        request_body = RefreshRequest,
+        responses(
+            (status = 200, description = "トークンの更新に成功した場合。使用したリフレッシュトークンは以後使えません。", body = AccessTokenResponse),
+            (status = 400, description = "リクエストの内容に問題があった場合。"),
+            (status = 403, description = "リフレッシュトークンが無効か、既に使用済みだった場合。使用済みのトークンだった場合は同じログインに由来するセッションも失効します。")
+        )
+    )
+)]
+#[tracing::instrument(skip(registry, client, req))]
+pub async fn refresh(
+    State(registry): State<AppRegistry>,
+    ClientInfo(client): ClientInfo,
+    ValidatedJson(req): ValidatedJson<RefreshRequest>,
 ) -> AppResult<Json<AccessTokenResponse>> {
-    let (user_id, access_token) = registry
+    let (user_id, access_token, refresh_token) = registry
         .auth_use_case()
-        .login(&req.email, &req.password)
+        .refresh(RefreshToken(req.refresh_token), client)
         .await?;
     Ok(Json(AccessTokenResponse {
         user_id,
         access_token: access_token.0,
+        refresh_token: refresh_token.0,
     }))
 }
 
@@ -63,3 +182,88 @@ pub async fn logout(
     registry.auth_use_case().logout(user.access_token).await?;
     Ok(StatusCode::NO_CONTENT)
 }
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        post,
+        path="/auth/password-reset",
+        request_body = PasswordResetRequest,
+        responses(
+            (status = 202, description = "パスワード再設定の受付に成功した場合。登録されていないメールアドレスの場合も同様に返します。"),
+            (status = 400, description = "リクエストの内容に問題があった場合。"),
+        )
+    )
+)]
+#[tracing::instrument(skip(registry, req))]
+pub async fn request_password_reset(
+    State(registry): State<AppRegistry>,
+    ValidatedJson(req): ValidatedJson<PasswordResetRequest>,
+) -> AppResult<StatusCode> {
+    registry
+        .auth_use_case()
+        .request_password_reset(&req.email)
+        .await?;
+    Ok(StatusCode::ACCEPTED)
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        post,
+        path="/auth/password-reset/confirm",
+        request_body = PasswordResetConfirmRequest,
+        responses(
+            (status = 204, description = "パスワードの再設定に成功した場合。"),
+            (status = 400, description = "リクエストの内容に問題があるか、新しいパスワードが強度の要件を満たさない場合。"),
+            (status = 422, description = "トークンが無効か、有効期限が切れていた場合。"),
+        )
+    )
+)]
+#[tracing::instrument(skip(registry, body))]
+pub async fn confirm_password_reset(
+    State(registry): State<AppRegistry>,
+    body: PasswordJson<PasswordResetConfirmRequest>,
+) -> AppResult<StatusCode> {
+    let reset_token = PasswordResetToken(body.unvalidated().token.clone());
+    let user = registry
+        .auth_use_case()
+        .find_password_reset_user(&reset_token)
+        .await?;
+    let req = body.validate(&PasswordContext::new(
+        registry.password_policy(),
+        user.name().as_ref(),
+        user.email().as_ref(),
+    ))?;
+    registry
+        .auth_use_case()
+        .reset_password(PasswordResetToken(req.token), req.new_password)
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(
+        post,
+        path="/auth/email-verification/confirm",
+        request_body = EmailVerificationConfirmRequest,
+        responses(
+            (status = 204, description = "メールアドレスの変更が確定した場合。"),
+            (status = 400, description = "リクエストの内容に問題があった場合。"),
+            (status = 409, description = "確認までの間に、メールアドレスが他のユーザーに使われた場合。"),
+            (status = 422, description = "トークンが無効か、有効期限が切れていた場合。"),
+        )
+    )
+)]
+#[tracing::instrument(skip(registry, req))]
+pub async fn confirm_email_change(
+    State(registry): State<AppRegistry>,
+    ValidatedJson(req): ValidatedJson<EmailVerificationConfirmRequest>,
+) -> AppResult<StatusCode> {
+    registry
+        .user_use_case()
+        .confirm_email_change(EmailVerificationToken(req.token))
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
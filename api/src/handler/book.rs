@@ -1,8 +1,13 @@
 use crate::{
-    extractor::{AuthorizedUser, ValidatedJson, ValidatedQuery},
+    extractor::{
+        AuthorizedUser, PermittedUser, ValidatedJson, ValidatedQuery,
+        permission::{ManageAllBooks, ManageUsers},
+    },
     model::book::{
-        BookListQuery, BookResponse, CreateBookRequest, PaginatedBookResponse, UpdateBookRequest,
-        UpdateBookRequestWithIds,
+        BookChangeLogsResponse, BookListQuery, BookListQueryWithUserId, BookResponse,
+        CreateBookRequest, PaginatedBookResponse, TransferAllBooksRequestWithIds,
+        TransferredBooksResponse, UpdateBookOwnerRequest, UpdateBookOwnerRequestWithIds,
+        UpdateBookRequest, UpdateBookRequestWithIds,
     },
 };
 use axum::{
@@ -10,9 +15,15 @@ use axum::{
     extract::{Path, State},
     http::StatusCode,
 };
-use kernel::model::{book::event::DeleteBook, id::BookId};
+use kernel::model::{
+    book::event::DeleteBook,
+    id::{BookId, UserId},
+};
 use registry::AppRegistry;
-use shared::error::{AppError, AppResult};
+use shared::{
+    error::{AppError, AppResult},
+    i18n::{Message, MessageKey},
+};
 
 #[cfg_attr(
     debug_assertions,
@@ -57,23 +68,29 @@ pub async fn register_book(
         params(
             ("limit" = i64, Query, description = "一度に取得する蔵書数の上限値の指定"),
             ("offset" = i64, Query, description = "取得対象とする蔵書一覧の開始位置"),
+            ("q" = Option<String>, Query, description = "タイトル・著者名・ISBN・説明文を対象とした全文検索キーワード"),
+            ("author" = Option<String>, Query, description = "著者名（部分一致）による絞り込み"),
+            ("isbn" = Option<String>, Query, description = "ISBN（ハイフンを無視した完全一致）による絞り込み"),
+            ("owner" = Option<Uuid>, Query, description = "蔵書の所有者IDによる絞り込み"),
+            ("availableOnly" = Option<bool>, Query, description = "貸出中でない蔵書のみに絞り込む場合は true"),
+            ("checkedOutByMe" = Option<bool>, Query, description = "自分が借りている蔵書のみに絞り込む場合は true"),
         )
     )
 )]
 #[tracing::instrument(
-    skip(_user, registry),
+    skip(user, registry),
     fields(
-        user_id = %_user.id().to_string()
+        user_id = %user.id().to_string()
     )
 )]
 pub async fn show_book_list(
-    _user: AuthorizedUser,
+    user: AuthorizedUser,
     ValidatedQuery(query): ValidatedQuery<BookListQuery>,
     State(registry): State<AppRegistry>,
 ) -> AppResult<Json<PaginatedBookResponse>> {
     registry
         .book_use_case()
-        .show_book_list(query.into())
+        .show_book_list(BookListQueryWithUserId::new(user.id(), query).into())
         .await
         .map(PaginatedBookResponse::from)
         .map(Json)
@@ -115,7 +132,7 @@ pub async fn show_book(
         .and_then(|bc| match bc {
             Some(bc) => Ok(Json(bc.into())),
             None => Err(AppError::EntityNotFound(
-                "The specific book was not found".to_string(),
+                Message::new(MessageKey::BookNotFound).arg(book_id),
             )),
         })
 }
@@ -127,6 +144,7 @@ pub async fn show_book(
         responses(
             (status = 200, description = "蔵書の更新に成功した場合。"),
             (status = 400, description = "リクエストのパラメータに不備があった場合。"),
+            (status = 403, description = "蔵書の所有者でも、すべての蔵書を管理する権限を持つユーザーでもない場合。"),
             (status = 404, description = "変更対象の書籍が見つからなかった場合。")
         ),
         params(
@@ -160,6 +178,7 @@ pub async fn update_book(
         responses(
             (status = 204, description = "書籍の削除に成功した場合。"),
             (status = 400, description = "リクエストのパラメータが不正だった場合。"),
+            (status = 403, description = "蔵書の所有者でも、すべての蔵書を管理する権限を持つユーザーでもない場合。"),
             (status = 404, description = "削除対象の書籍が存在しなかった場合。"),
         ),
         params(
@@ -188,3 +207,104 @@ pub async fn delete_book(
         .await
         .map(|_| StatusCode::OK)
 }
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(put, path="/api/v1/books/{book_id}/owner",
+        request_body = UpdateBookOwnerRequest,
+        responses(
+            (status = 200, description = "蔵書の所有者の変更に成功した場合。貸出中の状態や貸出履歴は引き継がれます。"),
+            (status = 400, description = "リクエストのパラメータに不備があった場合。"),
+            (status = 403, description = "蔵書の所有者でも、すべての蔵書を管理する権限を持つユーザーでもない場合。"),
+            (status = 404, description = "蔵書または変更後の所有者が見つからなかった場合。"),
+            (status = 422, description = "変更後の所有者が現在の所有者と同じ場合。")
+        ),
+        params(
+            ("book_id" = Uuid, Path, description = "蔵書ID")
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry),
+    fields(
+        user_id = %user.id().to_string()
+    )
+)]
+pub async fn update_book_owner(
+    user: AuthorizedUser,
+    Path(book_id): Path<BookId>,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<UpdateBookOwnerRequest>,
+) -> AppResult<StatusCode> {
+    registry
+        .book_use_case()
+        .update_book_owner(UpdateBookOwnerRequestWithIds::new(book_id, user.id(), req).into())
+        .await
+        .map(|_| StatusCode::OK)
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(put, path="/api/v1/users/{user_id}/books/owner",
+        request_body = UpdateBookOwnerRequest,
+        responses(
+            (status = 200, description = "指定したユーザーが所有するすべての蔵書の移管に成功した場合。移管した件数を返します。", body = TransferredBooksResponse),
+            (status = 400, description = "リクエストのパラメータに不備があった場合。"),
+            (status = 403, description = "管理者以外が実行した場合。"),
+            (status = 404, description = "移管元または移管先のユーザーが見つからなかった場合。"),
+            (status = 422, description = "移管元と移管先が同じユーザーの場合。")
+        ),
+        params(
+            ("user_id" = Uuid, Path, description = "移管元のユーザーID")
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry),
+    fields(
+        user_id = %user.id().to_string()
+    )
+)]
+pub async fn transfer_all_books(
+    user: PermittedUser<ManageUsers>,
+    Path(user_id): Path<UserId>,
+    State(registry): State<AppRegistry>,
+    Json(req): Json<UpdateBookOwnerRequest>,
+) -> AppResult<Json<TransferredBooksResponse>> {
+    registry
+        .book_use_case()
+        .transfer_all_books(TransferAllBooksRequestWithIds::new(user_id, user.id(), req).into())
+        .await
+        .map(|count| Json(TransferredBooksResponse { count }))
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(get, path="/api/v1/books/{book_id}/change-logs",
+        responses(
+            (status = 200, description = "蔵書の変更履歴の取得に成功した場合。削除済みの蔵書の履歴も返します。", body = BookChangeLogsResponse),
+            (status = 403, description = "すべての蔵書を管理する権限のないユーザーが実行した場合。"),
+        ),
+        params(
+            ("book_id" = Uuid, Path, description = "蔵書ID")
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry),
+    fields(
+        user_id = %user.id().to_string()
+    )
+)]
+pub async fn show_book_change_logs(
+    user: PermittedUser<ManageAllBooks>,
+    Path(book_id): Path<BookId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<BookChangeLogsResponse>> {
+    registry
+        .book_use_case()
+        .show_book_change_logs(book_id)
+        .await
+        .map(BookChangeLogsResponse::from)
+        .map(Json)
+}
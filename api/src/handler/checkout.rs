@@ -1,11 +1,14 @@
-use crate::{extractor::AuthorizedUser, model::checkout::CheckoutsResponse};
+use crate::{
+    extractor::{AuthorizedUser, PermittedUser, permission::ViewReports},
+    model::checkout::CheckoutsResponse,
+};
 use axum::{
     Json,
     extract::{Path, State},
     http::StatusCode,
 };
 use kernel::model::{
-    checkout::event::{CreateCheckout, UpdateReturned},
+    checkout::event::{CreateCheckout, UpdateRenewed, UpdateReturned},
     id::{BookId, CheckoutId},
 };
 use registry::AppRegistry;
@@ -54,8 +57,9 @@ pub async fn checkout_book(
     utoipa::path(put, path="/api/v1/books/{book_id}/checkouts/{checkout_id}/returned",
         responses(
             (status = 200, description = "返却に成功した場合。"),
-            (status = 400, description = "リクエストのパラメータが不正な場合。"),
-            (status = 422, description = "リクエストされた処理が実行できない場合。"),
+            (status = 400, description = "リクエストのパラメータが不正な場合や、貸出と蔵書が一致しない場合。"),
+            (status = 403, description = "借り手・管理者・蔵書の所有者以外が返却しようとした場合。"),
+            (status = 404, description = "指定の蔵書または貸出が存在しない場合。"),
             (status = 500, description = "返却の登録に失敗した場合。")
         ),
         params(
@@ -89,6 +93,47 @@ pub async fn return_book(
         .map(|_| StatusCode::OK)
 }
 
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(put, path="/api/v1/books/{book_id}/checkouts/{checkout_id}/renewal",
+        responses(
+            (status = 200, description = "貸出の延長に成功した場合。"),
+            (status = 400, description = "リクエストのパラメータが不正な場合。"),
+            (status = 404, description = "指定の蔵書が存在しない場合。"),
+            (status = 422, description = "予約がある場合や延長回数の上限に達している場合。"),
+            (status = 500, description = "貸出の延長に失敗した場合。")
+        ),
+        params(
+            ("book_id" = Uuid, Path, description = "蔵書ID"),
+            ("checkout_id" = Uuid, Path, description = "貸出ID")
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry),
+    fields(
+        user_id = %user.id().to_string()
+    )
+)]
+pub async fn renew_book(
+    user: AuthorizedUser,
+    Path((book_id, checkout_id)): Path<(BookId, CheckoutId)>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    let update_renewed = UpdateRenewed {
+        checkout_id,
+        book_id,
+        renewed_by: user.id(),
+        renewed_at: chrono::Utc::now(),
+    };
+
+    registry
+        .checkout_use_case()
+        .renew_book(update_renewed)
+        .await
+        .map(|_| StatusCode::OK)
+}
+
 #[cfg_attr(
     debug_assertions,
     utoipa::path(get, path="/api/v1/books/checkouts",
@@ -115,6 +160,33 @@ pub async fn show_checked_out_list(
         .map(Json)
 }
 
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(get, path="/api/v1/books/checkouts/overdue",
+        responses(
+            (status = 200, description = "返却期限を過ぎた貸出の一覧取得に成功した場合。", body = CheckoutsResponse),
+            (status = 403, description = "管理者・司書以外のユーザーがアクセスした場合。"),
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry),
+    fields(
+        user_id = %user.id().to_string()
+    )
+)]
+pub async fn show_overdue_list(
+    user: PermittedUser<ViewReports>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<CheckoutsResponse>> {
+    registry
+        .checkout_use_case()
+        .show_overdue_list()
+        .await
+        .map(CheckoutsResponse::from)
+        .map(Json)
+}
+
 #[cfg_attr(
     debug_assertions,
     utoipa::path(get, path="/api/v1/books/{book_id}/checkout-history",
@@ -0,0 +1,120 @@
+use crate::{extractor::AuthorizedUser, model::hold::HoldsResponse};
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use kernel::model::{
+    hold::event::{CreateHold, DeleteHold},
+    id::{BookId, HoldId},
+};
+use registry::AppRegistry;
+use shared::error::AppResult;
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(get, path="/api/v1/books/{book_id}/holds",
+        responses(
+            (status = 200, description = "蔵書の予約待ちの一覧取得に成功した場合。受け取り期限を過ぎた取り置きは一覧に含まれません。次の予約者の取り置きは、その蔵書の次の貸出・返却・予約の取り消しの際に開始されるため、それまでは readyAt と expiresAt が設定されません。", body = HoldsResponse),
+        ),
+        params(
+            ("book_id" = Uuid, Path, description = "蔵書ID")
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(_user, registry),
+    fields(
+        user_id = %_user.id().to_string()
+    )
+)]
+pub async fn show_hold_list(
+    _user: AuthorizedUser,
+    Path(book_id): Path<BookId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<HoldsResponse>> {
+    registry
+        .checkout_use_case()
+        .show_hold_list(book_id)
+        .await
+        .map(HoldsResponse::from)
+        .map(Json)
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(post, path="/api/v1/books/{book_id}/holds",
+        responses(
+            (status = 201, description = "予約の登録に成功した場合。"),
+            (status = 404, description = "指定の蔵書が存在しない場合。"),
+            (status = 422, description = "リクエストされた処理が実行できない場合。"),
+            (status = 500, description = "予約の登録に失敗した場合。")
+        ),
+        params(
+            ("book_id" = Uuid, Path, description = "蔵書ID")
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry),
+    fields(
+        user_id = %user.id().to_string()
+    )
+)]
+pub async fn place_hold(
+    user: AuthorizedUser,
+    Path(book_id): Path<BookId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    let create_hold = CreateHold {
+        book_id,
+        held_by: user.id(),
+        created_at: chrono::Utc::now(),
+    };
+
+    registry
+        .checkout_use_case()
+        .place_hold(create_hold)
+        .await
+        .map(|_| StatusCode::CREATED)
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(delete, path="/api/v1/books/{book_id}/holds/{hold_id}",
+        responses(
+            (status = 200, description = "予約の取り消しに成功した場合。"),
+            (status = 403, description = "予約者以外のユーザーが取り消そうとした場合。"),
+            (status = 404, description = "指定の予約が存在しない場合。"),
+            (status = 500, description = "予約の取り消しに失敗した場合。")
+        ),
+        params(
+            ("book_id" = Uuid, Path, description = "蔵書ID"),
+            ("hold_id" = Uuid, Path, description = "予約ID")
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry),
+    fields(
+        user_id = %user.id().to_string()
+    )
+)]
+pub async fn cancel_hold(
+    user: AuthorizedUser,
+    Path((book_id, hold_id)): Path<(BookId, HoldId)>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    let delete_hold = DeleteHold {
+        hold_id,
+        book_id,
+        requested_user: user.id(),
+        requested_at: chrono::Utc::now(),
+    };
+
+    registry
+        .checkout_use_case()
+        .cancel_hold(delete_hold)
+        .await
+        .map(|_| StatusCode::OK)
+}
@@ -0,0 +1,79 @@
+use crate::{
+    extractor::{
+        PermittedUser, ValidatedJson,
+        permission::{ManageLoanPolicies, ViewReports},
+    },
+    model::{
+        loan_policy::{
+            LoanPoliciesResponse, UpdateLoanPolicyRequest, UpdateLoanPolicyRequestWithRole,
+        },
+        user::RoleName,
+    },
+};
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use registry::AppRegistry;
+use shared::error::AppResult;
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(get, path="/api/v1/loan-policies",
+        responses(
+            (status = 200, description = "ロールごとの貸出ポリシーの一覧取得に成功した場合。"),
+            (status = 403, description = "管理者・司書以外のユーザーがアクセスした場合。"),
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry),
+    fields(
+        user_id = %user.id().to_string()
+    )
+)]
+pub async fn show_loan_policies(
+    user: PermittedUser<ViewReports>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<LoanPoliciesResponse>> {
+    registry
+        .checkout_use_case()
+        .show_loan_policies()
+        .await
+        .map(LoanPoliciesResponse::from)
+        .map(Json)
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(put, path="/api/v1/loan-policies/{role}",
+        responses(
+            (status = 200, description = "貸出ポリシーの更新に成功した場合。"),
+            (status = 400, description = "リクエストのパラメータが不正な場合。"),
+            (status = 403, description = "管理者以外のユーザーがアクセスした場合。"),
+        ),
+        params(
+            ("role" = String, Path, description = "ロール名（Admin、Librarian または User）")
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry, req),
+    fields(
+        user_id = %user.id().to_string()
+    )
+)]
+pub async fn update_loan_policy(
+    user: PermittedUser<ManageLoanPolicies>,
+    Path(role): Path<RoleName>,
+    State(registry): State<AppRegistry>,
+    ValidatedJson(req): ValidatedJson<UpdateLoanPolicyRequest>,
+) -> AppResult<StatusCode> {
+    registry
+        .checkout_use_case()
+        .update_loan_policy(UpdateLoanPolicyRequestWithRole::new(role, req).into())
+        .await?;
+
+    Ok(StatusCode::OK)
+}
@@ -1,10 +1,22 @@
 use crate::{
-    extractor::{AuthorizedUser, ValidatedJson},
+    extractor::{
+        AuthorizedUser, PasswordJson, PermittedUser, SessionUser, ValidatedJson, ValidatedQuery,
+        permission::ManageUsers,
+    },
     model::{
         checkout::CheckoutsResponse,
+        hold::HoldsResponse,
+        mfa::{MfaCodeRequest, MfaEnrollmentResponse, MfaStatusResponse, RecoveryCodesResponse},
+        personal_access_token::{
+            CreatePersonalAccessTokenRequest, CreatePersonalAccessTokenRequestWithUserId,
+            CreatedPersonalAccessTokenResponse, PersonalAccessTokensResponse,
+        },
+        session::SessionsResponse,
         user::{
-            CreateUserRequest, UpdateUserPasswordRequest, UpdateUserPasswordRequestWithUserId,
-            UpdateUserRoleRequest, UpdateUserRoleRequestWithUserId, UserResponse, UsersResponse,
+            CreateUserRequest, PaginatedUserResponse, UpdateUserPasswordRequest,
+            UpdateUserPasswordRequestWithUserId, UpdateUserProfileRequest,
+            UpdateUserProfileRequestWithUserId, UpdateUserRoleRequest,
+            UpdateUserRoleRequestWithUserId, UserListQuery, UserResponse,
         },
     },
 };
@@ -13,25 +25,30 @@ use axum::{
     extract::{Path, State},
     http::StatusCode,
 };
-use kernel::model::{id::UserId, user::event::DeleteUser};
+use kernel::model::{
+    id::{PersonalAccessTokenId, UserId},
+    password::PasswordContext,
+    personal_access_token::event::DeletePersonalAccessToken,
+    user::event::{DeactivateUser, DeleteUser},
+};
 use registry::AppRegistry;
-use shared::error::{AppError, AppResult};
+use shared::error::AppResult;
 
 #[tracing::instrument(
-    skip(user, registry, req),
+    skip(user, registry, body),
     fields(
         user_id = %user.id().to_string(),
     )
 )]
 pub async fn register_user(
-    user: AuthorizedUser,
+    user: PermittedUser<ManageUsers>,
     State(registry): State<AppRegistry>,
-    ValidatedJson(req): ValidatedJson<CreateUserRequest>,
+    body: PasswordJson<CreateUserRequest>,
 ) -> AppResult<Json<UserResponse>> {
-    if !user.is_admin() {
-        return Err(AppError::ForbiddenOperation);
-    }
-
+    let context = body
+        .unvalidated()
+        .password_context(registry.password_policy());
+    let req = body.validate(&context)?;
     let registered_user = registry
         .user_use_case()
         .register_user(req.try_into()?)
@@ -45,40 +62,92 @@ pub async fn register_user(
     utoipa::path(get, path="/api/v1/users",
         responses(
             (status = 200, description = "ユーザーの一覧を取得できた場合。"),
+            (status = 400, description = "指定されたクエリの値に不備があった場合。"),
             (status = 500, description = "サーバーサイドエラーが発生した場合。")
+        ),
+        params(
+            ("limit" = Option<i64>, Query, description = "一度に取得するユーザー数の上限値の指定（最大 100、省略時は 20）"),
+            ("offset" = Option<i64>, Query, description = "取得対象とするユーザー一覧の開始位置"),
+            ("q" = Option<String>, Query, description = "名前・メールアドレス（部分一致）による絞り込み"),
+            ("role" = Option<String>, Query, description = "ロール（Admin / Librarian / User）による絞り込み"),
+            ("status" = Option<String>, Query, description = "状態（active / deactivated / all）による絞り込み（省略時は active で、無効化済みのユーザーを含めない）"),
+            ("sort" = Option<String>, Query, description = "並び替えの基準（name / email / createdAt、省略時は createdAt）"),
+            ("order" = Option<String>, Query, description = "並び順（asc / desc、省略時は desc）"),
         )
     )
 )]
 pub async fn list_users(
     _user: AuthorizedUser,
+    ValidatedQuery(query): ValidatedQuery<UserListQuery>,
     State(registry): State<AppRegistry>,
-) -> AppResult<Json<UsersResponse>> {
-    let items = registry
+) -> AppResult<Json<PaginatedUserResponse>> {
+    registry
         .user_use_case()
-        .list_users()
-        .await?
-        .into_iter()
-        .map(UserResponse::from)
-        .collect();
-
-    Ok(Json(UsersResponse { items }))
+        .list_users(query.into())
+        .await
+        .map(PaginatedUserResponse::from)
+        .map(Json)
 }
 
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(delete, path="/api/v1/users/{user_id}",
+        params(
+            ("user_id" = UserId, Path, description = "ユーザーID")
+        ),
+        responses(
+            (status = 200, description = "ユーザーを無効化できた場合。ログインできなくなり、予約も取り消されますが、蔵書や貸出の履歴は残ります。"),
+            (status = 403, description = "管理者以外が実行した場合。"),
+            (status = 404, description = "指定のユーザーが見つからないか、既に無効化されていた場合。"),
+            (status = 409, description = "他の管理者の操作と競合し、やり直しても処理できなかった場合。"),
+            (status = 422, description = "返却されていない貸出が残っているか、最後の管理者だった場合。"),
+        )
+    )
+)]
 #[tracing::instrument(
     skip(user, registry),
     fields(
         user_id = %user.id().to_string(),
     )
 )]
-pub async fn delete_user(
-    user: AuthorizedUser,
+pub async fn deactivate_user(
+    user: PermittedUser<ManageUsers>,
     Path(user_id): Path<UserId>,
     State(registry): State<AppRegistry>,
 ) -> AppResult<StatusCode> {
-    if !user.is_admin() {
-        return Err(AppError::ForbiddenOperation);
-    }
+    registry
+        .user_use_case()
+        .deactivate_user(DeactivateUser { user_id })
+        .await?;
 
+    Ok(StatusCode::OK)
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(delete, path="/api/v1/users/{user_id}/purge",
+        params(
+            ("user_id" = UserId, Path, description = "ユーザーID")
+        ),
+        responses(
+            (status = 200, description = "ユーザーを、所有する蔵書や貸出の履歴ごと完全に削除できた場合。"),
+            (status = 403, description = "管理者以外が実行した場合。"),
+            (status = 404, description = "指定のユーザーが見つからなかった場合。"),
+            (status = 422, description = "ユーザーが無効化されていない場合。"),
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry),
+    fields(
+        user_id = %user.id().to_string(),
+    )
+)]
+pub async fn purge_user(
+    user: PermittedUser<ManageUsers>,
+    Path(user_id): Path<UserId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
     registry
         .user_use_case()
         .delete_user(DeleteUser { user_id })
@@ -87,16 +156,76 @@ pub async fn delete_user(
     Ok(StatusCode::OK)
 }
 
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(delete, path="/api/v1/users/{user_id}/lockout",
+        params(
+            ("user_id" = UserId, Path, description = "ユーザーID")
+        ),
+        responses(
+            (status = 200, description = "ログイン失敗によるロックを解除できた場合。このユーザーへのログインに失敗してロックされた接続元のロックも併せて解除します。ロックされていなかった場合も同様に返します。"),
+            (status = 403, description = "管理者以外が実行した場合。"),
+            (status = 404, description = "指定のユーザーが見つからなかった場合。"),
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry),
+    fields(
+        user_id = %user.id().to_string(),
+    )
+)]
+pub async fn unlock_user(
+    user: PermittedUser<ManageUsers>,
+    Path(user_id): Path<UserId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    registry.user_use_case().unlock_user(user_id).await?;
+
+    Ok(StatusCode::OK)
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(put, path="/api/v1/users/{user_id}",
+        params(
+            ("user_id" = UserId, Path, description = "ユーザーID")
+        ),
+        responses(
+            (status = 200, description = "ユーザーの名前またはメールアドレスの変更を受け付けた場合。メールアドレスは確認が済むまで変更されないため、変更前の値を返します。"),
+            (status = 400, description = "リクエストの内容に問題があった場合。"),
+            (status = 403, description = "管理者以外が実行した場合。"),
+            (status = 404, description = "指定のユーザーが見つからなかった場合。"),
+            (status = 409, description = "メールアドレスが他のユーザーに使われている場合。"),
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry, req),
+    fields(
+        user_id = %user.id().to_string(),
+    )
+)]
+pub async fn update_user(
+    user: PermittedUser<ManageUsers>,
+    Path(user_id): Path<UserId>,
+    State(registry): State<AppRegistry>,
+    ValidatedJson(req): ValidatedJson<UpdateUserProfileRequest>,
+) -> AppResult<Json<UserResponse>> {
+    registry
+        .user_use_case()
+        .update_profile(UpdateUserProfileRequestWithUserId::new(user_id, req).try_into()?)
+        .await
+        .map(UserResponse::from)
+        .map(Json)
+}
+
 pub async fn change_role(
-    user: AuthorizedUser,
+    _user: PermittedUser<ManageUsers>,
     Path(user_id): Path<UserId>,
     State(registry): State<AppRegistry>,
     Json(req): Json<UpdateUserRoleRequest>,
 ) -> AppResult<StatusCode> {
-    if !user.is_admin() {
-        return Err(AppError::ForbiddenOperation);
-    }
-
     registry
         .user_use_case()
         .change_role(UpdateUserRoleRequestWithUserId::new(user_id, req).into())
@@ -126,25 +255,61 @@ pub async fn get_current_user(user: AuthorizedUser) -> Json<UserResponse> {
 
 #[cfg_attr(
     debug_assertions,
-    utoipa::path(get, path="/api/v1/users/me/password",
+    utoipa::path(put, path="/api/v1/users/me",
+        responses(
+            (status = 200, description = "名前またはメールアドレスの変更を受け付けた場合。メールアドレスは新しいアドレスに届く確認メールで確定するまで変更されないため、変更前の値を返します。"),
+            (status = 400, description = "リクエストの内容に問題があった場合。"),
+            (status = 403, description = "パーソナルアクセストークンで実行した場合。"),
+            (status = 409, description = "メールアドレスが他のユーザーに使われている場合。"),
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry, req),
+    fields(
+        user_id = %user.id().to_string(),
+    )
+)]
+pub async fn update_current_user(
+    user: SessionUser,
+    State(registry): State<AppRegistry>,
+    ValidatedJson(req): ValidatedJson<UpdateUserProfileRequest>,
+) -> AppResult<Json<UserResponse>> {
+    registry
+        .user_use_case()
+        .update_profile(UpdateUserProfileRequestWithUserId::new(user.id(), req).try_into()?)
+        .await
+        .map(UserResponse::from)
+        .map(Json)
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(put, path="/api/v1/users/me/password",
         responses(
             (status = 200, description = "パスワードの変更に成功した場合。"),
-            (status = 400, description = "リクエストの形式に誤りがある場合。"),
+            (status = 400, description = "リクエストの形式に誤りがあるか、新しいパスワードが強度の要件を満たさない場合。"),
+            (status = 403, description = "パーソナルアクセストークンで実行した場合。"),
             (status = 500, description = "サーバーサイドエラーが発生した場合。")
         )
     )
 )]
 #[tracing::instrument(
-    skip(user, registry, req),
+    skip(user, registry, body),
     fields(
         user_id = %user.id().to_string(),
     )
 )]
 pub async fn change_password(
-    user: AuthorizedUser,
+    user: SessionUser,
     State(registry): State<AppRegistry>,
-    ValidatedJson(req): ValidatedJson<UpdateUserPasswordRequest>,
+    body: PasswordJson<UpdateUserPasswordRequest>,
 ) -> AppResult<StatusCode> {
+    let req = body.validate(&PasswordContext::new(
+        registry.password_policy(),
+        user.user.name().as_ref(),
+        user.user.email().as_ref(),
+    ))?;
     registry
         .user_use_case()
         .change_password(UpdateUserPasswordRequestWithUserId::new(user.id(), req).into())
@@ -179,3 +344,328 @@ pub async fn get_checkouts(
         .map(CheckoutsResponse::from)
         .map(Json)
 }
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(get, path="/api/v1/users/me/holds",
+        responses(
+            (status = 200, description = "予約中の書籍を取得できた場合。", body = HoldsResponse),
+            (status = 500, description = "サーバーサイドエラーが発生した場合。")
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry),
+    fields(
+        user_id = %user.id().to_string(),
+    )
+)]
+pub async fn get_holds(
+    user: AuthorizedUser,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<HoldsResponse>> {
+    registry
+        .user_use_case()
+        .get_holds(user.id())
+        .await
+        .map(HoldsResponse::from)
+        .map(Json)
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(get, path="/api/v1/users/me/tokens",
+        responses(
+            (status = 200, description = "アクセストークンの一覧を取得できた場合。", body = PersonalAccessTokensResponse),
+            (status = 403, description = "アクセストークンで認証していた場合。"),
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry),
+    fields(
+        user_id = %user.id().to_string(),
+    )
+)]
+pub async fn get_personal_access_tokens(
+    user: SessionUser,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<PersonalAccessTokensResponse>> {
+    registry
+        .auth_use_case()
+        .list_personal_access_tokens(user.id())
+        .await
+        .map(PersonalAccessTokensResponse::from)
+        .map(Json)
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(post, path="/api/v1/users/me/tokens",
+        request_body = CreatePersonalAccessTokenRequest,
+        responses(
+            (status = 201, description = "アクセストークンを発行できた場合。トークンはこのレスポンスでのみ返します。", body = CreatedPersonalAccessTokenResponse),
+            (status = 400, description = "リクエストの内容に問題があった場合。"),
+            (status = 403, description = "アクセストークンで認証していた場合。"),
+            (status = 422, description = "トークン名が重複している、ないしは有効期限が過去の日時だった場合。"),
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry, req),
+    fields(
+        user_id = %user.id().to_string(),
+    )
+)]
+pub async fn create_personal_access_token(
+    user: SessionUser,
+    State(registry): State<AppRegistry>,
+    ValidatedJson(req): ValidatedJson<CreatePersonalAccessTokenRequest>,
+) -> AppResult<(StatusCode, Json<CreatedPersonalAccessTokenResponse>)> {
+    let (token, secret) = registry
+        .auth_use_case()
+        .create_personal_access_token(
+            CreatePersonalAccessTokenRequestWithUserId::new(user.id(), req).into(),
+        )
+        .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(CreatedPersonalAccessTokenResponse {
+            token: secret.0,
+            detail: token.into(),
+        }),
+    ))
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(delete, path="/api/v1/users/me/tokens/{token_id}",
+        params(
+            ("token_id" = PersonalAccessTokenId, Path, description = "アクセストークンのID")
+        ),
+        responses(
+            (status = 200, description = "アクセストークンを失効できた場合。"),
+            (status = 403, description = "アクセストークンで認証していた場合。"),
+            (status = 404, description = "指定のアクセストークンが見つからなかった場合。"),
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry),
+    fields(
+        user_id = %user.id().to_string(),
+    )
+)]
+pub async fn revoke_personal_access_token(
+    user: SessionUser,
+    Path(token_id): Path<PersonalAccessTokenId>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    registry
+        .auth_use_case()
+        .revoke_personal_access_token(DeletePersonalAccessToken {
+            token_id,
+            requested_user: user.id(),
+        })
+        .await?;
+
+    Ok(StatusCode::OK)
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(get, path="/api/v1/users/me/sessions",
+        responses(
+            (status = 200, description = "ログイン中のセッション一覧を取得できた場合。", body = SessionsResponse),
+            (status = 403, description = "アクセストークンで認証していた場合。"),
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry),
+    fields(
+        user_id = %user.id().to_string(),
+    )
+)]
+pub async fn get_sessions(
+    user: SessionUser,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<SessionsResponse>> {
+    registry
+        .auth_use_case()
+        .list_sessions(user.id(), &user.access_token)
+        .await
+        .map(SessionsResponse::from)
+        .map(Json)
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(delete, path="/api/v1/users/me/sessions",
+        responses(
+            (status = 200, description = "現在のセッションを含む、すべてのセッションからログアウトできた場合。"),
+            (status = 403, description = "アクセストークンで認証していた場合。"),
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry),
+    fields(
+        user_id = %user.id().to_string(),
+    )
+)]
+pub async fn revoke_all_sessions(
+    user: SessionUser,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    registry
+        .auth_use_case()
+        .revoke_all_sessions(user.id())
+        .await?;
+
+    Ok(StatusCode::OK)
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(delete, path="/api/v1/users/me/sessions/{session_id}",
+        params(
+            ("session_id" = String, Path, description = "セッションのID")
+        ),
+        responses(
+            (status = 200, description = "指定のセッションからログアウトできた場合。"),
+            (status = 403, description = "アクセストークンで認証していた場合。"),
+            (status = 404, description = "指定のセッションが見つからなかった場合。"),
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry),
+    fields(
+        user_id = %user.id().to_string(),
+    )
+)]
+pub async fn revoke_session(
+    user: SessionUser,
+    Path(session_id): Path<String>,
+    State(registry): State<AppRegistry>,
+) -> AppResult<StatusCode> {
+    registry
+        .auth_use_case()
+        .revoke_session(user.id(), &session_id)
+        .await?;
+
+    Ok(StatusCode::OK)
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(get, path="/api/v1/users/me/mfa",
+        responses(
+            (status = 200, description = "二要素認証の設定状況を取得できた場合。", body = MfaStatusResponse),
+            (status = 403, description = "アクセストークンで認証していた場合。"),
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry),
+    fields(
+        user_id = %user.id().to_string(),
+    )
+)]
+pub async fn get_mfa_status(
+    user: SessionUser,
+    State(registry): State<AppRegistry>,
+) -> AppResult<Json<MfaStatusResponse>> {
+    let enabled = registry.auth_use_case().is_mfa_enabled(user.id()).await?;
+    Ok(Json(MfaStatusResponse { enabled }))
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(post, path="/api/v1/users/me/mfa",
+        responses(
+            (status = 201, description = "二要素認証の登録を開始できた場合。返した共有秘密を認証アプリに登録し、/api/v1/users/me/mfa/confirm で確認コードを送信すると有効になります。", body = MfaEnrollmentResponse),
+            (status = 403, description = "アクセストークンで認証していた場合。"),
+            (status = 422, description = "二要素認証が既に有効だった場合。"),
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry),
+    fields(
+        user_id = %user.id().to_string(),
+    )
+)]
+pub async fn start_mfa_enrollment(
+    user: SessionUser,
+    State(registry): State<AppRegistry>,
+) -> AppResult<(StatusCode, Json<MfaEnrollmentResponse>)> {
+    let enrollment = registry
+        .auth_use_case()
+        .start_mfa_enrollment(user.id())
+        .await?;
+    Ok((StatusCode::CREATED, Json(enrollment.into())))
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(post, path="/api/v1/users/me/mfa/confirm",
+        request_body = MfaCodeRequest,
+        responses(
+            (status = 200, description = "二要素認証を有効にできた場合。リカバリーコードはこのレスポンスでのみ返します。", body = RecoveryCodesResponse),
+            (status = 400, description = "リクエストの内容に問題があった場合。"),
+            (status = 403, description = "アクセストークンで認証していた場合。"),
+            (status = 422, description = "登録が開始されていない、ないしは確認コードが誤っていた場合。"),
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry, req),
+    fields(
+        user_id = %user.id().to_string(),
+    )
+)]
+pub async fn confirm_mfa_enrollment(
+    user: SessionUser,
+    State(registry): State<AppRegistry>,
+    ValidatedJson(req): ValidatedJson<MfaCodeRequest>,
+) -> AppResult<Json<RecoveryCodesResponse>> {
+    let recovery_codes = registry
+        .auth_use_case()
+        .confirm_mfa_enrollment(user.id(), &req.code)
+        .await?;
+    Ok(Json(RecoveryCodesResponse { recovery_codes }))
+}
+
+#[cfg_attr(
+    debug_assertions,
+    utoipa::path(delete, path="/api/v1/users/me/mfa",
+        request_body = MfaCodeRequest,
+        responses(
+            (status = 200, description = "二要素認証を無効にできた場合。"),
+            (status = 400, description = "リクエストの内容に問題があった場合。"),
+            (status = 403, description = "アクセストークンで認証していた場合。"),
+            (status = 422, description = "二要素認証が有効でない、コードが誤っていた、ないしは管理者に二要素認証が必須とされている場合。"),
+        )
+    )
+)]
+#[tracing::instrument(
+    skip(user, registry, req),
+    fields(
+        user_id = %user.id().to_string(),
+    )
+)]
+pub async fn disable_mfa(
+    user: SessionUser,
+    State(registry): State<AppRegistry>,
+    ValidatedJson(req): ValidatedJson<MfaCodeRequest>,
+) -> AppResult<StatusCode> {
+    registry
+        .auth_use_case()
+        .disable_mfa(user.id(), &req.code)
+        .await?;
+
+    Ok(StatusCode::OK)
+}
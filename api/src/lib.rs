@@ -1,5 +1,6 @@
 pub mod extractor;
 pub(crate) mod handler;
+pub mod middleware;
 pub mod model;
 pub mod route;
 
@@ -1,4 +1,16 @@
 pub mod auth;
 pub mod book;
 pub mod checkout;
+pub mod hold;
+pub mod loan_policy;
+pub mod mfa;
+pub mod personal_access_token;
+pub mod session;
 pub mod user;
+
+// 空白のみの検索条件は指定されなかったものとして扱う
+fn non_blank(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
@@ -1,4 +1,10 @@
-use kernel::model::id::UserId;
+use crate::model::mfa::MfaEnrollmentResponse;
+use garde::Validate;
+use kernel::model::{
+    id::UserId,
+    mfa::LoginOutcome,
+    password::{self, PasswordContext},
+};
 use serde::{Deserialize, Serialize};
 
 #[cfg(debug_assertions)]
@@ -18,4 +24,124 @@ pub struct LoginRequest {
 pub struct AccessTokenResponse {
     pub user_id: UserId,
     pub access_token: String,
+    pub refresh_token: String,
+}
+
+// 二要素認証が必要な場合は、トークンの代わりに確認コードの入力に使うチャレンジを返す
+#[derive(Serialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(untagged)]
+pub enum LoginResponse {
+    Authenticated(AccessTokenResponse),
+    MfaRequired(MfaChallengeResponse),
+}
+
+#[derive(Serialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct MfaChallengeResponse {
+    pub challenge_token: String,
+    // 二要素認証が必須の管理者が未設定だった場合のみ、登録に必要な情報を返す
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enrollment: Option<MfaEnrollmentResponse>,
+}
+
+impl From<LoginOutcome> for LoginResponse {
+    fn from(value: LoginOutcome) -> Self {
+        match value {
+            LoginOutcome::Authenticated {
+                user_id,
+                access_token,
+                refresh_token,
+            } => Self::Authenticated(AccessTokenResponse {
+                user_id,
+                access_token: access_token.0,
+                refresh_token: refresh_token.0,
+            }),
+            LoginOutcome::MfaRequired { challenge_token } => {
+                Self::MfaRequired(MfaChallengeResponse {
+                    challenge_token: challenge_token.0,
+                    enrollment: None,
+                })
+            }
+            LoginOutcome::MfaEnrollmentRequired {
+                challenge_token,
+                enrollment,
+            } => Self::MfaRequired(MfaChallengeResponse {
+                challenge_token: challenge_token.0,
+                enrollment: Some(enrollment.into()),
+            }),
+        }
+    }
+}
+
+#[derive(Deserialize, Validate)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct MfaLoginRequest {
+    #[garde(length(min = 1))]
+    pub challenge_token: String,
+    #[garde(length(min = 1))]
+    pub code: String,
+}
+
+#[derive(Serialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct MfaLoginResponse {
+    pub user_id: UserId,
+    pub access_token: String,
+    pub refresh_token: String,
+    // ログイン時に二要素認証の登録を済ませた場合のみ返す
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub recovery_codes: Vec<String>,
+}
+
+#[derive(Deserialize, Validate)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct RefreshRequest {
+    #[garde(length(min = 1))]
+    pub refresh_token: String,
+}
+
+#[derive(Deserialize, Validate)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct PasswordResetRequest {
+    #[garde(email)]
+    pub email: String,
+}
+
+#[derive(Deserialize, Validate)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[garde(context(PasswordContext))]
+#[serde(rename_all = "camelCase")]
+pub struct PasswordResetConfirmRequest {
+    #[garde(length(min = 1))]
+    pub token: String,
+    #[garde(
+        custom(password::min_length),
+        custom(password::char_classes),
+        custom(password::not_common),
+        custom(password::not_personal_info)
+    )]
+    pub new_password: String,
+}
+
+#[derive(Deserialize, Validate)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct EmailVerificationConfirmRequest {
+    #[garde(length(min = 1))]
+    pub token: String,
+}
+
+// ID プロバイダーが付けて返すパラメーターのため、名前は OAuth 2.0 の仕様に合わせる
+#[derive(Debug, Deserialize, Validate)]
+pub struct OidcCallbackQuery {
+    #[garde(length(min = 1))]
+    pub code: String,
+    #[garde(length(min = 1))]
+    pub state: String,
 }
@@ -1,11 +1,14 @@
-use super::user::{BookOwner, CheckoutUser};
+use super::{
+    non_blank,
+    user::{BookOwner, CheckoutUser},
+};
 use chrono::{DateTime, Utc};
 use derive_new::new;
 use garde::Validate;
 use kernel::model::{
     book::{
-        Book, BookListOptions, Checkout,
-        event::{CreateBook, UpdateBook},
+        Book, BookChangeAction, BookChangeLog, BookListOptions, Checkout,
+        event::{CreateBook, TransferAllBooks, UpdateBook, UpdateBookOwner},
     },
     id::{BookId, CheckoutId, UserId},
     list::PaginatedList,
@@ -90,7 +93,51 @@ impl TryFrom<UpdateBookRequestWithIds> for UpdateBook {
     }
 }
 
+#[derive(Debug, Deserialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateBookOwnerRequest {
+    pub owner_id: UserId,
+}
+
+#[derive(new)]
+pub struct UpdateBookOwnerRequestWithIds(BookId, UserId, UpdateBookOwnerRequest);
+impl From<UpdateBookOwnerRequestWithIds> for UpdateBookOwner {
+    fn from(value: UpdateBookOwnerRequestWithIds) -> Self {
+        let UpdateBookOwnerRequestWithIds(book_id, user_id, UpdateBookOwnerRequest { owner_id }) =
+            value;
+        Self {
+            book_id,
+            new_owner: owner_id,
+            requested_user: user_id,
+        }
+    }
+}
+
+// 1 つ目が移管元、2 つ目が操作したユーザーの ID
+#[derive(new)]
+pub struct TransferAllBooksRequestWithIds(UserId, UserId, UpdateBookOwnerRequest);
+impl From<TransferAllBooksRequestWithIds> for TransferAllBooks {
+    fn from(value: TransferAllBooksRequestWithIds) -> Self {
+        let TransferAllBooksRequestWithIds(from_user, user_id, UpdateBookOwnerRequest { owner_id }) =
+            value;
+        Self {
+            from_user,
+            to_user: owner_id,
+            requested_user: user_id,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct TransferredBooksResponse {
+    pub count: u64,
+}
+
 #[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
 pub struct BookListQuery {
     #[garde(range(min = 0))]
     #[serde(default = "default_limit")]
@@ -98,6 +145,20 @@ pub struct BookListQuery {
     #[garde(range(min = 0))]
     #[serde(default)]
     pub offset: i64,
+    #[garde(inner(length(max = 255)))]
+    pub q: Option<String>,
+    #[garde(inner(length(max = 255)))]
+    pub author: Option<String>,
+    #[garde(inner(length(max = 255)))]
+    pub isbn: Option<String>,
+    #[garde(skip)]
+    pub owner: Option<UserId>,
+    #[garde(skip)]
+    #[serde(default)]
+    pub available_only: bool,
+    #[garde(skip)]
+    #[serde(default)]
+    pub checked_out_by_me: bool,
 }
 
 const DEFAULT_LIMIT: i64 = 20;
@@ -105,10 +166,33 @@ const fn default_limit() -> i64 {
     DEFAULT_LIMIT
 }
 
-impl From<BookListQuery> for BookListOptions {
-    fn from(value: BookListQuery) -> Self {
-        let BookListQuery { limit, offset } = value;
-        Self { limit, offset }
+#[derive(new)]
+pub struct BookListQueryWithUserId(UserId, BookListQuery);
+impl From<BookListQueryWithUserId> for BookListOptions {
+    fn from(value: BookListQueryWithUserId) -> Self {
+        let BookListQueryWithUserId(
+            user_id,
+            BookListQuery {
+                limit,
+                offset,
+                q,
+                author,
+                isbn,
+                owner,
+                available_only,
+                checked_out_by_me,
+            },
+        ) = value;
+        Self {
+            limit,
+            offset,
+            query: non_blank(q),
+            author: non_blank(author),
+            isbn: non_blank(isbn),
+            owner,
+            available_only,
+            checked_out_by: checked_out_by_me.then_some(user_id),
+        }
     }
 }
 
@@ -174,15 +258,74 @@ pub struct BookCheckoutResponse {
     pub id: CheckoutId,
     pub checked_out_by: CheckoutUser,
     pub checked_out_at: DateTime<Utc>,
+    pub due_at: DateTime<Utc>,
+    pub is_overdue: bool,
 }
 
 impl From<Checkout> for BookCheckoutResponse {
     fn from(value: Checkout) -> Self {
-        let (id, user, checked_out_at) = value.into_parts();
+        let is_overdue = value.is_overdue(Utc::now());
+        let (id, user, checked_out_at, due_at) = value.into_parts();
         Self {
             id,
             checked_out_by: user.into(),
             checked_out_at,
+            due_at,
+            is_overdue,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum BookChangeActionName {
+    Update,
+    Delete,
+    Transfer,
+}
+
+impl From<BookChangeAction> for BookChangeActionName {
+    fn from(value: BookChangeAction) -> Self {
+        match value {
+            BookChangeAction::Update => Self::Update,
+            BookChangeAction::Delete => Self::Delete,
+            BookChangeAction::Transfer => Self::Transfer,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct BookChangeLogsResponse {
+    pub items: Vec<BookChangeLogResponse>,
+}
+
+impl From<Vec<BookChangeLog>> for BookChangeLogsResponse {
+    fn from(value: Vec<BookChangeLog>) -> Self {
+        Self {
+            items: value.into_iter().map(BookChangeLogResponse::from).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct BookChangeLogResponse {
+    pub action: BookChangeActionName,
+    pub performed_by: Option<UserId>,
+    pub performed_at: DateTime<Utc>,
+}
+
+impl From<BookChangeLog> for BookChangeLogResponse {
+    fn from(value: BookChangeLog) -> Self {
+        let (action, performed_by, performed_at) = value.into_parts();
+        Self {
+            action: action.into(),
+            performed_by,
+            performed_at,
         }
     }
 }
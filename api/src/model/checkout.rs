@@ -30,7 +30,10 @@ pub struct CheckoutResponse {
     pub id: CheckoutId,
     pub checked_out_by: UserId,
     pub checked_out_at: DateTime<Utc>,
+    pub due_at: DateTime<Utc>,
+    pub renewal_count: i64,
     pub returned_at: Option<DateTime<Utc>>,
+    pub is_overdue: bool,
     pub book: CheckoutBookResponse,
 }
 
@@ -40,7 +43,10 @@ impl From<Checkout> for CheckoutResponse {
             id: value.id(),
             checked_out_by: value.checked_out_by(),
             checked_out_at: value.checked_out_at(),
+            due_at: value.due_at(),
+            renewal_count: value.renewal_count(),
             returned_at: value.returned_at(),
+            is_overdue: value.is_overdue(Utc::now()),
             book: value.book().clone().into(),
         }
     }
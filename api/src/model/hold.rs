@@ -0,0 +1,52 @@
+use super::checkout::CheckoutBookResponse;
+use chrono::{DateTime, Utc};
+use kernel::model::{
+    hold::Hold,
+    id::{HoldId, UserId},
+};
+use serde::Serialize;
+
+#[cfg(debug_assertions)]
+use utoipa::ToSchema;
+
+#[derive(Serialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct HoldsResponse {
+    pub items: Vec<HoldResponse>,
+}
+
+impl From<Vec<Hold>> for HoldsResponse {
+    fn from(value: Vec<Hold>) -> Self {
+        Self {
+            items: value.into_iter().map(HoldResponse::from).collect(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct HoldResponse {
+    pub id: HoldId,
+    pub held_by: UserId,
+    pub position: i64,
+    pub created_at: DateTime<Utc>,
+    pub ready_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub book: CheckoutBookResponse,
+}
+
+impl From<Hold> for HoldResponse {
+    fn from(value: Hold) -> Self {
+        Self {
+            id: value.id(),
+            held_by: value.held_by(),
+            position: value.position(),
+            created_at: value.created_at(),
+            ready_at: value.ready_at(),
+            expires_at: value.expires_at(),
+            book: value.book().clone().into(),
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use super::user::RoleName;
+use derive_new::new;
+use garde::Validate;
+use kernel::model::loan_policy::{LoanPolicy, event::UpdateLoanPolicy};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoanPoliciesResponse {
+    pub items: Vec<LoanPolicyResponse>,
+}
+
+impl From<Vec<LoanPolicy>> for LoanPoliciesResponse {
+    fn from(value: Vec<LoanPolicy>) -> Self {
+        Self {
+            items: value.into_iter().map(LoanPolicyResponse::from).collect(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoanPolicyResponse {
+    pub role: RoleName,
+    pub max_loans: i64,
+    pub loan_period_days: i64,
+    pub max_renewals: i64,
+}
+
+impl From<LoanPolicy> for LoanPolicyResponse {
+    fn from(value: LoanPolicy) -> Self {
+        let (role, max_loans, loan_period_days, max_renewals) = value.into_parts();
+        Self {
+            role: RoleName::from(role),
+            max_loans,
+            loan_period_days,
+            max_renewals,
+        }
+    }
+}
+
+#[derive(Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateLoanPolicyRequest {
+    #[garde(range(min = 0))]
+    max_loans: i64,
+    #[garde(range(min = 1))]
+    loan_period_days: i64,
+    #[garde(range(min = 0))]
+    max_renewals: i64,
+}
+
+#[derive(new)]
+pub struct UpdateLoanPolicyRequestWithRole(RoleName, UpdateLoanPolicyRequest);
+impl From<UpdateLoanPolicyRequestWithRole> for UpdateLoanPolicy {
+    fn from(value: UpdateLoanPolicyRequestWithRole) -> Self {
+        let UpdateLoanPolicyRequestWithRole(
+            role,
+            UpdateLoanPolicyRequest {
+                max_loans,
+                loan_period_days,
+                max_renewals,
+            },
+        ) = value;
+        Self {
+            role: role.into(),
+            max_loans,
+            loan_period_days,
+            max_renewals,
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use garde::Validate;
+use kernel::model::mfa::MfaEnrollment;
+use serde::{Deserialize, Serialize};
+
+#[cfg(debug_assertions)]
+use utoipa::ToSchema;
+
+#[derive(Serialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct MfaStatusResponse {
+    pub enabled: bool,
+}
+
+#[derive(Serialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct MfaEnrollmentResponse {
+    // 認証アプリに手入力する場合の Base32 の共有秘密
+    pub secret: String,
+    // QR コードにして認証アプリで読み取る otpauth:// 形式の URL
+    pub otpauth_url: String,
+}
+
+impl From<MfaEnrollment> for MfaEnrollmentResponse {
+    fn from(value: MfaEnrollment) -> Self {
+        let MfaEnrollment {
+            secret,
+            otpauth_url,
+        } = value;
+        Self {
+            secret,
+            otpauth_url,
+        }
+    }
+}
+
+#[derive(Deserialize, Validate)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct MfaCodeRequest {
+    #[garde(length(min = 1))]
+    pub code: String,
+}
+
+#[derive(Serialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveryCodesResponse {
+    pub recovery_codes: Vec<String>,
+}
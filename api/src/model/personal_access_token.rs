@@ -0,0 +1,130 @@
+use chrono::{DateTime, Utc};
+use derive_new::new;
+use garde::Validate;
+use kernel::model::{
+    id::{PersonalAccessTokenId, UserId},
+    personal_access_token::{PersonalAccessToken, TokenScope, event::CreatePersonalAccessToken},
+};
+use serde::{Deserialize, Serialize};
+
+#[cfg(debug_assertions)]
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum TokenScopeName {
+    Read,
+    Write,
+}
+
+impl From<TokenScope> for TokenScopeName {
+    fn from(value: TokenScope) -> Self {
+        match value {
+            TokenScope::Read => Self::Read,
+            TokenScope::Write => Self::Write,
+        }
+    }
+}
+
+impl From<TokenScopeName> for TokenScope {
+    fn from(value: TokenScopeName) -> Self {
+        match value {
+            TokenScopeName::Read => Self::Read,
+            TokenScopeName::Write => Self::Write,
+        }
+    }
+}
+
+#[derive(Deserialize, Validate)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct CreatePersonalAccessTokenRequest {
+    #[garde(length(min = 1, max = 255))]
+    pub name: String,
+    #[garde(length(min = 1))]
+    pub scopes: Vec<TokenScopeName>,
+    #[garde(skip)]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(new)]
+pub struct CreatePersonalAccessTokenRequestWithUserId(UserId, CreatePersonalAccessTokenRequest);
+impl From<CreatePersonalAccessTokenRequestWithUserId> for CreatePersonalAccessToken {
+    fn from(value: CreatePersonalAccessTokenRequestWithUserId) -> Self {
+        let CreatePersonalAccessTokenRequestWithUserId(
+            user_id,
+            CreatePersonalAccessTokenRequest {
+                name,
+                mut scopes,
+                expires_at,
+            },
+        ) = value;
+        scopes.sort();
+        scopes.dedup();
+        CreatePersonalAccessToken::new(
+            user_id,
+            name,
+            scopes.into_iter().map(TokenScope::from).collect(),
+            expires_at,
+        )
+    }
+}
+
+#[derive(Serialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct PersonalAccessTokensResponse {
+    pub items: Vec<PersonalAccessTokenResponse>,
+}
+
+impl From<Vec<PersonalAccessToken>> for PersonalAccessTokensResponse {
+    fn from(value: Vec<PersonalAccessToken>) -> Self {
+        Self {
+            items: value
+                .into_iter()
+                .map(PersonalAccessTokenResponse::from)
+                .collect(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct PersonalAccessTokenResponse {
+    pub id: PersonalAccessTokenId,
+    pub name: String,
+    pub scopes: Vec<TokenScopeName>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<PersonalAccessToken> for PersonalAccessTokenResponse {
+    fn from(value: PersonalAccessToken) -> Self {
+        Self {
+            id: value.id(),
+            name: value.name().to_string(),
+            scopes: value
+                .scopes()
+                .iter()
+                .copied()
+                .map(TokenScopeName::from)
+                .collect(),
+            expires_at: value.expires_at(),
+            last_used_at: value.last_used_at(),
+            created_at: value.created_at(),
+        }
+    }
+}
+
+// 平文のトークンは作成時のレスポンスでのみ返す
+#[derive(Serialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct CreatedPersonalAccessTokenResponse {
+    pub token: String,
+    #[serde(flatten)]
+    pub detail: PersonalAccessTokenResponse,
+}
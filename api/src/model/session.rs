@@ -0,0 +1,46 @@
+use chrono::{DateTime, Utc};
+use kernel::model::session::Session;
+use serde::Serialize;
+
+#[cfg(debug_assertions)]
+use utoipa::ToSchema;
+
+#[derive(Serialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct SessionsResponse {
+    pub items: Vec<SessionResponse>,
+}
+
+impl From<Vec<Session>> for SessionsResponse {
+    fn from(value: Vec<Session>) -> Self {
+        Self {
+            items: value.into_iter().map(SessionResponse::from).collect(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct SessionResponse {
+    pub id: String,
+    pub device: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+    pub current: bool,
+}
+
+impl From<Session> for SessionResponse {
+    fn from(value: Session) -> Self {
+        Self {
+            id: value.id().to_string(),
+            device: value.client().device.clone(),
+            ip_address: value.client().ip_address.clone(),
+            created_at: value.created_at(),
+            last_seen_at: value.last_seen_at(),
+            current: value.is_current(),
+        }
+    }
+}
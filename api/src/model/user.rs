@@ -1,24 +1,28 @@
+use super::non_blank;
 use derive_new::new;
 use garde::Validate;
 use kernel::model::{
     id::UserId,
+    list::{PaginatedList, SortOrder},
+    password::{self, PasswordContext},
     role::Role,
     user::{
-        User,
-        event::{CreateUser, UpdateUserPassword, UpdateUserRole},
+        User, UserListOptions, UserSortKey, UserStatus,
+        event::{CreateUser, UpdateUserPassword, UpdateUserProfile, UpdateUserRole},
     },
 };
 use serde::{Deserialize, Serialize};
-use shared::error::AppError;
+use shared::{config::PasswordPolicyConfig, error::AppError};
 use strum::VariantNames;
 
 #[cfg(debug_assertions)]
 use utoipa::ToSchema;
 
-#[derive(Serialize, Deserialize, VariantNames)]
+#[derive(Debug, Serialize, Deserialize, VariantNames)]
 #[strum(serialize_all = "kebab-case")]
 pub enum RoleName {
     Admin,
+    Librarian,
     User,
 }
 
@@ -26,6 +30,7 @@ impl From<Role> for RoleName {
     fn from(value: Role) -> Self {
         match value {
             Role::Admin => Self::Admin,
+            Role::Librarian => Self::Librarian,
             Role::User => Self::User,
         }
     }
@@ -35,17 +40,140 @@ impl From<RoleName> for Role {
     fn from(value: RoleName) -> Self {
         match value {
             RoleName::Admin => Self::Admin,
+            RoleName::Librarian => Self::Librarian,
             RoleName::User => Self::User,
         }
     }
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum UserStatusName {
+    Active,
+    Deactivated,
+    All,
+}
+
+impl From<UserStatusName> for UserStatus {
+    fn from(value: UserStatusName) -> Self {
+        match value {
+            UserStatusName::Active => Self::Active,
+            UserStatusName::Deactivated => Self::Deactivated,
+            UserStatusName::All => Self::All,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum UserSortKeyName {
+    Name,
+    Email,
+    CreatedAt,
+}
+
+impl From<UserSortKeyName> for UserSortKey {
+    fn from(value: UserSortKeyName) -> Self {
+        match value {
+            UserSortKeyName::Name => Self::Name,
+            UserSortKeyName::Email => Self::Email,
+            UserSortKeyName::CreatedAt => Self::CreatedAt,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SortOrderName {
+    Asc,
+    Desc,
+}
+
+impl From<SortOrderName> for SortOrder {
+    fn from(value: SortOrderName) -> Self {
+        match value {
+            SortOrderName::Asc => Self::Asc,
+            SortOrderName::Desc => Self::Desc,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct UserListQuery {
+    #[garde(range(min = 0, max = MAX_LIMIT))]
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+    #[garde(range(min = 0))]
+    #[serde(default)]
+    pub offset: i64,
+    #[garde(inner(length(max = 255)))]
+    pub q: Option<String>,
+    #[garde(skip)]
+    pub role: Option<RoleName>,
+    #[garde(skip)]
+    pub status: Option<UserStatusName>,
+    #[garde(skip)]
+    pub sort: Option<UserSortKeyName>,
+    #[garde(skip)]
+    pub order: Option<SortOrderName>,
+}
+
+const DEFAULT_LIMIT: i64 = 20;
+const MAX_LIMIT: i64 = 100;
+const fn default_limit() -> i64 {
+    DEFAULT_LIMIT
+}
+
+impl From<UserListQuery> for UserListOptions {
+    fn from(value: UserListQuery) -> Self {
+        let UserListQuery {
+            limit,
+            offset,
+            q,
+            role,
+            status,
+            sort,
+            order,
+        } = value;
+        Self {
+            limit,
+            offset,
+            query: non_blank(q),
+            role: role.map(Role::from),
+            status: status.map(UserStatus::from).unwrap_or_default(),
+            sort: sort.map(UserSortKey::from).unwrap_or_default(),
+            order: order.map(SortOrder::from).unwrap_or_default(),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct UsersResponse {
+pub struct PaginatedUserResponse {
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
     pub items: Vec<UserResponse>,
 }
 
+impl From<PaginatedList<User>> for PaginatedUserResponse {
+    fn from(value: PaginatedList<User>) -> Self {
+        let PaginatedList {
+            total,
+            limit,
+            offset,
+            items,
+        } = value;
+        Self {
+            total,
+            limit,
+            offset,
+            items: items.into_iter().map(UserResponse::from).collect(),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UserResponse {
@@ -68,11 +196,17 @@ impl From<User> for UserResponse {
 }
 
 #[derive(Deserialize, Validate)]
+#[garde(context(PasswordContext))]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateUserPasswordRequest {
     #[garde(length(min = 1))]
     current_password: String,
-    #[garde(length(min = 1))]
+    #[garde(
+        custom(password::min_length),
+        custom(password::char_classes),
+        custom(password::not_common),
+        custom(password::not_personal_info)
+    )]
     new_password: String,
 }
 
@@ -96,16 +230,29 @@ impl From<UpdateUserPasswordRequestWithUserId> for UpdateUserPassword {
 }
 
 #[derive(Deserialize, Validate)]
+#[garde(context(PasswordContext))]
 #[serde(rename_all = "camelCase")]
 pub struct CreateUserRequest {
     #[garde(length(min = 1))]
     name: String,
     #[garde(email)]
     email: String,
-    #[garde(length(min = 1))]
+    #[garde(
+        custom(password::min_length),
+        custom(password::char_classes),
+        custom(password::not_common),
+        custom(password::not_personal_info)
+    )]
     password: String,
 }
 
+impl CreateUserRequest {
+    // 登録するユーザーの名前とメールアドレスは、リクエストの内容と照合する
+    pub fn password_context(&self, policy: PasswordPolicyConfig) -> PasswordContext {
+        PasswordContext::new(policy, &self.name, &self.email)
+    }
+}
+
 impl TryFrom<CreateUserRequest> for CreateUser {
     type Error = AppError;
 
@@ -123,6 +270,31 @@ impl TryFrom<CreateUserRequest> for CreateUser {
     }
 }
 
+#[derive(Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateUserProfileRequest {
+    #[garde(length(min = 1))]
+    name: String,
+    #[garde(email)]
+    email: String,
+}
+
+#[derive(new)]
+pub struct UpdateUserProfileRequestWithUserId(UserId, UpdateUserProfileRequest);
+impl TryFrom<UpdateUserProfileRequestWithUserId> for UpdateUserProfile {
+    type Error = AppError;
+
+    fn try_from(value: UpdateUserProfileRequestWithUserId) -> Result<Self, Self::Error> {
+        let UpdateUserProfileRequestWithUserId(user_id, UpdateUserProfileRequest { name, email }) =
+            value;
+        Ok(Self {
+            user_id,
+            name: name.parse()?,
+            email: email.parse()?,
+        })
+    }
+}
+
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateUserRoleRequest {
@@ -24,29 +24,92 @@ Rust でしたいと思った際に、どのようなクレートや実装を利
         handler::book::register_book,
         handler::book::update_book,
         handler::book::delete_book,
+        handler::book::update_book_owner,
+        handler::book::transfer_all_books,
+        handler::book::show_book_change_logs,
         handler::checkout::checkout_book,
         handler::checkout::return_book,
+        handler::checkout::renew_book,
         handler::checkout::checkout_history,
+        handler::checkout::show_overdue_list,
+        handler::hold::show_hold_list,
+        handler::hold::place_hold,
+        handler::hold::cancel_hold,
+        handler::user::get_holds,
+        handler::user::get_personal_access_tokens,
+        handler::user::create_personal_access_token,
+        handler::user::revoke_personal_access_token,
+        handler::user::get_sessions,
+        handler::user::revoke_all_sessions,
+        handler::user::revoke_session,
+        handler::user::deactivate_user,
+        handler::user::purge_user,
+        handler::user::unlock_user,
+        handler::user::get_mfa_status,
+        handler::user::start_mfa_enrollment,
+        handler::user::confirm_mfa_enrollment,
+        handler::user::disable_mfa,
+        handler::loan_policy::show_loan_policies,
+        handler::loan_policy::update_loan_policy,
         handler::user::get_current_user,
+        handler::user::update_current_user,
+        handler::user::update_user,
         handler::auth::login,
+        handler::auth::login_mfa,
         handler::auth::logout,
+        handler::auth::refresh,
+        handler::auth::oidc_login,
+        handler::auth::oidc_callback,
+        handler::auth::request_password_reset,
+        handler::auth::confirm_password_reset,
+        handler::auth::confirm_email_change,
     ),
     components(schemas(
         model::book::CreateBookRequest,
         model::book::UpdateBookRequest,
+        model::book::UpdateBookOwnerRequest,
+        model::book::TransferredBooksResponse,
         model::book::BookResponse,
         model::book::PaginatedBookResponse,
         model::book::BookCheckoutResponse,
+        model::book::BookChangeActionName,
+        model::book::BookChangeLogsResponse,
+        model::book::BookChangeLogResponse,
         model::checkout::CheckoutsResponse,
         model::checkout::CheckoutResponse,
         model::checkout::CheckoutBookResponse,
+        model::hold::HoldsResponse,
+        model::hold::HoldResponse,
+        model::personal_access_token::TokenScopeName,
+        model::personal_access_token::CreatePersonalAccessTokenRequest,
+        model::personal_access_token::PersonalAccessTokensResponse,
+        model::personal_access_token::PersonalAccessTokenResponse,
+        model::personal_access_token::CreatedPersonalAccessTokenResponse,
+        model::session::SessionsResponse,
+        model::session::SessionResponse,
+        model::mfa::MfaStatusResponse,
+        model::mfa::MfaEnrollmentResponse,
+        model::mfa::MfaCodeRequest,
+        model::mfa::RecoveryCodesResponse,
         model::user::BookOwner,
         model::user::CheckoutUser,
         model::auth::LoginRequest,
         model::auth::AccessTokenResponse,
+        model::auth::LoginResponse,
+        model::auth::MfaChallengeResponse,
+        model::auth::MfaLoginRequest,
+        model::auth::MfaLoginResponse,
+        model::auth::RefreshRequest,
+        model::auth::PasswordResetRequest,
+        model::auth::PasswordResetConfirmRequest,
+        model::auth::EmailVerificationConfirmRequest,
+        shared::error::ProblemDetails,
+        shared::error::FieldError,
         kernel::model::id::BookId,
         kernel::model::id::UserId,
         kernel::model::id::CheckoutId,
+        kernel::model::id::HoldId,
+        kernel::model::id::PersonalAccessTokenId,
     ))
 )]
 pub struct ApiDoc;
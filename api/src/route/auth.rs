@@ -1,10 +1,23 @@
-use crate::handler::auth::{login, logout};
-use axum::{Router, routing::post};
+use crate::handler::auth::{
+    confirm_email_change, confirm_password_reset, login, login_mfa, logout, oidc_callback,
+    oidc_login, refresh, request_password_reset,
+};
+use axum::{
+    Router,
+    routing::{get, post},
+};
 use registry::AppRegistry;
 
 pub fn routes() -> Router<AppRegistry> {
     let auth_router = Router::new()
         .route("/login", post(login))
-        .route("/logout", post(logout));
+        .route("/login/mfa", post(login_mfa))
+        .route("/logout", post(logout))
+        .route("/oidc/login", get(oidc_login))
+        .route("/oidc/callback", get(oidc_callback))
+        .route("/refresh", post(refresh))
+        .route("/password-reset", post(request_password_reset))
+        .route("/password-reset/confirm", post(confirm_password_reset))
+        .route("/email-verification/confirm", post(confirm_email_change));
     Router::new().nest("/auth", auth_router)
 }
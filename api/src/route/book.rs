@@ -1,6 +1,13 @@
 use crate::handler::{
-    book::{delete_book, register_book, show_book, show_book_list, update_book},
-    checkout::{checkout_book, checkout_history, return_book, show_checked_out_list},
+    book::{
+        delete_book, register_book, show_book, show_book_change_logs, show_book_list, update_book,
+        update_book_owner,
+    },
+    checkout::{
+        checkout_book, checkout_history, renew_book, return_book, show_checked_out_list,
+        show_overdue_list,
+    },
+    hold::{cancel_hold, place_hold, show_hold_list},
 };
 use axum::{
     Router,
@@ -14,16 +21,28 @@ pub fn build_book_routers() -> Router<AppRegistry> {
         .route("/", get(show_book_list))
         .route("/:book_id", get(show_book))
         .route("/:book_id", put(update_book))
-        .route("/:book_id", delete(delete_book));
+        .route("/:book_id", delete(delete_book))
+        .route("/:book_id/owner", put(update_book_owner))
+        .route("/:book_id/change-logs", get(show_book_change_logs));
 
     let checkout_router = Router::new()
         .route("/checkouts", get(show_checked_out_list))
+        .route("/checkouts/overdue", get(show_overdue_list))
         .route("/:book_id/checkouts", post(checkout_book))
         .route(
             "/:book_id/checkouts/:checkout_id/returned",
             put(return_book),
         )
+        .route("/:book_id/checkouts/:checkout_id/renewal", put(renew_book))
         .route("/:book_id/checkout-history", get(checkout_history));
 
-    Router::new().nest("/books", books_routers.merge(checkout_router))
+    let hold_router = Router::new()
+        .route("/:book_id/holds", get(show_hold_list))
+        .route("/:book_id/holds", post(place_hold))
+        .route("/:book_id/holds/:hold_id", delete(cancel_hold));
+
+    Router::new().nest(
+        "/books",
+        books_routers.merge(checkout_router).merge(hold_router),
+    )
 }
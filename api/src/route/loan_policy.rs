@@ -0,0 +1,14 @@
+use crate::handler::loan_policy::{show_loan_policies, update_loan_policy};
+use axum::{
+    Router,
+    routing::{get, put},
+};
+use registry::AppRegistry;
+
+pub fn build_loan_policy_routers() -> Router<AppRegistry> {
+    let routers = Router::new()
+        .route("/", get(show_loan_policies))
+        .route("/:role", put(update_loan_policy));
+
+    Router::new().nest("/loan-policies", routers)
+}
@@ -1,19 +1,49 @@
-use crate::handler::user::{
-    change_password, change_role, delete_user, get_checkouts, get_current_user, list_users,
-    register_user,
+use crate::handler::{
+    book::transfer_all_books,
+    user::{
+        change_password, change_role, confirm_mfa_enrollment, create_personal_access_token,
+        deactivate_user, disable_mfa, get_checkouts, get_current_user, get_holds, get_mfa_status,
+        get_personal_access_tokens, get_sessions, list_users, purge_user, register_user,
+        revoke_all_sessions, revoke_personal_access_token, revoke_session, start_mfa_enrollment,
+        unlock_user, update_current_user, update_user,
+    },
 };
 use axum::{
     Router,
-    routing::{delete, get, put},
+    routing::{delete, get, post, put},
 };
 use registry::AppRegistry;
 
 pub fn build_user_router() -> Router<AppRegistry> {
     Router::new()
-        .route("/users/me", get(get_current_user))
+        .route("/users/me", get(get_current_user).put(update_current_user))
         .route("/users/me/password", put(change_password))
         .route("/users/me/checkouts", get(get_checkouts))
+        .route("/users/me/holds", get(get_holds))
+        .route(
+            "/users/me/sessions",
+            get(get_sessions).delete(revoke_all_sessions),
+        )
+        .route("/users/me/sessions/:session_id", delete(revoke_session))
+        .route(
+            "/users/me/tokens",
+            get(get_personal_access_tokens).post(create_personal_access_token),
+        )
+        .route(
+            "/users/me/tokens/:token_id",
+            delete(revoke_personal_access_token),
+        )
+        .route(
+            "/users/me/mfa",
+            get(get_mfa_status)
+                .post(start_mfa_enrollment)
+                .delete(disable_mfa),
+        )
+        .route("/users/me/mfa/confirm", post(confirm_mfa_enrollment))
         .route("/users", get(list_users).post(register_user))
-        .route("/users/:user_id", delete(delete_user))
+        .route("/users/:user_id", put(update_user).delete(deactivate_user))
+        .route("/users/:user_id/purge", delete(purge_user))
         .route("/users/:user_id/role", put(change_role))
+        .route("/users/:user_id/lockout", delete(unlock_user))
+        .route("/users/:user_id/books/owner", put(transfer_all_books))
 }
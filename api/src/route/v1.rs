@@ -1,5 +1,6 @@
 use super::{
-    book::build_book_routers, health::build_health_check_routers, user::build_user_router,
+    book::build_book_routers, health::build_health_check_routers,
+    loan_policy::build_loan_policy_routers, user::build_user_router,
 };
 use axum::Router;
 use registry::AppRegistry;
@@ -8,6 +9,7 @@ pub fn routes() -> Router<AppRegistry> {
     let router = Router::new()
         .merge(build_health_check_routers())
         .merge(build_book_routers())
+        .merge(build_loan_policy_routers())
         .merge(build_user_router());
     Router::new().nest("/api/v1", router)
 }
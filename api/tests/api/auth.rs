@@ -0,0 +1,313 @@
+use crate::{
+    deserialize_json,
+    helper::{make_router, password_policy},
+};
+use axum::{
+    body::Body,
+    http::{Request, StatusCode, header},
+};
+use kernel::{
+    model::{
+        auth::{AccessToken, RefreshToken},
+        id::UserId,
+        role::Role,
+        user::User,
+    },
+    use_case::{auth::MockAuthUseCase, user::MockUserUseCase},
+};
+use registry::MockAppRegistryExt;
+use rstest::rstest;
+use serde_json::Value;
+use shared::{
+    error::{AppError, ProblemDetails},
+    i18n::{Message, MessageKey},
+};
+use std::sync::Arc;
+use tower::ServiceExt;
+
+#[rstest]
+#[tokio::test]
+async fn request_password_reset_202() -> anyhow::Result<()> {
+    let mut registry = MockAppRegistryExt::new();
+    registry.expect_auth_use_case().returning(|| {
+        let mut mock = MockAuthUseCase::new();
+        mock.expect_request_password_reset()
+            .withf(|email| email == "user@example.com")
+            .returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(registry);
+
+    let req = Request::post("/auth/password-reset")
+        .header("content-type", "application/json")
+        .body(Body::from(r#"{"email": "user@example.com"}"#))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::ACCEPTED);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn request_password_reset_invalid_email() -> anyhow::Result<()> {
+    let app: axum::Router = make_router(MockAppRegistryExt::new());
+
+    let req = Request::post("/auth/password-reset")
+        .header("content-type", "application/json")
+        .body(Body::from(r#"{"email": "not-an-email"}"#))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
+
+#[rstest]
+#[case(Ok(()), StatusCode::NO_CONTENT)]
+#[case(
+    Err(AppError::UnprocessableEntity(Message::new(MessageKey::PasswordResetTokenInvalid))),
+    StatusCode::UNPROCESSABLE_ENTITY
+)]
+#[tokio::test]
+async fn confirm_password_reset(
+    #[case] result: Result<(), AppError>,
+    #[case] expected: StatusCode,
+) -> anyhow::Result<()> {
+    let mut mock = mock_reset_user_auth_use_case();
+    mock.expect_reset_password()
+        .withf(|token, new_password| token.0 == "reset-token" && new_password == "Correct-h0rse")
+        .return_once(move |_, _| result);
+    let mock = Arc::new(mock);
+
+    let mut registry = MockAppRegistryExt::new();
+    registry.expect_password_policy().returning(password_policy);
+    // ユーザーの特定と再設定で、同じモックを 2 回取り出す
+    registry
+        .expect_auth_use_case()
+        .returning(move || mock.clone());
+
+    let app: axum::Router = make_router(registry);
+
+    let req = Request::post("/auth/password-reset/confirm")
+        .header("content-type", "application/json")
+        .body(Body::from(
+            r#"{"token": "reset-token", "newPassword": "Correct-h0rse"}"#,
+        ))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected);
+
+    if expected != StatusCode::NO_CONTENT {
+        let result = deserialize_json!(resp, ProblemDetails);
+        assert_eq!(result.code, "UNPROCESSABLE_ENTITY");
+    }
+
+    Ok(())
+}
+
+// トークンから特定したユーザーの名前とメールアドレスとも照合する
+#[rstest]
+// 文字数・文字種・よく使われるパスワードの 3 つのルールに違反している
+#[case("password", 3)]
+// 本人の名前を含んでいる
+#[case("Eleazar-Fig-2024", 1)]
+#[tokio::test]
+async fn confirm_password_reset_weak_password_400(
+    #[case] new_password: &'static str,
+    #[case] errors: usize,
+) -> anyhow::Result<()> {
+    let mut registry = MockAppRegistryExt::new();
+    registry.expect_password_policy().returning(password_policy);
+    registry
+        .expect_auth_use_case()
+        .returning(|| Arc::new(mock_reset_user_auth_use_case()));
+
+    let app: axum::Router = make_router(registry);
+
+    let req = Request::post("/auth/password-reset/confirm")
+        .header("content-type", "application/json")
+        .body(Body::from(format!(
+            r#"{{"token": "reset-token", "newPassword": "{new_password}"}}"#
+        )))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    let result = deserialize_json!(resp, ProblemDetails);
+    assert_eq!(result.code, "VALIDATION_FAILED");
+    assert_eq!(result.errors.len(), errors);
+    assert!(result.errors.iter().all(|e| e.field == "new_password"));
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn confirm_password_reset_invalid_token_422() -> anyhow::Result<()> {
+    let mut registry = MockAppRegistryExt::new();
+    registry.expect_auth_use_case().returning(|| {
+        let mut mock = MockAuthUseCase::new();
+        mock.expect_find_password_reset_user().returning(|_| {
+            Err(AppError::UnprocessableEntity(Message::new(
+                MessageKey::PasswordResetTokenInvalid,
+            )))
+        });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(registry);
+
+    let req = Request::post("/auth/password-reset/confirm")
+        .header("content-type", "application/json")
+        .body(Body::from(
+            r#"{"token": "reset-token", "newPassword": "Correct-h0rse"}"#,
+        ))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    Ok(())
+}
+
+// トークンからパスワードを再設定するユーザーを特定できる状態の AuthUseCase のモック
+fn mock_reset_user_auth_use_case() -> MockAuthUseCase {
+    let mut mock = MockAuthUseCase::new();
+    mock.expect_find_password_reset_user()
+        .withf(|token| token.0 == "reset-token")
+        .returning(|_| {
+            Ok(User::new(
+                UserId::new(),
+                "Eleazar Fig".parse().unwrap(),
+                "eleazar.fig@example.com".parse().unwrap(),
+                Role::User,
+            ))
+        });
+    mock
+}
+
+#[rstest]
+#[tokio::test]
+async fn refresh_200() -> anyhow::Result<()> {
+    let user_id = UserId::new();
+    let mut registry = MockAppRegistryExt::new();
+    registry.expect_auth_use_case().returning(move || {
+        let mut mock = MockAuthUseCase::new();
+        mock.expect_refresh()
+            .withf(|refresh_token, _| refresh_token.0 == "old-refresh")
+            .returning(move |_, _| {
+                Ok((
+                    user_id,
+                    AccessToken("new-access".into()),
+                    RefreshToken("new-refresh".into()),
+                ))
+            });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(registry);
+
+    let req = Request::post("/auth/refresh")
+        .header("content-type", "application/json")
+        .body(Body::from(r#"{"refreshToken": "old-refresh"}"#))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let result = deserialize_json!(resp, Value);
+    assert_eq!(result["userId"], user_id.to_string());
+    assert_eq!(result["accessToken"], "new-access");
+    assert_eq!(result["refreshToken"], "new-refresh");
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn refresh_with_reused_token_403() -> anyhow::Result<()> {
+    let mut registry = MockAppRegistryExt::new();
+    registry.expect_auth_use_case().returning(|| {
+        let mut mock = MockAuthUseCase::new();
+        mock.expect_refresh()
+            .returning(|_, _| Err(AppError::UnauthenticatedError));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(registry);
+
+    let req = Request::post("/auth/refresh")
+        .header("content-type", "application/json")
+        .body(Body::from(r#"{"refreshToken": "used-refresh"}"#))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn refresh_without_token_400() -> anyhow::Result<()> {
+    let app: axum::Router = make_router(MockAppRegistryExt::new());
+
+    let req = Request::post("/auth/refresh")
+        .header("content-type", "application/json")
+        .body(Body::from(r#"{"refreshToken": ""}"#))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn login_locked_429() -> anyhow::Result<()> {
+    let mut registry = MockAppRegistryExt::new();
+    registry.expect_auth_use_case().returning(|| {
+        let mut mock = MockAuthUseCase::new();
+        mock.expect_login()
+            .returning(|_, _, _| Err(AppError::TooManyRequests(30)));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(registry);
+
+    let req = Request::post("/auth/login")
+        .header("content-type", "application/json")
+        .body(Body::from(
+            r#"{"email": "user@example.com", "password": "wrong"}"#,
+        ))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(resp.headers()[header::RETRY_AFTER], "30");
+
+    let result = deserialize_json!(resp, ProblemDetails);
+    assert_eq!(result.code, "TOO_MANY_REQUESTS");
+
+    Ok(())
+}
+
+#[rstest]
+#[case(Ok(()), StatusCode::NO_CONTENT)]
+#[case(
+    Err(AppError::UnprocessableEntity(Message::new(MessageKey::EmailVerificationTokenInvalid))),
+    StatusCode::UNPROCESSABLE_ENTITY
+)]
+#[tokio::test]
+async fn confirm_email_change(
+    #[case] result: Result<(), AppError>,
+    #[case] expected: StatusCode,
+) -> anyhow::Result<()> {
+    let mut registry = MockAppRegistryExt::new();
+    registry.expect_user_use_case().return_once(move || {
+        let mut mock = MockUserUseCase::new();
+        mock.expect_confirm_email_change()
+            .withf(|token| token.0 == "verification-token")
+            .return_once(move |_| result);
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(registry);
+
+    let req = Request::post("/auth/email-verification/confirm")
+        .header("content-type", "application/json")
+        .body(Body::from(r#"{"token": "verification-token"}"#))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected);
+
+    Ok(())
+}
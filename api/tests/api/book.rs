@@ -1,19 +1,30 @@
 use crate::{
     deserialize_json,
-    helper::{TestRequestExt, fixture, make_router, v1},
+    helper::{TestRequestExt, fixture, make_router, mock_auth_use_case_with_role, v1},
 };
-use api::model::book::PaginatedBookResponse;
-use axum::{body::Body, http::Request};
+use api::model::book::{
+    BookChangeActionName, BookChangeLogsResponse, PaginatedBookResponse, TransferredBooksResponse,
+};
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use chrono::Utc;
 use kernel::{
     model::{
-        book::Book,
+        book::{Book, BookChangeAction, BookChangeLog},
         id::{BookId, UserId},
         list::PaginatedList,
+        role::Role,
         user::BookOwner,
     },
     use_case::book::MockBookUseCase,
 };
 use rstest::rstest;
+use shared::{
+    error::{AppError, ProblemDetails},
+    i18n::{Message, MessageKey},
+};
 use std::sync::Arc;
 use tower::ServiceExt;
 
@@ -108,3 +119,185 @@ async fn show_book_list_with_query_400(
 
     Ok(())
 }
+
+#[rstest]
+#[case("/books?q=Rust", Some("Rust"), None, false, false)]
+#[case("/books?q=%20%20&author=Toyoda", None, Some("Toyoda"), false, false)]
+#[case("/books?availableOnly=true", None, None, true, false)]
+#[case("/books?checkedOutByMe=true", None, None, false, true)]
+#[tokio::test]
+async fn show_book_list_with_filters_200(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] path: &str,
+    #[case] expected_query: Option<&'static str>,
+    #[case] expected_author: Option<&'static str>,
+    #[case] expected_available_only: bool,
+    #[case] expected_checked_out_by_me: bool,
+) -> anyhow::Result<()> {
+    fixture.expect_book_use_case().returning(move || {
+        let mut mock = MockBookUseCase::new();
+        mock.expect_show_book_list()
+            .withf(move |opt| {
+                opt.query.as_deref() == expected_query
+                    && opt.author.as_deref() == expected_author
+                    && opt.available_only == expected_available_only
+                    && opt.checked_out_by.is_some() == expected_checked_out_by_me
+            })
+            .returning(|opt| {
+                Ok(PaginatedList {
+                    total: 0,
+                    limit: opt.limit,
+                    offset: opt.offset,
+                    items: vec![],
+                })
+            });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+
+    let req = Request::get(v1(path)).bearer().body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::OK);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn update_book_owner_200(mut fixture: registry::MockAppRegistryExt) -> anyhow::Result<()> {
+    let book_id = BookId::new();
+    let owner_id = UserId::new();
+    fixture.expect_book_use_case().returning(move || {
+        let mut mock = MockBookUseCase::new();
+        mock.expect_update_book_owner()
+            .withf(move |e| e.book_id == book_id && e.new_owner == owner_id)
+            .returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+
+    let req = Request::put(v1(&format!("/books/{book_id}/owner")))
+        .bearer()
+        .header("content-type", "application/json")
+        .body(Body::from(format!(r#"{{"ownerId":"{owner_id}"}}"#)))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::OK);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn transfer_all_books_200() -> anyhow::Result<()> {
+    let from_user = UserId::new();
+    let to_user = UserId::new();
+    let mut registry = registry::MockAppRegistryExt::new();
+    registry
+        .expect_auth_use_case()
+        .returning(|| Arc::new(mock_auth_use_case_with_role(Role::Admin)));
+    registry.expect_book_use_case().returning(move || {
+        let mut mock = MockBookUseCase::new();
+        mock.expect_transfer_all_books()
+            .withf(move |e| e.from_user == from_user && e.to_user == to_user)
+            .returning(|_| Ok(3));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(registry);
+
+    let req = Request::put(v1(&format!("/users/{from_user}/books/owner")))
+        .bearer()
+        .header("content-type", "application/json")
+        .body(Body::from(format!(r#"{{"ownerId":"{to_user}"}}"#)))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), axum::http::StatusCode::OK);
+
+    let result = deserialize_json!(resp, TransferredBooksResponse);
+    assert_eq!(result.count, 3);
+
+    Ok(())
+}
+
+#[rstest]
+#[case(
+    AppError::UnprocessableEntity(Message::new(MessageKey::TransferToSameUser)),
+    StatusCode::UNPROCESSABLE_ENTITY,
+    "UNPROCESSABLE_ENTITY"
+)]
+#[case(
+    AppError::EntityNotFound(Message::new(MessageKey::UserNotFound)),
+    StatusCode::NOT_FOUND,
+    "ENTITY_NOT_FOUND"
+)]
+#[tokio::test]
+async fn transfer_all_books_error(
+    #[case] error: AppError,
+    #[case] expected: StatusCode,
+    #[case] expected_code: &str,
+) -> anyhow::Result<()> {
+    let user_id = UserId::new();
+    let mut registry = registry::MockAppRegistryExt::new();
+    registry
+        .expect_auth_use_case()
+        .returning(|| Arc::new(mock_auth_use_case_with_role(Role::Admin)));
+    registry.expect_book_use_case().return_once(move || {
+        let mut mock = MockBookUseCase::new();
+        mock.expect_transfer_all_books()
+            .return_once(move |_| Err(error));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(registry);
+
+    let req = Request::put(v1(&format!("/users/{user_id}/books/owner")))
+        .bearer()
+        .header("content-type", "application/json")
+        .body(Body::from(format!(r#"{{"ownerId":"{user_id}"}}"#)))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected);
+
+    let result = deserialize_json!(resp, ProblemDetails);
+    assert_eq!(result.code, expected_code);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn show_book_change_logs_200() -> anyhow::Result<()> {
+    let book_id = BookId::new();
+    let admin_id = UserId::new();
+    let mut registry = registry::MockAppRegistryExt::new();
+    registry
+        .expect_auth_use_case()
+        .returning(|| Arc::new(mock_auth_use_case_with_role(Role::Admin)));
+    registry.expect_book_use_case().returning(move || {
+        let mut mock = MockBookUseCase::new();
+        mock.expect_show_book_change_logs()
+            .withf(move |id| *id == book_id)
+            .returning(move |_| {
+                Ok(vec![
+                    BookChangeLog::new(BookChangeAction::Update, Some(admin_id), Utc::now()),
+                    BookChangeLog::new(BookChangeAction::Delete, None, Utc::now()),
+                ])
+            });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(registry);
+
+    let req = Request::get(v1(&format!("/books/{book_id}/change-logs")))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let result = deserialize_json!(resp, BookChangeLogsResponse);
+    assert_eq!(result.items.len(), 2);
+    assert_eq!(result.items[0].action, BookChangeActionName::Update);
+    assert_eq!(result.items[0].performed_by, Some(admin_id));
+    assert_eq!(result.items[1].action, BookChangeActionName::Delete);
+    assert!(result.items[1].performed_by.is_none());
+
+    Ok(())
+}
@@ -0,0 +1,101 @@
+use crate::{
+    deserialize_json,
+    helper::{TestRequestExt, fixture, make_router, v1},
+};
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use kernel::{
+    model::id::{BookId, CheckoutId},
+    use_case::checkout::MockCheckoutUseCase,
+};
+use rstest::rstest;
+use shared::{
+    error::{AppError, ProblemDetails},
+    i18n::{Message, MessageKey},
+};
+use std::sync::Arc;
+use tower::ServiceExt;
+
+#[rstest]
+#[tokio::test]
+async fn return_book_200(mut fixture: registry::MockAppRegistryExt) -> anyhow::Result<()> {
+    let book_id = BookId::new();
+    let checkout_id = CheckoutId::new();
+
+    fixture.expect_checkout_use_case().returning(move || {
+        let mut mock = MockCheckoutUseCase::new();
+        mock.expect_return_book()
+            .withf(move |event| event.book_id == book_id && event.checkout_id == checkout_id)
+            .returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+
+    let req = Request::put(v1(&format!(
+        "/books/{book_id}/checkouts/{checkout_id}/returned"
+    )))
+    .bearer()
+    .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    Ok(())
+}
+
+#[rstest]
+#[case(
+    AppError::EntityNotFound(Message::new(MessageKey::CheckoutNotFound)),
+    StatusCode::NOT_FOUND,
+    "ENTITY_NOT_FOUND"
+)]
+#[case(
+    AppError::CheckoutBookMismatch(Message::new(MessageKey::CheckoutBookMismatch)),
+    StatusCode::BAD_REQUEST,
+    "CHECKOUT_BOOK_MISMATCH"
+)]
+#[case(
+    AppError::ReturnForbidden(Message::new(MessageKey::ReturnForbidden)),
+    StatusCode::FORBIDDEN,
+    "RETURN_FORBIDDEN"
+)]
+#[tokio::test]
+async fn return_book_error(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] error: AppError,
+    #[case] expected: StatusCode,
+    #[case] expected_code: &str,
+) -> anyhow::Result<()> {
+    let expected_detail = error.to_string();
+    fixture.expect_checkout_use_case().return_once(move || {
+        let mut mock = MockCheckoutUseCase::new();
+        mock.expect_return_book().return_once(move |_| Err(error));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+
+    let req = Request::put(v1(&format!(
+        "/books/{}/checkouts/{}/returned",
+        BookId::new(),
+        CheckoutId::new()
+    )))
+    .bearer()
+    .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected);
+    assert_eq!(
+        resp.headers().get("content-type").unwrap(),
+        "application/problem+json"
+    );
+
+    let result = deserialize_json!(resp, ProblemDetails);
+    assert_eq!(result.status, expected.as_u16());
+    assert_eq!(result.code, expected_code);
+    assert_eq!(result.detail, expected_detail);
+    assert!(result.request_id.is_some());
+
+    Ok(())
+}
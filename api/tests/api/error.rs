@@ -0,0 +1,146 @@
+use crate::{
+    deserialize_json,
+    helper::{TestRequestExt, fixture, make_router, mock_auth_use_case_with_role, v1},
+};
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use kernel::{
+    model::{id::BookId, role::Role},
+    use_case::book::MockBookUseCase,
+};
+use rstest::rstest;
+use shared::error::{AppError, ProblemDetails};
+use std::sync::Arc;
+use tower::ServiceExt;
+
+#[rstest]
+#[tokio::test]
+async fn validation_error_has_field_errors() -> anyhow::Result<()> {
+    let mut registry = registry::MockAppRegistryExt::new();
+    registry
+        .expect_auth_use_case()
+        .returning(|| Arc::new(mock_auth_use_case_with_role(Role::Admin)));
+    let app: axum::Router = make_router(registry);
+
+    let req = Request::put(v1("/loan-policies/User"))
+        .bearer()
+        .header("content-type", "application/json")
+        .header("x-request-id", "test-request-id")
+        .body(Body::from(
+            r#"{"maxLoans": 3, "loanPeriodDays": 0, "maxRenewals": 1}"#,
+        ))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+        resp.headers().get("x-request-id").unwrap(),
+        "test-request-id"
+    );
+
+    let result = deserialize_json!(resp, ProblemDetails);
+    assert_eq!(result.code, "VALIDATION_FAILED");
+    assert_eq!(result.errors.len(), 1);
+    assert_eq!(result.errors[0].field, "loan_period_days");
+    assert_eq!(result.request_id.as_deref(), Some("test-request-id"));
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn server_error_hides_detail(
+    mut fixture: registry::MockAppRegistryExt,
+) -> anyhow::Result<()> {
+    fixture.expect_book_use_case().returning(|| {
+        let mut mock = MockBookUseCase::new();
+        mock.expect_show_book_list()
+            .returning(|_| Err(AppError::NoRowsAffectedError("books".into())));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+
+    let req = Request::get(v1("/books")).bearer().body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+    let result = deserialize_json!(resp, ProblemDetails);
+    assert_eq!(result.code, "NO_ROWS_AFFECTED");
+    assert!(!result.detail.contains("books"));
+
+    Ok(())
+}
+
+#[rstest]
+#[case("a b")]
+#[case("id;drop")]
+#[case("\u{3042}")]
+#[case(&"a".repeat(65))]
+#[tokio::test]
+async fn invalid_request_id_is_replaced(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] request_id: &str,
+) -> anyhow::Result<()> {
+    fixture.expect_book_use_case().returning(|| {
+        let mut mock = MockBookUseCase::new();
+        mock.expect_show_book().returning(|_| Ok(None));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+
+    let req = Request::get(v1(&format!("/books/{}", BookId::new())))
+        .bearer()
+        .header("x-request-id", request_id)
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    let header = resp
+        .headers()
+        .get("x-request-id")
+        .unwrap()
+        .to_str()?
+        .to_owned();
+    assert_ne!(header, request_id);
+    assert!(uuid::Uuid::parse_str(&header).is_ok());
+
+    let result = deserialize_json!(resp, ProblemDetails);
+    assert_eq!(result.request_id, Some(header));
+
+    Ok(())
+}
+
+#[rstest]
+#[case(None, "書籍（{}）が見つかりませんでした。")]
+#[case(Some("en-US,en;q=0.9"), "Book ({}) was not found.")]
+#[case(Some("fr, ja;q=0.5"), "書籍（{}）が見つかりませんでした。")]
+#[tokio::test]
+async fn error_detail_follows_accept_language(
+    mut fixture: registry::MockAppRegistryExt,
+    #[case] accept_language: Option<&'static str>,
+    #[case] expected: &str,
+) -> anyhow::Result<()> {
+    fixture.expect_book_use_case().returning(|| {
+        let mut mock = MockBookUseCase::new();
+        mock.expect_show_book().returning(|_| Ok(None));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(fixture);
+
+    let book_id = BookId::new();
+    let mut req = Request::get(v1(&format!("/books/{}", book_id))).bearer();
+    if let Some(value) = accept_language {
+        req = req.header("accept-language", value);
+    }
+    let resp = app.oneshot(req.body(Body::empty())?).await?;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    let result = deserialize_json!(resp, ProblemDetails);
+    assert_eq!(result.code, "ENTITY_NOT_FOUND");
+    assert_eq!(result.detail, expected.replace("{}", &book_id.to_string()));
+
+    Ok(())
+}
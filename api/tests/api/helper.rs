@@ -1,11 +1,21 @@
-use api::route::{auth, v1};
-use axum::{Router, http::request::Builder};
+use api::{
+    middleware::{locale, request_id},
+    route::{auth, v1},
+};
+use axum::{Router, http::request::Builder, middleware};
 use kernel::{
-    model::{auth::AccessToken, id::UserId, role::Role, user::User},
+    model::{
+        auth::{AccessToken, RefreshToken},
+        id::UserId,
+        mfa::LoginOutcome,
+        role::Role,
+        user::User,
+    },
     use_case::auth::MockAuthUseCase,
 };
 use registry::MockAppRegistryExt;
 use rstest::fixture;
+use shared::config::PasswordPolicyConfig;
 use std::sync::Arc;
 
 pub fn v1(endpoint: &str) -> String {
@@ -16,31 +26,55 @@ pub fn make_router(registry: MockAppRegistryExt) -> Router {
     Router::new()
         .merge(v1::routes())
         .merge(auth::routes())
+        .layer(middleware::from_fn(locale))
+        .layer(middleware::from_fn(request_id))
         .with_state(Arc::new(registry))
 }
 
+pub fn password_policy() -> PasswordPolicyConfig {
+    PasswordPolicyConfig {
+        min_length: 10,
+        min_char_classes: 3,
+    }
+}
+
 #[fixture]
 pub fn fixture_registry() -> MockAppRegistryExt {
     MockAppRegistryExt::new()
 }
 
+// Bearer トークンでの認証が通る状態の AuthUseCase のモック
+pub fn mock_auth_use_case() -> MockAuthUseCase {
+    mock_auth_use_case_with_role(Role::User)
+}
+
+// 指定したロールのユーザーとして認証が通る状態の AuthUseCase のモック
+pub fn mock_auth_use_case_with_role(role: Role) -> MockAuthUseCase {
+    let mut mock_auth_use_case = MockAuthUseCase::new();
+    mock_auth_use_case
+        .expect_find_authorized_user()
+        .returning(move |_| {
+            Ok(User::new(
+                UserId::new(),
+                "dummy-user".parse().unwrap(),
+                "dummy@example.com".parse().unwrap(),
+                role.clone(),
+            ))
+        });
+    mock_auth_use_case
+}
+
 #[fixture]
 pub fn fixture(mut fixture_registry: MockAppRegistryExt) -> MockAppRegistryExt {
     fixture_registry.expect_auth_use_case().returning(|| {
-        let mut mock_auth_use_case = MockAuthUseCase::new();
-        mock_auth_use_case
-            .expect_find_authorized_user()
-            .returning(|_| {
-                Ok(User::new(
-                    UserId::new(),
-                    "dummy-user".parse().unwrap(),
-                    "dummy@example.com".parse().unwrap(),
-                    Role::User,
-                ))
-            });
-        mock_auth_use_case
-            .expect_login()
-            .returning(|_, _| Ok((UserId::new(), AccessToken("dummy".into()))));
+        let mut mock_auth_use_case = mock_auth_use_case();
+        mock_auth_use_case.expect_login().returning(|_, _, _| {
+            Ok(LoginOutcome::Authenticated {
+                user_id: UserId::new(),
+                access_token: AccessToken("dummy".into()),
+                refresh_token: RefreshToken("dummy-refresh".into()),
+            })
+        });
         Arc::new(mock_auth_use_case)
     });
     fixture_registry
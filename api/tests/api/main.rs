@@ -1,2 +1,11 @@
+mod auth;
 mod book;
+mod checkout;
+mod error;
 mod helper;
+mod mfa;
+mod oidc;
+mod permission;
+mod personal_access_token;
+mod session;
+mod user;
@@ -0,0 +1,290 @@
+use crate::{
+    deserialize_json,
+    helper::{TestRequestExt, make_router, mock_auth_use_case, v1},
+};
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use kernel::{
+    model::{
+        auth::{AccessToken, MfaChallengeToken, RefreshToken},
+        id::UserId,
+        mfa::{LoginOutcome, MfaEnrollment},
+        personal_access_token::TokenScope,
+        role::Role,
+        user::User,
+    },
+    use_case::auth::MockAuthUseCase,
+};
+use registry::MockAppRegistryExt;
+use rstest::rstest;
+use serde_json::Value;
+use shared::{
+    error::AppError,
+    i18n::{Message, MessageKey},
+};
+use std::sync::Arc;
+use tower::ServiceExt;
+
+fn login_request() -> anyhow::Result<Request<Body>> {
+    Ok(Request::post("/auth/login")
+        .header("content-type", "application/json")
+        .body(Body::from(
+            r#"{"email": "admin@example.com", "password": "passwd"}"#,
+        ))?)
+}
+
+#[rstest]
+#[tokio::test]
+async fn login_mfa_required_200() -> anyhow::Result<()> {
+    let mut registry = MockAppRegistryExt::new();
+    registry.expect_auth_use_case().returning(|| {
+        let mut mock = MockAuthUseCase::new();
+        mock.expect_login().returning(|_, _, _| {
+            Ok(LoginOutcome::MfaRequired {
+                challenge_token: MfaChallengeToken("challenge".into()),
+            })
+        });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(registry);
+
+    let resp = app.oneshot(login_request()?).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    // 確認コードの検証が済むまでトークンは返さない
+    let result = deserialize_json!(resp, Value);
+    assert_eq!(result["challengeToken"], "challenge");
+    assert!(result.get("accessToken").is_none());
+    assert!(result.get("enrollment").is_none());
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn login_mfa_enrollment_required_200() -> anyhow::Result<()> {
+    let mut registry = MockAppRegistryExt::new();
+    registry.expect_auth_use_case().returning(|| {
+        let mut mock = MockAuthUseCase::new();
+        mock.expect_login().returning(|_, _, _| {
+            Ok(LoginOutcome::MfaEnrollmentRequired {
+                challenge_token: MfaChallengeToken("challenge".into()),
+                enrollment: MfaEnrollment {
+                    secret: "SECRET".into(),
+                    otpauth_url: "otpauth://totp/dummy".into(),
+                },
+            })
+        });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(registry);
+
+    let resp = app.oneshot(login_request()?).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let result = deserialize_json!(resp, Value);
+    assert_eq!(result["challengeToken"], "challenge");
+    assert_eq!(result["enrollment"]["secret"], "SECRET");
+    assert_eq!(result["enrollment"]["otpauthUrl"], "otpauth://totp/dummy");
+
+    Ok(())
+}
+
+#[rstest]
+#[case(vec![], None)]
+#[case(vec!["aaaaa-bbbbb".to_string()], Some(1))]
+#[tokio::test]
+async fn login_mfa_200(
+    #[case] recovery_codes: Vec<String>,
+    #[case] expected_codes: Option<usize>,
+) -> anyhow::Result<()> {
+    let user_id = UserId::new();
+    let mut registry = MockAppRegistryExt::new();
+    registry.expect_auth_use_case().return_once(move || {
+        let mut mock = MockAuthUseCase::new();
+        mock.expect_verify_mfa()
+            .withf(|challenge, code, _| challenge.0 == "challenge" && code == "123456")
+            .return_once(move |_, _, _| {
+                Ok((
+                    user_id,
+                    AccessToken("access".into()),
+                    RefreshToken("refresh".into()),
+                    recovery_codes,
+                ))
+            });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(registry);
+
+    let req = Request::post("/auth/login/mfa")
+        .header("content-type", "application/json")
+        .body(Body::from(
+            r#"{"challengeToken": "challenge", "code": "123456"}"#,
+        ))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let result = deserialize_json!(resp, Value);
+    assert_eq!(result["userId"], user_id.to_string());
+    assert_eq!(result["accessToken"], "access");
+    assert_eq!(result["refreshToken"], "refresh");
+    assert_eq!(
+        result
+            .get("recoveryCodes")
+            .and_then(Value::as_array)
+            .map(Vec::len),
+        expected_codes
+    );
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn login_mfa_with_wrong_code_403() -> anyhow::Result<()> {
+    let mut registry = MockAppRegistryExt::new();
+    registry.expect_auth_use_case().returning(|| {
+        let mut mock = MockAuthUseCase::new();
+        mock.expect_verify_mfa()
+            .returning(|_, _, _| Err(AppError::UnauthenticatedError));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(registry);
+
+    let req = Request::post("/auth/login/mfa")
+        .header("content-type", "application/json")
+        .body(Body::from(
+            r#"{"challengeToken": "challenge", "code": "000000"}"#,
+        ))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn start_mfa_enrollment_201() -> anyhow::Result<()> {
+    let mut registry = MockAppRegistryExt::new();
+    registry.expect_auth_use_case().returning(|| {
+        let mut mock = mock_auth_use_case();
+        mock.expect_start_mfa_enrollment().returning(|_| {
+            Ok(MfaEnrollment {
+                secret: "SECRET".into(),
+                otpauth_url: "otpauth://totp/dummy".into(),
+            })
+        });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(registry);
+
+    let req = Request::post(v1("/users/me/mfa"))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+
+    let result = deserialize_json!(resp, Value);
+    assert_eq!(result["secret"], "SECRET");
+    assert_eq!(result["otpauthUrl"], "otpauth://totp/dummy");
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn confirm_mfa_enrollment_200() -> anyhow::Result<()> {
+    let mut registry = MockAppRegistryExt::new();
+    registry.expect_auth_use_case().returning(|| {
+        let mut mock = mock_auth_use_case();
+        mock.expect_confirm_mfa_enrollment()
+            .withf(|_, code| code == "123456")
+            .returning(|_, _| Ok(vec!["aaaaa-bbbbb".into(), "ccccc-ddddd".into()]));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(registry);
+
+    let req = Request::post(v1("/users/me/mfa/confirm"))
+        .bearer()
+        .header("content-type", "application/json")
+        .body(Body::from(r#"{"code": "123456"}"#))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let result = deserialize_json!(resp, Value);
+    assert_eq!(result["recoveryCodes"].as_array().map(Vec::len), Some(2));
+
+    Ok(())
+}
+
+#[rstest]
+#[case(|| Ok(()), StatusCode::OK)]
+#[case(
+    || Err(AppError::UnprocessableEntity(Message::new(MessageKey::MfaRequiredForAdmin))),
+    StatusCode::UNPROCESSABLE_ENTITY
+)]
+#[tokio::test]
+async fn disable_mfa(
+    #[case] result: fn() -> Result<(), AppError>,
+    #[case] expected: StatusCode,
+) -> anyhow::Result<()> {
+    let mut registry = MockAppRegistryExt::new();
+    registry.expect_auth_use_case().returning(move || {
+        let mut mock = mock_auth_use_case();
+        mock.expect_disable_mfa()
+            .withf(|_, code| code == "123456")
+            .returning(move |_, _| result());
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(registry);
+
+    let req = Request::delete(v1("/users/me/mfa"))
+        .bearer()
+        .header("content-type", "application/json")
+        .body(Body::from(r#"{"code": "123456"}"#))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn personal_access_token_cannot_manage_mfa() -> anyhow::Result<()> {
+    let mut registry = MockAppRegistryExt::new();
+    registry.expect_auth_use_case().returning(|| {
+        let mut mock = MockAuthUseCase::new();
+        mock.expect_find_personal_access_token_user()
+            .returning(|_| {
+                Ok((
+                    User::new(
+                        UserId::new(),
+                        "dummy-user".parse().unwrap(),
+                        "dummy@example.com".parse().unwrap(),
+                        Role::User,
+                    ),
+                    vec![TokenScope::Read, TokenScope::Write],
+                ))
+            });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(registry);
+
+    let req = Request::post(v1("/users/me/mfa"))
+        .header("Authorization", "Bearer pat_dummy")
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    Ok(())
+}
@@ -0,0 +1,143 @@
+use crate::{deserialize_json, helper::make_router};
+use axum::{
+    body::Body,
+    http::{Request, StatusCode, header},
+};
+use kernel::{
+    model::{
+        auth::{AccessToken, RefreshToken},
+        id::UserId,
+        mfa::LoginOutcome,
+    },
+    use_case::auth::MockAuthUseCase,
+};
+use registry::MockAppRegistryExt;
+use rstest::rstest;
+use serde_json::Value;
+use shared::{
+    error::AppError,
+    i18n::{Message, MessageKey},
+};
+use std::sync::Arc;
+use tower::ServiceExt;
+
+#[rstest]
+#[tokio::test]
+async fn oidc_login_redirects_to_provider() -> anyhow::Result<()> {
+    let mut registry = MockAppRegistryExt::new();
+    registry.expect_auth_use_case().returning(|| {
+        let mut mock = MockAuthUseCase::new();
+        mock.expect_start_oidc_login()
+            .returning(|| Ok("https://idp.example.com/authorize?state=abc".into()));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(registry);
+
+    let req = Request::get("/auth/oidc/login").body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::SEE_OTHER);
+    assert_eq!(
+        resp.headers()[header::LOCATION],
+        "https://idp.example.com/authorize?state=abc"
+    );
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn oidc_login_not_configured_404() -> anyhow::Result<()> {
+    let mut registry = MockAppRegistryExt::new();
+    registry.expect_auth_use_case().returning(|| {
+        let mut mock = MockAuthUseCase::new();
+        mock.expect_start_oidc_login().returning(|| {
+            Err(AppError::EntityNotFound(Message::new(
+                MessageKey::OidcNotConfigured,
+            )))
+        });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(registry);
+
+    let req = Request::get("/auth/oidc/login").body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn oidc_callback_200() -> anyhow::Result<()> {
+    let user_id = UserId::new();
+    let mut registry = MockAppRegistryExt::new();
+    registry.expect_auth_use_case().returning(move || {
+        let mut mock = MockAuthUseCase::new();
+        mock.expect_oidc_login()
+            .withf(|state, code, _| state == "abc" && code == "auth-code")
+            .returning(move |_, _, _| {
+                Ok(LoginOutcome::Authenticated {
+                    user_id,
+                    access_token: AccessToken("access".into()),
+                    refresh_token: RefreshToken("refresh".into()),
+                })
+            });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(registry);
+
+    let req = Request::get("/auth/oidc/callback?code=auth-code&state=abc").body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let result = deserialize_json!(resp, Value);
+    assert_eq!(result["userId"], user_id.to_string());
+    assert_eq!(result["accessToken"], "access");
+    assert_eq!(result["refreshToken"], "refresh");
+
+    Ok(())
+}
+
+#[rstest]
+#[case(|| Err(AppError::UnauthenticatedError), StatusCode::FORBIDDEN)]
+#[case(
+    || Err(AppError::ExternalServiceError("connection refused".into())),
+    StatusCode::BAD_GATEWAY
+)]
+#[tokio::test]
+async fn oidc_callback_error(
+    #[case] result: fn() -> Result<LoginOutcome, AppError>,
+    #[case] expected: StatusCode,
+) -> anyhow::Result<()> {
+    let mut registry = MockAppRegistryExt::new();
+    registry.expect_auth_use_case().returning(move || {
+        let mut mock = MockAuthUseCase::new();
+        mock.expect_oidc_login().returning(move |_, _, _| result());
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(registry);
+
+    let req = Request::get("/auth/oidc/callback?code=auth-code&state=abc").body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn oidc_callback_without_code_400() -> anyhow::Result<()> {
+    let app: axum::Router = make_router(MockAppRegistryExt::new());
+
+    // ID プロバイダーが認可を拒否した場合は code を付けずに戻ってくる
+    let req =
+        Request::get("/auth/oidc/callback?error=access_denied&state=abc").body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
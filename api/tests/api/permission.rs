@@ -0,0 +1,102 @@
+use crate::helper::{TestRequestExt, make_router, mock_auth_use_case_with_role, v1};
+use axum::{
+    body::Body,
+    http::{Method, Request, StatusCode},
+};
+use kernel::{
+    model::role::Role,
+    use_case::{book::MockBookUseCase, checkout::MockCheckoutUseCase},
+};
+use registry::MockAppRegistryExt;
+use rstest::rstest;
+use std::sync::Arc;
+use tower::ServiceExt;
+
+fn registry_with_role(role: Role) -> MockAppRegistryExt {
+    let mut registry = MockAppRegistryExt::new();
+    registry
+        .expect_auth_use_case()
+        .returning(move || Arc::new(mock_auth_use_case_with_role(role.clone())));
+    registry.expect_checkout_use_case().returning(|| {
+        let mut mock = MockCheckoutUseCase::new();
+        mock.expect_show_overdue_list().returning(|| Ok(vec![]));
+        mock.expect_show_loan_policies().returning(|| Ok(vec![]));
+        Arc::new(mock)
+    });
+    registry.expect_book_use_case().returning(|| {
+        let mut mock = MockBookUseCase::new();
+        mock.expect_show_book_change_logs()
+            .returning(|_| Ok(vec![]));
+        Arc::new(mock)
+    });
+    registry
+}
+
+// 司書は延滞一覧などを参照できるが、ユーザーや貸出ポリシーは管理できない
+#[rstest]
+#[case(Role::Admin, Method::GET, "/books/checkouts/overdue", StatusCode::OK)]
+#[case(
+    Role::Librarian,
+    Method::GET,
+    "/books/checkouts/overdue",
+    StatusCode::OK
+)]
+#[case(
+    Role::User,
+    Method::GET,
+    "/books/checkouts/overdue",
+    StatusCode::FORBIDDEN
+)]
+#[case(Role::Librarian, Method::GET, "/loan-policies", StatusCode::OK)]
+#[case(Role::User, Method::GET, "/loan-policies", StatusCode::FORBIDDEN)]
+#[case(
+    Role::Librarian,
+    Method::GET,
+    "/books/9890736e-a4e4-461a-a77d-eac3517ef11b/change-logs",
+    StatusCode::OK
+)]
+#[case(
+    Role::User,
+    Method::GET,
+    "/books/9890736e-a4e4-461a-a77d-eac3517ef11b/change-logs",
+    StatusCode::FORBIDDEN
+)]
+#[case(
+    Role::Librarian,
+    Method::PUT,
+    "/loan-policies/User",
+    StatusCode::FORBIDDEN
+)]
+#[case(Role::Librarian, Method::POST, "/users", StatusCode::FORBIDDEN)]
+#[case(
+    Role::Librarian,
+    Method::DELETE,
+    "/users/5b4c96ac-316a-4bee-8e69-cac5eb84ff4c/lockout",
+    StatusCode::FORBIDDEN
+)]
+#[case(
+    Role::Librarian,
+    Method::PUT,
+    "/users/5b4c96ac-316a-4bee-8e69-cac5eb84ff4c/books/owner",
+    StatusCode::FORBIDDEN
+)]
+#[tokio::test]
+async fn permission_guard(
+    #[case] role: Role,
+    #[case] method: Method,
+    #[case] path: &str,
+    #[case] expected: StatusCode,
+) -> anyhow::Result<()> {
+    let app: axum::Router = make_router(registry_with_role(role));
+
+    let req = Request::builder()
+        .method(method)
+        .uri(v1(path))
+        .bearer()
+        .header("content-type", "application/json")
+        .body(Body::from("{}"))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected);
+
+    Ok(())
+}
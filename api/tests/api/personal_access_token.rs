@@ -0,0 +1,161 @@
+use crate::{
+    deserialize_json,
+    helper::{TestRequestExt, make_router, password_policy, v1},
+};
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use chrono::Utc;
+use kernel::{
+    model::{
+        auth::AccessToken,
+        id::{PersonalAccessTokenId, UserId},
+        personal_access_token::{PersonalAccessToken, TokenScope},
+        role::Role,
+        user::User,
+    },
+    use_case::auth::MockAuthUseCase,
+};
+use registry::MockAppRegistryExt;
+use rstest::rstest;
+use serde_json::Value;
+use std::sync::Arc;
+use tower::ServiceExt;
+
+fn registry_with_personal_access_token(scopes: Vec<TokenScope>) -> MockAppRegistryExt {
+    let mut registry = MockAppRegistryExt::new();
+    registry.expect_auth_use_case().returning(move || {
+        let scopes = scopes.clone();
+        let mut mock = MockAuthUseCase::new();
+        mock.expect_find_personal_access_token_user()
+            .withf(|token| token.0 == "pat_dummy")
+            .returning(move |_| {
+                Ok((
+                    User::new(
+                        UserId::new(),
+                        "dummy-user".parse().unwrap(),
+                        "dummy@example.com".parse().unwrap(),
+                        Role::User,
+                    ),
+                    scopes.clone(),
+                ))
+            });
+        Arc::new(mock)
+    });
+    registry
+}
+
+#[rstest]
+#[case(vec![TokenScope::Read], StatusCode::FORBIDDEN)]
+#[case(vec![TokenScope::Write], StatusCode::BAD_REQUEST)]
+#[tokio::test]
+async fn personal_access_token_scope(
+    #[case] scopes: Vec<TokenScope>,
+    #[case] expected: StatusCode,
+) -> anyhow::Result<()> {
+    let app: axum::Router = make_router(registry_with_personal_access_token(scopes));
+
+    // write スコープがあれば認可を通過し、本文の検証で失敗する
+    let req = Request::post(v1("/books"))
+        .header("Authorization", "Bearer pat_dummy")
+        .header("content-type", "application/json")
+        .body(Body::from("{}"))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn personal_access_token_cannot_manage_tokens() -> anyhow::Result<()> {
+    let app: axum::Router = make_router(registry_with_personal_access_token(vec![
+        TokenScope::Read,
+        TokenScope::Write,
+    ]));
+
+    let req = Request::get(v1("/users/me/tokens"))
+        .header("Authorization", "Bearer pat_dummy")
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn personal_access_token_cannot_change_password() -> anyhow::Result<()> {
+    let mut registry =
+        registry_with_personal_access_token(vec![TokenScope::Read, TokenScope::Write]);
+    registry.expect_password_policy().returning(password_policy);
+    let app: axum::Router = make_router(registry);
+
+    let req = Request::put(v1("/users/me/password"))
+        .header("Authorization", "Bearer pat_dummy")
+        .header("content-type", "application/json")
+        .body(Body::from(
+            r#"{"currentPassword": "Pa55w0rd", "newPassword": "c0rrect-h0rse-battery"}"#,
+        ))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn create_personal_access_token_201() -> anyhow::Result<()> {
+    let mut registry = MockAppRegistryExt::new();
+    registry.expect_auth_use_case().returning(|| {
+        let mut mock = MockAuthUseCase::new();
+        mock.expect_find_authorized_user().returning(|_| {
+            Ok(User::new(
+                UserId::new(),
+                "dummy-user".parse().unwrap(),
+                "dummy@example.com".parse().unwrap(),
+                Role::User,
+            ))
+        });
+        mock.expect_create_personal_access_token()
+            .withf(|event| {
+                event.name == "inventory"
+                    && event.scopes == vec![TokenScope::Read]
+                    && event.secret.starts_with("pat_")
+            })
+            .returning(|event| {
+                Ok((
+                    PersonalAccessToken::new(
+                        PersonalAccessTokenId::new(),
+                        event.user_id,
+                        event.name.clone(),
+                        event.scopes.clone(),
+                        None,
+                        None,
+                        Utc::now(),
+                    ),
+                    AccessToken(event.secret),
+                ))
+            });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(registry);
+
+    let req = Request::post(v1("/users/me/tokens"))
+        .bearer()
+        .header("content-type", "application/json")
+        .body(Body::from(
+            r#"{"name": "inventory", "scopes": ["read", "read"]}"#,
+        ))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+
+    let result = deserialize_json!(resp, Value);
+    assert!(result["token"].as_str().unwrap().starts_with("pat_"));
+    assert_eq!(result["name"], "inventory");
+    assert_eq!(result["scopes"], serde_json::json!(["read"]));
+
+    Ok(())
+}
@@ -0,0 +1,142 @@
+use crate::{
+    deserialize_json,
+    helper::{TestRequestExt, make_router, mock_auth_use_case, v1},
+};
+use axum::{
+    body::Body,
+    extract::ConnectInfo,
+    http::{Request, StatusCode},
+};
+use chrono::Utc;
+use kernel::model::{
+    auth::{AccessToken, RefreshToken},
+    id::UserId,
+    mfa::LoginOutcome,
+    session::{Session, SessionClient},
+};
+use registry::MockAppRegistryExt;
+use rstest::rstest;
+use serde_json::Value;
+use shared::config::ProxyConfig;
+use std::{net::SocketAddr, sync::Arc};
+use tower::ServiceExt;
+
+// 信頼するプロキシを経由した場合のみ X-Forwarded-For を使う。
+// 偽装されたヘッダーでログイン試行の集計先（接続元の IP アドレス）を変えられないことを確認する
+#[rstest]
+#[case("10.0.0.1", "203.0.113.5", "203.0.113.5")]
+#[case("10.0.0.1", "198.51.100.99, 203.0.113.5, 10.0.0.2", "203.0.113.5")]
+#[case("198.51.100.7", "203.0.113.5", "198.51.100.7")]
+#[case("198.51.100.7", "10.0.0.2", "198.51.100.7")]
+#[tokio::test]
+async fn login_records_client(
+    #[case] peer: &'static str,
+    #[case] forwarded_for: &'static str,
+    #[case] expected_ip: &'static str,
+) -> anyhow::Result<()> {
+    let mut registry = MockAppRegistryExt::new();
+    registry.expect_proxy_config().returning(|| ProxyConfig {
+        trusted_proxies: vec!["10.0.0.0/8".parse().unwrap()],
+    });
+    registry.expect_auth_use_case().returning(move || {
+        let mut mock = mock_auth_use_case();
+        mock.expect_login()
+            .withf(move |_, _, client| {
+                client
+                    == &SessionClient::new(
+                        Some("inventory-script/1.0".into()),
+                        Some(expected_ip.into()),
+                    )
+            })
+            .returning(|_, _, _| {
+                Ok(LoginOutcome::Authenticated {
+                    user_id: UserId::new(),
+                    access_token: AccessToken("dummy".into()),
+                    refresh_token: RefreshToken("dummy-refresh".into()),
+                })
+            });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(registry);
+
+    let req = Request::post("/auth/login")
+        .header("content-type", "application/json")
+        .header("user-agent", "inventory-script/1.0")
+        .header("x-forwarded-for", forwarded_for)
+        .extension(ConnectInfo(SocketAddr::new(peer.parse()?, 40000)))
+        .body(Body::from(
+            r#"{"email": "user@example.com", "password": "password"}"#,
+        ))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn get_sessions_200() -> anyhow::Result<()> {
+    let mut registry = MockAppRegistryExt::new();
+    registry.expect_auth_use_case().returning(|| {
+        let mut mock = mock_auth_use_case();
+        mock.expect_list_sessions()
+            .withf(|_, current| current.0 == "dummy")
+            .returning(|_, _| {
+                let now = Utc::now();
+                Ok(vec![
+                    Session::new(
+                        "current".into(),
+                        SessionClient::new(Some("browser".into()), None),
+                        now,
+                        now,
+                        true,
+                    ),
+                    Session::new("other".into(), SessionClient::default(), now, now, false),
+                ])
+            });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(registry);
+
+    let req = Request::get(v1("/users/me/sessions"))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let result = deserialize_json!(resp, Value);
+    let items = result["items"].as_array().unwrap();
+    assert_eq!(items.len(), 2);
+    assert_eq!(items[0]["id"], "current");
+    assert_eq!(items[0]["device"], "browser");
+    assert_eq!(items[0]["current"], true);
+    assert_eq!(items[1]["current"], false);
+
+    Ok(())
+}
+
+#[rstest]
+#[case("/users/me/sessions")]
+#[case("/users/me/sessions/abc")]
+#[tokio::test]
+async fn revoke_sessions_200(#[case] path: &str) -> anyhow::Result<()> {
+    let mut registry = MockAppRegistryExt::new();
+    registry.expect_auth_use_case().returning(|| {
+        let mut mock = mock_auth_use_case();
+        mock.expect_revoke_all_sessions().returning(|_| Ok(()));
+        mock.expect_revoke_session()
+            .withf(|_, session_id| session_id == "abc")
+            .returning(|_, _| Ok(()));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(registry);
+
+    let req = Request::delete(v1(path)).bearer().body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    Ok(())
+}
@@ -0,0 +1,434 @@
+use crate::{
+    deserialize_json,
+    helper::{
+        TestRequestExt, make_router, mock_auth_use_case, mock_auth_use_case_with_role,
+        password_policy, v1,
+    },
+};
+use api::model::user::PaginatedUserResponse;
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use kernel::{
+    model::{
+        id::UserId,
+        list::{PaginatedList, SortOrder},
+        role::Role,
+        user::{User, UserSortKey, UserStatus},
+    },
+    use_case::user::MockUserUseCase,
+};
+use registry::MockAppRegistryExt;
+use rstest::rstest;
+use shared::{
+    error::{AppError, AppResult, ProblemDetails},
+    i18n::{Message, MessageKey},
+};
+use std::sync::Arc;
+use tower::ServiceExt;
+
+#[rstest]
+#[tokio::test]
+async fn unlock_user_200() -> anyhow::Result<()> {
+    let user_id = UserId::new();
+    let mut registry = MockAppRegistryExt::new();
+    registry
+        .expect_auth_use_case()
+        .returning(|| Arc::new(mock_auth_use_case_with_role(Role::Admin)));
+    registry.expect_user_use_case().returning(move || {
+        let mut mock = MockUserUseCase::new();
+        mock.expect_unlock_user()
+            .withf(move |id| *id == user_id)
+            .returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(registry);
+
+    let req = Request::delete(v1(&format!("/users/{user_id}/lockout")))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn unlock_user_by_non_admin_403() -> anyhow::Result<()> {
+    let mut registry = MockAppRegistryExt::new();
+    registry
+        .expect_auth_use_case()
+        .returning(|| Arc::new(mock_auth_use_case()));
+
+    let app: axum::Router = make_router(registry);
+
+    let req = Request::delete(v1(&format!("/users/{}/lockout", UserId::new())))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn register_user_200() -> anyhow::Result<()> {
+    let mut registry = MockAppRegistryExt::new();
+    registry.expect_password_policy().returning(password_policy);
+    registry
+        .expect_auth_use_case()
+        .returning(|| Arc::new(mock_auth_use_case_with_role(Role::Admin)));
+    registry.expect_user_use_case().returning(|| {
+        let mut mock = MockUserUseCase::new();
+        mock.expect_register_user().returning(|event| {
+            Ok(User::new(
+                UserId::new(),
+                event.name,
+                event.email,
+                Role::User,
+            ))
+        });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(registry);
+
+    let req = Request::post(v1("/users"))
+        .bearer()
+        .header("content-type", "application/json")
+        .body(Body::from(
+            r#"{"name": "Eleazar Fig", "email": "eleazar.fig@example.com", "password": "Correct-h0rse"}"#,
+        ))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn register_user_weak_password_400() -> anyhow::Result<()> {
+    let mut registry = MockAppRegistryExt::new();
+    registry.expect_password_policy().returning(password_policy);
+    registry
+        .expect_auth_use_case()
+        .returning(|| Arc::new(mock_auth_use_case_with_role(Role::Admin)));
+
+    let app: axum::Router = make_router(registry);
+
+    let req = Request::post(v1("/users"))
+        .bearer()
+        .header("content-type", "application/json")
+        .body(Body::from(
+            r#"{"name": "Eleazar Fig", "email": "eleazar.fig@example.com", "password": "eleazar"}"#,
+        ))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    // 文字数・文字種・名前を含むことの 3 つのルールに違反している
+    let result = deserialize_json!(resp, ProblemDetails);
+    assert_eq!(result.errors.len(), 3);
+    assert!(result.errors.iter().all(|e| e.field == "password"));
+
+    Ok(())
+}
+
+#[rstest]
+#[case("Str0ng-Passphrase", StatusCode::OK)]
+#[case("Dummy-user-2024", StatusCode::BAD_REQUEST)]
+#[tokio::test]
+async fn change_password(
+    #[case] new_password: &'static str,
+    #[case] expected: StatusCode,
+) -> anyhow::Result<()> {
+    let mut registry = MockAppRegistryExt::new();
+    registry.expect_password_policy().returning(password_policy);
+    registry
+        .expect_auth_use_case()
+        .returning(|| Arc::new(mock_auth_use_case()));
+    registry.expect_user_use_case().returning(|| {
+        let mut mock = MockUserUseCase::new();
+        mock.expect_change_password().returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(registry);
+
+    let req = Request::put(v1("/users/me/password"))
+        .bearer()
+        .header("content-type", "application/json")
+        .body(Body::from(format!(
+            r#"{{"currentPassword": "current", "newPassword": "{new_password}"}}"#
+        )))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected);
+
+    if expected == StatusCode::BAD_REQUEST {
+        // 本人の名前を含んでいる
+        let result = deserialize_json!(resp, ProblemDetails);
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].field, "new_password");
+    }
+
+    Ok(())
+}
+
+#[rstest]
+#[case(
+    Ok(User::new(
+        UserId::new(),
+        "Renamed User".parse().unwrap(),
+        "dummy@example.com".parse().unwrap(),
+        Role::User,
+    )),
+    StatusCode::OK
+)]
+#[case(
+    Err(AppError::Conflict(
+        Message::new(MessageKey::EmailAlreadyInUse).arg("taken@example.com")
+    )),
+    StatusCode::CONFLICT
+)]
+#[tokio::test]
+async fn update_current_user(
+    #[case] result: AppResult<User>,
+    #[case] expected: StatusCode,
+) -> anyhow::Result<()> {
+    let mut registry = MockAppRegistryExt::new();
+    registry
+        .expect_auth_use_case()
+        .returning(|| Arc::new(mock_auth_use_case()));
+    registry.expect_user_use_case().return_once(move || {
+        let mut mock = MockUserUseCase::new();
+        mock.expect_update_profile()
+            .withf(|event| {
+                event.name.as_ref() == "Renamed User" && event.email.as_ref() == "taken@example.com"
+            })
+            .return_once(move |_| result);
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(registry);
+
+    let req = Request::put(v1("/users/me"))
+        .bearer()
+        .header("content-type", "application/json")
+        .body(Body::from(
+            r#"{"name": "Renamed User", "email": "taken@example.com"}"#,
+        ))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected);
+
+    if expected == StatusCode::CONFLICT {
+        let result = deserialize_json!(resp, ProblemDetails);
+        assert_eq!(result.code, "CONFLICT");
+    }
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn update_current_user_invalid_email_400() -> anyhow::Result<()> {
+    let mut registry = MockAppRegistryExt::new();
+    registry
+        .expect_auth_use_case()
+        .returning(|| Arc::new(mock_auth_use_case()));
+
+    let app: axum::Router = make_router(registry);
+
+    let req = Request::put(v1("/users/me"))
+        .bearer()
+        .header("content-type", "application/json")
+        .body(Body::from(r#"{"name": "", "email": "not-an-email"}"#))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    let result = deserialize_json!(resp, ProblemDetails);
+    assert_eq!(result.errors.len(), 2);
+
+    Ok(())
+}
+
+#[rstest]
+#[case(Role::Admin, StatusCode::OK)]
+#[case(Role::User, StatusCode::FORBIDDEN)]
+#[tokio::test]
+async fn update_user(#[case] role: Role, #[case] expected: StatusCode) -> anyhow::Result<()> {
+    let user_id = UserId::new();
+    let mut registry = MockAppRegistryExt::new();
+    registry
+        .expect_auth_use_case()
+        .returning(move || Arc::new(mock_auth_use_case_with_role(role.clone())));
+    registry.expect_user_use_case().returning(move || {
+        let mut mock = MockUserUseCase::new();
+        mock.expect_update_profile()
+            .withf(move |event| event.user_id == user_id)
+            .returning(|event| {
+                Ok(User::new(
+                    event.user_id,
+                    event.name,
+                    event.email,
+                    Role::User,
+                ))
+            });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(registry);
+
+    let req = Request::put(v1(&format!("/users/{user_id}")))
+        .bearer()
+        .header("content-type", "application/json")
+        .body(Body::from(
+            r#"{"name": "Eleazar Fig", "email": "eleazar.fig@example.com"}"#,
+        ))?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected);
+
+    Ok(())
+}
+
+#[rstest]
+#[case(Ok(()), StatusCode::OK)]
+#[case(
+    Err(AppError::UnprocessableEntity(
+        Message::new(MessageKey::UserHasUnreturnedCheckouts).arg(UserId::new()).arg(1)
+    )),
+    StatusCode::UNPROCESSABLE_ENTITY
+)]
+#[tokio::test]
+async fn deactivate_user(
+    #[case] result: AppResult<()>,
+    #[case] expected: StatusCode,
+) -> anyhow::Result<()> {
+    let user_id = UserId::new();
+    let mut registry = MockAppRegistryExt::new();
+    registry
+        .expect_auth_use_case()
+        .returning(|| Arc::new(mock_auth_use_case_with_role(Role::Admin)));
+    registry.expect_user_use_case().return_once(move || {
+        let mut mock = MockUserUseCase::new();
+        mock.expect_deactivate_user()
+            .withf(move |event| event.user_id == user_id)
+            .return_once(move |_| result);
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(registry);
+
+    let req = Request::delete(v1(&format!("/users/{user_id}")))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected);
+
+    Ok(())
+}
+
+#[rstest]
+#[case(Role::Admin, StatusCode::OK)]
+#[case(Role::User, StatusCode::FORBIDDEN)]
+#[tokio::test]
+async fn purge_user(#[case] role: Role, #[case] expected: StatusCode) -> anyhow::Result<()> {
+    let user_id = UserId::new();
+    let mut registry = MockAppRegistryExt::new();
+    registry
+        .expect_auth_use_case()
+        .returning(move || Arc::new(mock_auth_use_case_with_role(role.clone())));
+    registry.expect_user_use_case().returning(move || {
+        let mut mock = MockUserUseCase::new();
+        mock.expect_delete_user()
+            .withf(move |event| event.user_id == user_id)
+            .returning(|_| Ok(()));
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(registry);
+
+    let req = Request::delete(v1(&format!("/users/{user_id}/purge")))
+        .bearer()
+        .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), expected);
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn list_users_200() -> anyhow::Result<()> {
+    let mut registry = MockAppRegistryExt::new();
+    registry
+        .expect_auth_use_case()
+        .returning(|| Arc::new(mock_auth_use_case_with_role(Role::Admin)));
+    registry.expect_user_use_case().returning(|| {
+        let mut mock = MockUserUseCase::new();
+        mock.expect_list_users()
+            .withf(|options| {
+                options.limit == 10
+                    && options.offset == 20
+                    && options.query.as_deref() == Some("fig")
+                    && options.role == Some(Role::Admin)
+                    && options.status == UserStatus::All
+                    && options.sort == UserSortKey::Name
+                    && options.order == SortOrder::Asc
+            })
+            .returning(|options| {
+                Ok(PaginatedList {
+                    total: 21,
+                    limit: options.limit,
+                    offset: options.offset,
+                    items: vec![User::new(
+                        UserId::new(),
+                        "Eleazar Fig".parse().unwrap(),
+                        "eleazar.fig@example.com".parse().unwrap(),
+                        Role::Admin,
+                    )],
+                })
+            });
+        Arc::new(mock)
+    });
+
+    let app: axum::Router = make_router(registry);
+
+    let req = Request::get(v1(
+        "/users?limit=10&offset=20&q=%20fig%20&role=Admin&status=all&sort=name&order=asc",
+    ))
+    .bearer()
+    .body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let result = deserialize_json!(resp, PaginatedUserResponse);
+    assert_eq!(result.total, 21);
+    assert_eq!(result.items.len(), 1);
+
+    Ok(())
+}
+
+#[rstest]
+#[case("/users?limit=101")]
+#[case("/users?offset=-1")]
+#[case("/users?sort=password")]
+#[case("/users?role=Owner")]
+#[case("/users?status=deleted")]
+#[tokio::test]
+async fn list_users_400(#[case] path: &str) -> anyhow::Result<()> {
+    let mut registry = MockAppRegistryExt::new();
+    registry
+        .expect_auth_use_case()
+        .returning(|| Arc::new(mock_auth_use_case_with_role(Role::Admin)));
+
+    let app: axum::Router = make_router(registry);
+
+    let req = Request::get(v1(path)).bearer().body(Body::empty())?;
+    let resp = app.oneshot(req).await?;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
@@ -39,8 +39,8 @@ const DeleteUserButton: FC<DeleteUserButtonProps> = ({
 
     if (res.ok) {
       toast({
-        title: "ユーザーを削除しました",
-        description: `ユーザー「${user.name}」を削除しました`,
+        title: "ユーザーを無効化しました",
+        description: `ユーザー「${user.name}」を無効化しました`,
         status: "success",
         duration: 5000,
         isClosable: true,
@@ -49,8 +49,9 @@ const DeleteUserButton: FC<DeleteUserButtonProps> = ({
       mutate(["/api/v1/users", accessToken]);
     } else {
       toast({
-        title: "ユーザーを削除できませんでした",
-        description: "サーバーからエラー応答が返却されました。",
+        title: "ユーザーを無効化できませんでした",
+        description:
+          "返却されていない貸出がないか確認してください。サーバーからエラー応答が返却されました。",
         status: "error",
         duration: 5000,
         isClosable: true,
@@ -75,13 +76,13 @@ const DeleteUserButton: FC<DeleteUserButtonProps> = ({
         <AlertDialogOverlay>
           <AlertDialogContent>
             <AlertDialogHeader fontWeight="bold"></AlertDialogHeader>
-            <AlertDialogBody>{`ユーザー「${user.name}」を削除しますか？`}</AlertDialogBody>
+            <AlertDialogBody>{`ユーザー「${user.name}」を無効化しますか？蔵書や貸出の履歴は残ります。`}</AlertDialogBody>
             <AlertDialogFooter>
               <Button ref={cancelRef} onClick={onClose}>
                 Cancel
               </Button>
               <Button colorScheme="red" onClick={handleDelete} ml={3}>
-                Delete
+                Deactivate
               </Button>
             </AlertDialogFooter>
           </AlertDialogContent>
@@ -56,7 +56,7 @@ const UpdateUserRoleSelector: FC<UpdateUserRoleSelectorProps> = ({
         handleUpdateRole(e.target.value);
       }}
     >
-      {["Admin", "User"].map((r) => (
+      {["Admin", "Librarian", "User"].map((r) => (
         <option
           key={`${user.id}-${r}`}
           {...{
@@ -6,5 +6,8 @@ export type User = {
 };
 
 export type Users = {
+  total: number;
+  limit: number;
+  offset: number;
   items: User[];
 };
@@ -40,9 +40,30 @@ resource "aws_apprunner_service" "backend_book_manager" {
       image_configuration {
         port = "8080"
         runtime_environment_variables = {
-          AUTH_TOKEN_TTL = 86400
-          HOST           = "0.0.0.0"
-          PORT           = 8080
+          AUTH_BACKEND                   = "redis"
+          AUTH_EMAIL_VERIFICATION_TTL    = 86400
+          AUTH_LOCKOUT_BASE_SECS         = 30
+          AUTH_LOCKOUT_EMAIL_THRESHOLD   = 5
+          AUTH_LOCKOUT_IP_THRESHOLD      = 20
+          AUTH_LOCKOUT_MAX_SECS          = 3600
+          AUTH_MFA_CHALLENGE_TTL         = 300
+          AUTH_MFA_ISSUER                = "rusty-book-manager"
+          AUTH_MFA_REQUIRED_FOR_ADMIN    = true
+          AUTH_PASSWORD_HASH_MEMORY_COST = 19456
+          AUTH_PASSWORD_HASH_PARALLELISM = 1
+          AUTH_PASSWORD_HASH_TIME_COST   = 2
+          AUTH_PASSWORD_MIN_CHAR_CLASSES = 3
+          AUTH_PASSWORD_MIN_LENGTH       = 12
+          AUTH_PASSWORD_RESET_TTL        = 3600
+          AUTH_REFRESH_TOKEN_TTL         = 2592000
+          AUTH_TOKEN_TTL                 = 86400
+          CHECKOUT_HOLD_PICKUP_DAYS      = 3
+          CHECKOUT_LOAN_PERIOD_DAYS      = 14
+          CHECKOUT_MAX_LOANS             = 5
+          CHECKOUT_MAX_RENEWALS          = 2
+          HOST                           = "0.0.0.0"
+          MAIL_SENDER                    = "noreply@example.com"
+          PORT                           = 8080
         }
         runtime_environment_secrets = {
           DATABASE_HOST     = "${var.book_app_secrets_manager_arn}:DATABASE_HOST::"
@@ -1,4 +1,7 @@
+pub mod mail;
 pub mod model;
+pub mod oidc;
+pub mod password;
 pub mod repository;
 pub mod unit_of_work;
 pub mod use_case;
@@ -0,0 +1,9 @@
+use crate::model::mail::Mail;
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+#[mockall::automock]
+#[async_trait]
+pub trait MailSender: Send + Sync {
+    async fn send(&self, mail: Mail) -> AppResult<()>;
+}
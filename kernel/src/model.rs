@@ -1,8 +1,16 @@
 pub mod auth;
 pub mod book;
 pub mod checkout;
+pub mod hold;
 pub mod id;
 pub mod list;
+pub mod loan_policy;
+pub mod mail;
+pub mod mfa;
+pub mod oidc;
+pub mod password;
+pub mod personal_access_token;
 pub mod role;
+pub mod session;
 pub mod user;
 pub mod value;
@@ -1,3 +1,45 @@
+use crate::model::{
+    id::UserId, personal_access_token::PERSONAL_ACCESS_TOKEN_PREFIX, value::UserEmail,
+};
+
 pub mod event;
 
 pub struct AccessToken(pub String);
+
+impl AccessToken {
+    pub fn is_personal_access_token(&self) -> bool {
+        self.0.starts_with(PERSONAL_ACCESS_TOKEN_PREFIX)
+    }
+}
+
+pub struct PasswordResetToken(pub String);
+
+pub struct EmailVerificationToken(pub String);
+
+// 確認を待っているメールアドレスの変更
+#[derive(Debug, PartialEq, Eq)]
+pub struct PendingEmailChange {
+    pub user_id: UserId,
+    pub email: UserEmail,
+}
+
+pub struct RefreshToken(pub String);
+
+// パスワード認証を通過し、確認コードの入力を待っている状態を表すトークン
+pub struct MfaChallengeToken(pub String);
+
+// 保存されているリフレッシュトークンの状態
+#[derive(Debug)]
+pub struct RefreshTokenEntry {
+    pub user_id: UserId,
+    pub family_id: String,
+    // ローテーション済み（使用済み）かどうか
+    pub rotated: bool,
+}
+
+// ログインの失敗回数を数える単位
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoginAttemptKey {
+    Email(String),
+    IpAddress(String),
+}
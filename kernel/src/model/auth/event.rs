@@ -1,18 +1,96 @@
-use crate::model::id::UserId;
+use crate::model::{id::UserId, session::SessionClient, value::UserEmail};
 use uuid::Uuid;
 
 #[derive(Debug)]
 pub struct CreateToken {
     pub user_id: UserId,
     pub access_token: String,
+    pub client: SessionClient,
+    // ログインごとに払い出すリフレッシュトークンの系列。ローテーションしても引き継ぐ
+    pub family_id: String,
 }
 
 impl CreateToken {
-    pub fn new(user_id: UserId) -> Self {
+    pub fn new(user_id: UserId, client: SessionClient) -> Self {
+        Self::with_family(user_id, client, Uuid::new_v4().simple().to_string())
+    }
+
+    pub fn with_family(user_id: UserId, client: SessionClient, family_id: String) -> Self {
         let access_token = Uuid::new_v4().simple().to_string();
         Self {
             user_id,
             access_token,
+            client,
+            family_id,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct CreateRefreshToken {
+    pub user_id: UserId,
+    pub family_id: String,
+    pub refresh_token: String,
+}
+
+impl CreateRefreshToken {
+    pub fn new(user_id: UserId, family_id: String) -> Self {
+        let refresh_token = Uuid::new_v4().simple().to_string();
+        Self {
+            user_id,
+            family_id,
+            refresh_token,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct CreateMfaChallenge {
+    pub user_id: UserId,
+    pub challenge_token: String,
+}
+
+impl CreateMfaChallenge {
+    pub fn new(user_id: UserId) -> Self {
+        let challenge_token = Uuid::new_v4().simple().to_string();
+        Self {
+            user_id,
+            challenge_token,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct CreatePasswordResetToken {
+    pub user_id: UserId,
+    pub reset_token: String,
+}
+
+impl CreatePasswordResetToken {
+    pub fn new(user_id: UserId) -> Self {
+        let reset_token = Uuid::new_v4().simple().to_string();
+        Self {
+            user_id,
+            reset_token,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct CreateEmailVerificationToken {
+    pub user_id: UserId,
+    // 確認が済むまでは反映しない、変更後のメールアドレス
+    pub email: UserEmail,
+    pub verification_token: String,
+}
+
+impl CreateEmailVerificationToken {
+    pub fn new(user_id: UserId, email: UserEmail) -> Self {
+        let verification_token = Uuid::new_v4().simple().to_string();
+        Self {
+            user_id,
+            email,
+            verification_token,
         }
     }
 }
@@ -1,11 +1,12 @@
 use crate::model::{
-    id::{BookId, CheckoutId},
+    id::{BookId, CheckoutId, UserId},
     user::{BookOwner, CheckoutUser},
     value::{BookAuthor, BookDescription, BookIsbn, BookTitle},
 };
 use chrono::{DateTime, Utc};
 use derive_new::new;
 use shared::error::AppError;
+use strum::{AsRefStr, EnumIter, EnumString};
 
 pub mod event;
 
@@ -109,10 +110,16 @@ impl TryFrom<(event::CreateBook, BookOwner)> for Book {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct BookListOptions {
     pub limit: i64,
     pub offset: i64,
+    pub query: Option<String>,
+    pub author: Option<String>,
+    pub isbn: Option<String>,
+    pub owner: Option<UserId>,
+    pub available_only: bool,
+    pub checked_out_by: Option<UserId>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, new)]
@@ -120,6 +127,7 @@ pub struct Checkout {
     checkout_id: CheckoutId,
     checked_out_by: CheckoutUser,
     checked_out_at: DateTime<Utc>,
+    due_at: DateTime<Utc>,
 }
 
 impl Checkout {
@@ -135,7 +143,55 @@ impl Checkout {
         self.checked_out_at
     }
 
-    pub fn into_parts(self) -> (CheckoutId, CheckoutUser, DateTime<Utc>) {
-        (self.checkout_id, self.checked_out_by, self.checked_out_at)
+    pub fn due_at(&self) -> DateTime<Utc> {
+        self.due_at
+    }
+
+    pub fn is_overdue(&self, now: DateTime<Utc>) -> bool {
+        now > self.due_at
+    }
+
+    pub fn into_parts(self) -> (CheckoutId, CheckoutUser, DateTime<Utc>, DateTime<Utc>) {
+        (
+            self.checkout_id,
+            self.checked_out_by,
+            self.checked_out_at,
+            self.due_at,
+        )
+    }
+}
+
+// 蔵書に対して行った操作の種類。変更履歴には snake_case の文字列で保存する
+#[derive(Debug, Clone, Copy, EnumString, AsRefStr, EnumIter, PartialEq, Eq)]
+#[strum(serialize_all = "snake_case")]
+pub enum BookChangeAction {
+    Update,
+    Delete,
+    Transfer,
+}
+
+#[derive(Debug, PartialEq, Eq, new)]
+pub struct BookChangeLog {
+    action: BookChangeAction,
+    // 操作したユーザーが削除されている場合は None
+    performed_by: Option<UserId>,
+    performed_at: DateTime<Utc>,
+}
+
+impl BookChangeLog {
+    pub fn action(&self) -> BookChangeAction {
+        self.action
+    }
+
+    pub fn performed_by(&self) -> Option<UserId> {
+        self.performed_by
+    }
+
+    pub fn performed_at(&self) -> DateTime<Utc> {
+        self.performed_at
+    }
+
+    pub fn into_parts(self) -> (BookChangeAction, Option<UserId>, DateTime<Utc>) {
+        (self.action, self.performed_by, self.performed_at)
     }
 }
@@ -26,3 +26,18 @@ pub struct DeleteBook {
     pub book_id: BookId,
     pub requested_user: UserId,
 }
+
+#[derive(Debug)]
+pub struct UpdateBookOwner {
+    pub book_id: BookId,
+    pub new_owner: UserId,
+    pub requested_user: UserId,
+}
+
+// 退職者などが所有するすべての蔵書を別のユーザーに引き継ぐ
+#[derive(Debug)]
+pub struct TransferAllBooks {
+    pub from_user: UserId,
+    pub to_user: UserId,
+    pub requested_user: UserId,
+}
@@ -11,6 +11,8 @@ pub struct Checkout {
     id: CheckoutId,
     checked_out_by: UserId,
     checked_out_at: DateTime<Utc>,
+    due_at: DateTime<Utc>,
+    renewal_count: i64,
     returned_at: Option<DateTime<Utc>>,
     book: CheckoutBook,
 }
@@ -20,6 +22,8 @@ impl Checkout {
         id: CheckoutId,
         checked_out_by: UserId,
         checked_out_at: DateTime<Utc>,
+        due_at: DateTime<Utc>,
+        renewal_count: i64,
         returned_at: Option<DateTime<Utc>>,
         book: CheckoutBook,
     ) -> Self {
@@ -27,6 +31,8 @@ impl Checkout {
             id,
             checked_out_by,
             checked_out_at,
+            due_at,
+            renewal_count,
             returned_at,
             book,
         }
@@ -44,10 +50,22 @@ impl Checkout {
         self.checked_out_at
     }
 
+    pub fn due_at(&self) -> DateTime<Utc> {
+        self.due_at
+    }
+
+    pub fn renewal_count(&self) -> i64 {
+        self.renewal_count
+    }
+
     pub fn returned_at(&self) -> Option<DateTime<Utc>> {
         self.returned_at
     }
 
+    pub fn is_overdue(&self, now: DateTime<Utc>) -> bool {
+        self.returned_at.unwrap_or(now) > self.due_at
+    }
+
     pub fn book(&self) -> &CheckoutBook {
         &self.book
     }
@@ -97,4 +115,5 @@ pub struct CheckoutState {
     pub book_id: BookId,
     pub checkout_id: Option<CheckoutId>,
     pub user_id: Option<UserId>,
+    pub renewal_count: i64,
 }
@@ -15,3 +15,11 @@ pub struct UpdateReturned {
     pub returned_by: UserId,
     pub returned_at: DateTime<Utc>,
 }
+
+#[derive(Debug)]
+pub struct UpdateRenewed {
+    pub checkout_id: CheckoutId,
+    pub book_id: BookId,
+    pub renewed_by: UserId,
+    pub renewed_at: DateTime<Utc>,
+}
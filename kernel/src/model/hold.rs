@@ -0,0 +1,72 @@
+use crate::model::{
+    checkout::CheckoutBook,
+    id::{HoldId, UserId},
+};
+use chrono::{DateTime, Utc};
+
+pub mod event;
+
+#[derive(Debug)]
+pub struct Hold {
+    id: HoldId,
+    held_by: UserId,
+    position: i64,
+    created_at: DateTime<Utc>,
+    ready_at: Option<DateTime<Utc>>,
+    expires_at: Option<DateTime<Utc>>,
+    book: CheckoutBook,
+}
+
+impl Hold {
+    pub fn new(
+        id: HoldId,
+        held_by: UserId,
+        position: i64,
+        created_at: DateTime<Utc>,
+        ready_at: Option<DateTime<Utc>>,
+        expires_at: Option<DateTime<Utc>>,
+        book: CheckoutBook,
+    ) -> Self {
+        Self {
+            id,
+            held_by,
+            position,
+            created_at,
+            ready_at,
+            expires_at,
+            book,
+        }
+    }
+
+    pub fn id(&self) -> HoldId {
+        self.id
+    }
+
+    pub fn held_by(&self) -> UserId {
+        self.held_by
+    }
+
+    pub fn position(&self) -> i64 {
+        self.position
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    pub fn ready_at(&self) -> Option<DateTime<Utc>> {
+        self.ready_at
+    }
+
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.expires_at
+    }
+
+    pub fn book(&self) -> &CheckoutBook {
+        &self.book
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.ready_at.is_some()
+    }
+}
@@ -0,0 +1,17 @@
+use crate::model::id::{BookId, HoldId, UserId};
+use chrono::{DateTime, Utc};
+
+#[derive(Debug)]
+pub struct CreateHold {
+    pub book_id: BookId,
+    pub held_by: UserId,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug)]
+pub struct DeleteHold {
+    pub hold_id: HoldId,
+    pub book_id: BookId,
+    pub requested_user: UserId,
+    pub requested_at: DateTime<Utc>,
+}
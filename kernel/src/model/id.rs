@@ -61,6 +61,8 @@ macro_rules! define_id {
 define_id!(UserId);
 define_id!(BookId);
 define_id!(CheckoutId);
+define_id!(HoldId);
+define_id!(PersonalAccessTokenId);
 
 #[cfg(test)]
 mod tests {
@@ -1,3 +1,5 @@
+use strum::AsRefStr;
+
 #[derive(Debug)]
 pub struct PaginatedList<T> {
     pub total: i64,
@@ -11,3 +13,11 @@ impl<T> PaginatedList<T> {
         self.items
     }
 }
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, AsRefStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum SortOrder {
+    Asc,
+    #[default]
+    Desc,
+}
@@ -0,0 +1,58 @@
+use crate::model::role::Role;
+use shared::config::CheckoutConfig;
+
+pub mod event;
+
+#[derive(Debug)]
+pub struct LoanPolicy {
+    role: Role,
+    max_loans: i64,
+    loan_period_days: i64,
+    max_renewals: i64,
+}
+
+impl LoanPolicy {
+    pub fn new(role: Role, max_loans: i64, loan_period_days: i64, max_renewals: i64) -> Self {
+        Self {
+            role,
+            max_loans,
+            loan_period_days,
+            max_renewals,
+        }
+    }
+
+    // ロールごとの設定が登録されていない場合は設定値を既定のポリシーとする
+    pub fn default_for(role: Role, config: &CheckoutConfig) -> Self {
+        Self::new(
+            role,
+            config.max_loans,
+            config.loan_period_days,
+            config.max_renewals,
+        )
+    }
+
+    pub fn role(&self) -> &Role {
+        &self.role
+    }
+
+    pub fn max_loans(&self) -> i64 {
+        self.max_loans
+    }
+
+    pub fn loan_period_days(&self) -> i64 {
+        self.loan_period_days
+    }
+
+    pub fn max_renewals(&self) -> i64 {
+        self.max_renewals
+    }
+
+    pub fn into_parts(self) -> (Role, i64, i64, i64) {
+        (
+            self.role,
+            self.max_loans,
+            self.loan_period_days,
+            self.max_renewals,
+        )
+    }
+}
@@ -0,0 +1,9 @@
+use crate::model::role::Role;
+
+#[derive(Debug)]
+pub struct UpdateLoanPolicy {
+    pub role: Role,
+    pub max_loans: i64,
+    pub loan_period_days: i64,
+    pub max_renewals: i64,
+}
@@ -0,0 +1,9 @@
+use crate::model::value::UserEmail;
+use derive_new::new;
+
+#[derive(Debug, new)]
+pub struct Mail {
+    pub to: UserEmail,
+    pub subject: String,
+    pub body: String,
+}
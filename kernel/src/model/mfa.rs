@@ -0,0 +1,128 @@
+use crate::model::{
+    auth::{AccessToken, MfaChallengeToken, RefreshToken},
+    id::UserId,
+};
+use chrono::Utc;
+use shared::error::{AppError, AppResult};
+use totp_rs::{Algorithm, Secret, TOTP};
+use uuid::Uuid;
+
+pub mod event;
+
+// 二要素認証を有効にした際に払い出すリカバリーコードの数
+pub const RECOVERY_CODE_COUNT: usize = 10;
+
+#[derive(Debug)]
+pub struct UserMfa {
+    pub user_id: UserId,
+    // Base32 でエンコードした TOTP の共有秘密
+    pub secret: String,
+    // 確認コードの入力による登録の完了まで false のまま
+    pub enabled: bool,
+}
+
+// 認証アプリへの登録に必要な情報
+#[derive(Debug)]
+pub struct MfaEnrollment {
+    pub secret: String,
+    pub otpauth_url: String,
+}
+
+impl MfaEnrollment {
+    pub fn new(secret: String, issuer: &str, account_name: &str) -> AppResult<Self> {
+        let otpauth_url = totp(&secret, issuer, account_name)?.get_url();
+        Ok(Self {
+            secret,
+            otpauth_url,
+        })
+    }
+}
+
+// パスワード認証の結果。二要素認証が必要な場合はトークンの代わりにチャレンジを返す
+pub enum LoginOutcome {
+    Authenticated {
+        user_id: UserId,
+        access_token: AccessToken,
+        refresh_token: RefreshToken,
+    },
+    MfaRequired {
+        challenge_token: MfaChallengeToken,
+    },
+    // 二要素認証が必須の管理者が未設定の場合は、登録を済ませてからログインを完了させる
+    MfaEnrollmentRequired {
+        challenge_token: MfaChallengeToken,
+        enrollment: MfaEnrollment,
+    },
+}
+
+pub fn generate_secret() -> String {
+    Secret::generate_secret().to_encoded().to_string()
+}
+
+pub fn generate_recovery_codes() -> Vec<String> {
+    (0..RECOVERY_CODE_COUNT)
+        .map(|_| {
+            let raw = Uuid::new_v4().simple().to_string();
+            format!("{}-{}", &raw[..5], &raw[5..10])
+        })
+        .collect()
+}
+
+// 時計のずれを考慮して前後 1 ステップ（30 秒）分のコードも受け付ける。
+// 一致した場合は、同じコードの再利用を拒否するために一致したタイムステップを返す
+pub fn verify_code(secret: &str, code: &str) -> AppResult<Option<i64>> {
+    verify_code_at(secret, code, Utc::now().timestamp().max(0) as u64)
+}
+
+fn verify_code_at(secret: &str, code: &str, time: u64) -> AppResult<Option<i64>> {
+    let totp = totp(secret, "", "")?;
+    let current = time / totp.step;
+    let skew = u64::from(totp.skew);
+    // 1 ステップずつ照合して、どのタイムステップのコードかを特定する
+    let exact = TOTP { skew: 0, ..totp };
+    Ok((current.saturating_sub(skew)..=current + skew)
+        .find(|step| exact.check(code.trim(), step * exact.step))
+        .map(|step| step as i64))
+}
+
+fn totp(secret: &str, issuer: &str, account_name: &str) -> AppResult<TOTP> {
+    let to_error = |e: String| AppError::ConversionEntityError(e);
+    let secret = Secret::Encoded(secret.to_string())
+        .to_bytes()
+        .map_err(|e| to_error(e.to_string()))?;
+    let issuer = (!issuer.is_empty()).then(|| issuer.to_string());
+    TOTP::new(
+        Algorithm::SHA1,
+        6,
+        1,
+        30,
+        secret,
+        issuer,
+        account_name.to_string(),
+    )
+    .map_err(|e| to_error(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_code() -> anyhow::Result<()> {
+        let secret = generate_secret();
+        let code = totp(&secret, "", "")?.generate_current()?;
+        assert!(verify_code(&secret, &code)?.is_some());
+        assert!(verify_code(&generate_secret(), &code)?.is_none());
+
+        // 前後 1 ステップまでのコードは、生成されたタイムステップとともに受け付ける
+        let time = 1_000 * 30;
+        let previous = totp(&secret, "", "")?.generate(time - 30);
+        assert_eq!(verify_code_at(&secret, &previous, time)?, Some(999));
+        assert_eq!(verify_code_at(&secret, &previous, time + 30)?, None);
+
+        let enrollment = MfaEnrollment::new(secret, "rusty-book-manager", "admin@example.com")?;
+        assert!(enrollment.otpauth_url.starts_with("otpauth://totp/"));
+        assert!(enrollment.otpauth_url.contains("issuer=rusty-book-manager"));
+        Ok(())
+    }
+}
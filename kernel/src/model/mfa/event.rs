@@ -0,0 +1,15 @@
+use crate::model::id::UserId;
+
+// 未完了の登録があれば、新しい共有秘密で置き換える
+#[derive(Debug)]
+pub struct StartMfaEnrollment {
+    pub user_id: UserId,
+    pub secret: String,
+}
+
+#[derive(Debug)]
+pub struct EnableMfa {
+    pub user_id: UserId,
+    // 平文のリカバリーコード。永続化する際はハッシュ化する
+    pub recovery_codes: Vec<String>,
+}
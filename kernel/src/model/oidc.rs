@@ -0,0 +1,38 @@
+use uuid::Uuid;
+
+// 認可リクエストごとに払い出し、コールバックを受けるまで保存しておく値
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OidcAuthorizationRequest {
+    // CSRF 対策としてコールバックで照合する値
+    pub state: String,
+    // ID トークンの再利用を防ぐため、ID トークンに含まれる値と照合する
+    pub nonce: String,
+    // PKCE の検証に使う値。ID プロバイダーにはハッシュのみを渡す
+    pub code_verifier: String,
+}
+
+impl OidcAuthorizationRequest {
+    pub fn new() -> Self {
+        Self {
+            state: Uuid::new_v4().simple().to_string(),
+            nonce: Uuid::new_v4().simple().to_string(),
+            code_verifier: format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple()),
+        }
+    }
+}
+
+impl Default for OidcAuthorizationRequest {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// 検証済みの ID トークンから取り出したクレーム
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OidcClaims {
+    pub subject: String,
+    pub email: String,
+    // ID プロバイダーが返さなかった場合は None
+    pub email_verified: Option<bool>,
+    pub name: Option<String>,
+}
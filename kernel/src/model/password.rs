@@ -0,0 +1,144 @@
+use shared::{
+    config::PasswordPolicyConfig,
+    i18n::{Locale, Message, MessageKey},
+};
+use std::{collections::HashSet, sync::LazyLock};
+
+// 漏えいしたパスワードの集計などでよく使われているもの。小文字にして照合する
+static COMMON_PASSWORDS: LazyLock<HashSet<&'static str>> = LazyLock::new(|| {
+    include_str!("password/common_passwords.txt")
+        .lines()
+        .collect()
+});
+
+// 名前やメールアドレスの一部がこれより短い場合は、偶然の一致とみなして照合しない
+const MIN_PERSONAL_INFO_LENGTH: usize = 3;
+
+/// パスワードを設定するリクエストで共通に使う garde のコンテキスト。
+/// 登録・変更・再設定のいずれでも、同じ 4 つのルールをこのコンテキストで検証する
+pub struct PasswordContext {
+    pub policy: PasswordPolicyConfig,
+    // パスワードに含めてはいけない、パスワードを設定するユーザーの名前とメールアドレス
+    pub name: String,
+    pub email: String,
+}
+
+impl PasswordContext {
+    pub fn new(policy: PasswordPolicyConfig, name: &str, email: &str) -> Self {
+        Self {
+            policy,
+            name: name.into(),
+            email: email.into(),
+        }
+    }
+}
+
+fn error(message: Message) -> garde::Error {
+    garde::Error::new(message.render(Locale::current()))
+}
+
+// 以下はいずれも garde の custom ルールとして使い、違反したルールごとにエラーを返す
+
+pub fn min_length(value: &str, context: &PasswordContext) -> garde::Result {
+    let min_length = context.policy.min_length;
+    if value.chars().count() < min_length {
+        return Err(error(
+            Message::new(MessageKey::PasswordTooShort).arg(min_length),
+        ));
+    }
+    Ok(())
+}
+
+pub fn char_classes(value: &str, context: &PasswordContext) -> garde::Result {
+    let min_char_classes = context.policy.min_char_classes;
+    let classes = [
+        value.chars().any(|c| c.is_lowercase()),
+        value.chars().any(|c| c.is_uppercase()),
+        value.chars().any(|c| c.is_numeric()),
+        value.chars().any(|c| !c.is_alphanumeric()),
+    ];
+    if classes.into_iter().filter(|&found| found).count() < min_char_classes {
+        return Err(error(
+            Message::new(MessageKey::PasswordTooFewCharClasses).arg(min_char_classes),
+        ));
+    }
+    Ok(())
+}
+
+pub fn not_common(value: &str, _context: &PasswordContext) -> garde::Result {
+    if COMMON_PASSWORDS.contains(value.to_lowercase().as_str()) {
+        return Err(error(Message::new(MessageKey::PasswordTooCommon)));
+    }
+    Ok(())
+}
+
+// 名前・メールアドレスや、それらを区切った各単語を含むパスワードを拒否する
+pub fn not_personal_info(value: &str, context: &PasswordContext) -> garde::Result {
+    let PasswordContext { name, email, .. } = context;
+    let value = value.to_lowercase();
+    let local_part = email.split('@').next().unwrap_or_default();
+    let words = format!("{name} {local_part}");
+    let words = words.split(|c: char| c.is_whitespace() || matches!(c, '.' | '_' | '-' | '+'));
+    if [name.as_str(), email.as_str()]
+        .into_iter()
+        .chain(words)
+        .map(str::to_lowercase)
+        .filter(|info| info.chars().count() >= MIN_PERSONAL_INFO_LENGTH)
+        .any(|info| value.contains(&info))
+    {
+        return Err(error(Message::new(
+            MessageKey::PasswordContainsPersonalInfo,
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::i18n::LOCALE;
+
+    const POLICY: PasswordPolicyConfig = PasswordPolicyConfig {
+        min_length: 10,
+        min_char_classes: 3,
+    };
+
+    #[test]
+    fn test_password_rules() {
+        let context = PasswordContext::new(POLICY, "Eleazar Fig", "eleazar.fig@example.com");
+        let context = &context;
+
+        assert!(min_length("Sh0rt!", context).is_err());
+        assert!(min_length("long-enough", context).is_ok());
+
+        assert!(char_classes("alllowercase", context).is_err());
+        assert!(char_classes("lower-and-digits-123", context).is_ok());
+        assert!(char_classes("Lower-And-Upper", context).is_ok());
+
+        assert!(not_common("Password123", context).is_err());
+        assert!(not_common("correct horse battery staple", context).is_ok());
+
+        let check = |value: &str| not_personal_info(value, context);
+        assert!(check("my-ELEAZAR.FIG-2024").is_err());
+        assert!(check("eleazar fig rocks").is_err());
+        assert!(check("Fig-tree-2024").is_err());
+        assert!(check("Unrelated-Passw0rd").is_ok());
+    }
+
+    #[test]
+    fn test_password_rule_messages_follow_locale() {
+        let context = PasswordContext::new(POLICY, "Eleazar Fig", "eleazar.fig@example.com");
+        let message = |locale| {
+            let result = LOCALE.sync_scope(locale, || min_length("Sh0rt!", &context));
+            result.unwrap_err().message().to_string()
+        };
+        assert_eq!(
+            message(Locale::Ja),
+            "パスワードは10文字以上にしてください。"
+        );
+        assert_eq!(
+            message(Locale::En),
+            "The password must be at least 10 characters long."
+        );
+    }
+}
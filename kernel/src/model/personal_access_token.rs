@@ -0,0 +1,76 @@
+use crate::model::id::{PersonalAccessTokenId, UserId};
+use chrono::{DateTime, Utc};
+use strum::{AsRefStr, EnumIter, EnumString};
+
+pub mod event;
+
+// パーソナルアクセストークンをセッション用のトークンと区別するためのプレフィックス
+pub const PERSONAL_ACCESS_TOKEN_PREFIX: &str = "pat_";
+
+#[derive(Debug, Clone, Copy, EnumString, AsRefStr, EnumIter, PartialEq, Eq)]
+#[strum(serialize_all = "snake_case")]
+pub enum TokenScope {
+    Read,
+    Write,
+}
+
+#[derive(Debug)]
+pub struct PersonalAccessToken {
+    id: PersonalAccessTokenId,
+    user_id: UserId,
+    name: String,
+    scopes: Vec<TokenScope>,
+    expires_at: Option<DateTime<Utc>>,
+    last_used_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+}
+
+impl PersonalAccessToken {
+    pub fn new(
+        id: PersonalAccessTokenId,
+        user_id: UserId,
+        name: String,
+        scopes: Vec<TokenScope>,
+        expires_at: Option<DateTime<Utc>>,
+        last_used_at: Option<DateTime<Utc>>,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id,
+            user_id,
+            name,
+            scopes,
+            expires_at,
+            last_used_at,
+            created_at,
+        }
+    }
+
+    pub fn id(&self) -> PersonalAccessTokenId {
+        self.id
+    }
+
+    pub fn user_id(&self) -> UserId {
+        self.user_id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn scopes(&self) -> &[TokenScope] {
+        &self.scopes
+    }
+
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.expires_at
+    }
+
+    pub fn last_used_at(&self) -> Option<DateTime<Utc>> {
+        self.last_used_at
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+}
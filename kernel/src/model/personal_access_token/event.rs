@@ -0,0 +1,45 @@
+use crate::model::{
+    id::{PersonalAccessTokenId, UserId},
+    personal_access_token::{PERSONAL_ACCESS_TOKEN_PREFIX, TokenScope},
+};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+#[derive(Debug)]
+pub struct CreatePersonalAccessToken {
+    pub user_id: UserId,
+    pub name: String,
+    pub scopes: Vec<TokenScope>,
+    pub expires_at: Option<DateTime<Utc>>,
+    // 作成時にのみ利用者へ返す平文のトークン。永続化する際はハッシュ化する
+    pub secret: String,
+}
+
+impl CreatePersonalAccessToken {
+    pub fn new(
+        user_id: UserId,
+        name: String,
+        scopes: Vec<TokenScope>,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        let secret = format!(
+            "{}{}{}",
+            PERSONAL_ACCESS_TOKEN_PREFIX,
+            Uuid::new_v4().simple(),
+            Uuid::new_v4().simple()
+        );
+        Self {
+            user_id,
+            name,
+            scopes,
+            expires_at,
+            secret,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct DeletePersonalAccessToken {
+    pub token_id: PersonalAccessTokenId,
+    pub requested_user: UserId,
+}
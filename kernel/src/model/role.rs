@@ -1,8 +1,61 @@
 use strum::{AsRefStr, EnumIter, EnumString};
 
-#[derive(Debug, EnumString, AsRefStr, EnumIter, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, EnumString, AsRefStr, EnumIter, Default, PartialEq, Eq)]
 pub enum Role {
     Admin,
+    Librarian,
     #[default]
     User,
 }
+
+// ロールに付与する操作の権限。ロールとの対応は Role::permissions で固定する
+#[derive(Debug, Clone, Copy, AsRefStr, EnumIter, PartialEq, Eq)]
+pub enum Permission {
+    // ユーザーの登録・変更・無効化やロールの変更
+    ManageUsers,
+    // 他のユーザーが所有する蔵書の変更・削除
+    ManageAllBooks,
+    // 借りた本人に代わっての返却などの手続き
+    CheckoutOnBehalf,
+    // 延滞一覧や貸出ポリシーなどの参照
+    ViewReports,
+    // ロールごとの貸出期間や貸出上限などの貸出ポリシーの変更
+    ManageLoanPolicies,
+}
+
+impl Role {
+    pub fn permissions(&self) -> &'static [Permission] {
+        use Permission::*;
+        match self {
+            Self::Admin => &[
+                ManageUsers,
+                ManageAllBooks,
+                CheckoutOnBehalf,
+                ViewReports,
+                ManageLoanPolicies,
+            ],
+            Self::Librarian => &[ManageAllBooks, CheckoutOnBehalf, ViewReports],
+            Self::User => &[],
+        }
+    }
+
+    pub fn has_permission(&self, permission: Permission) -> bool {
+        self.permissions().contains(&permission)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Permission, Role};
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn test_role_permissions() {
+        assert!(Permission::iter().all(|p| Role::Admin.has_permission(p)));
+        assert!(Permission::iter().all(|p| !Role::User.has_permission(p)));
+
+        assert!(Role::Librarian.has_permission(Permission::CheckoutOnBehalf));
+        assert!(!Role::Librarian.has_permission(Permission::ManageUsers));
+        assert!(!Role::Librarian.has_permission(Permission::ManageLoanPolicies));
+    }
+}
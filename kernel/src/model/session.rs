@@ -0,0 +1,56 @@
+use chrono::{DateTime, Utc};
+use derive_new::new;
+
+// ログイン時のクライアント情報。セッション一覧で端末を見分けるために使う
+#[derive(Debug, Clone, Default, PartialEq, Eq, new)]
+pub struct SessionClient {
+    pub device: Option<String>,
+    pub ip_address: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct Session {
+    id: String,
+    client: SessionClient,
+    created_at: DateTime<Utc>,
+    last_seen_at: DateTime<Utc>,
+    current: bool,
+}
+
+impl Session {
+    pub fn new(
+        id: String,
+        client: SessionClient,
+        created_at: DateTime<Utc>,
+        last_seen_at: DateTime<Utc>,
+        current: bool,
+    ) -> Self {
+        Self {
+            id,
+            client,
+            created_at,
+            last_seen_at,
+            current,
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn client(&self) -> &SessionClient {
+        &self.client
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    pub fn last_seen_at(&self) -> DateTime<Utc> {
+        self.last_seen_at
+    }
+
+    pub fn is_current(&self) -> bool {
+        self.current
+    }
+}
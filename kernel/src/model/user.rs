@@ -1,9 +1,11 @@
 use crate::model::{
     id::UserId,
+    list::SortOrder,
     role::Role,
     value::{UserEmail, UserName},
 };
 use shared::error::AppError;
+use strum::AsRefStr;
 
 pub mod event;
 
@@ -106,3 +108,34 @@ impl CheckoutUser {
         (self.id, self.name)
     }
 }
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, AsRefStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum UserSortKey {
+    Name,
+    Email,
+    #[default]
+    CreatedAt,
+}
+
+#[derive(Debug, Default)]
+pub struct UserListOptions {
+    pub limit: i64,
+    pub offset: i64,
+    // 名前・メールアドレスの部分一致で絞り込む
+    pub query: Option<String>,
+    pub role: Option<Role>,
+    pub status: UserStatus,
+    pub sort: UserSortKey,
+    pub order: SortOrder,
+}
+
+// 一覧に含めるユーザーの状態。指定しない場合は無効化済みのユーザーを含めない
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, AsRefStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum UserStatus {
+    #[default]
+    Active,
+    Deactivated,
+    All,
+}
@@ -12,6 +12,25 @@ pub struct CreateUser {
 }
 
 #[derive(Debug)]
+pub struct UpdateUserProfile {
+    pub user_id: UserId,
+    pub name: UserName,
+    pub email: UserEmail,
+}
+
+#[derive(Debug)]
+pub struct UpdateUserName {
+    pub user_id: UserId,
+    pub name: UserName,
+}
+
+#[derive(Debug)]
+pub struct UpdateUserEmail {
+    pub user_id: UserId,
+    pub email: UserEmail,
+}
+
+#[derive(Debug, Clone)]
 pub struct UpdateUserRole {
     pub user_id: UserId,
     pub role: Role,
@@ -24,7 +43,18 @@ pub struct UpdateUserPassword {
     pub new_password: String,
 }
 
+#[derive(Debug, Clone)]
+pub struct DeactivateUser {
+    pub user_id: UserId,
+}
+
 #[derive(Debug)]
 pub struct DeleteUser {
     pub user_id: UserId,
 }
+
+#[derive(Debug)]
+pub struct UpdatePasswordHash {
+    pub user_id: UserId,
+    pub password_hash: String,
+}
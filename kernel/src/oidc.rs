@@ -0,0 +1,16 @@
+use crate::model::oidc::{OidcAuthorizationRequest, OidcClaims};
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+#[mockall::automock]
+#[async_trait]
+pub trait OidcProvider: Send + Sync {
+    // 利用者をリダイレクトさせる、ID プロバイダーの認可エンドポイントの URL を返す
+    async fn authorization_url(&self, request: &OidcAuthorizationRequest) -> AppResult<String>;
+    // 認可コードをトークンと交換し、ID トークンを検証してクレームを返す
+    async fn exchange_code(
+        &self,
+        code: &str,
+        request: &OidcAuthorizationRequest,
+    ) -> AppResult<OidcClaims>;
+}
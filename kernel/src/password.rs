@@ -0,0 +1,9 @@
+use shared::error::AppResult;
+
+#[mockall::automock]
+pub trait PasswordHasher: Send + Sync {
+    fn hash(&self, password: &str) -> AppResult<String>;
+    fn verify(&self, password: &str, password_hash: &str) -> AppResult<bool>;
+    // 現在のアルゴリズムやパラメータと異なる方式で作られたハッシュなら true を返す
+    fn needs_rehash(&self, password_hash: &str) -> bool;
+}
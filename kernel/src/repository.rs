@@ -2,4 +2,8 @@ pub mod auth;
 pub mod book;
 pub mod checkout;
 pub mod health;
+pub mod hold;
+pub mod loan_policy;
+pub mod mfa;
+pub mod personal_access_token;
 pub mod user;
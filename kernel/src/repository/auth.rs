@@ -1,17 +1,112 @@
 use crate::model::{
-    auth::{AccessToken, event::CreateToken},
+    auth::{
+        AccessToken, EmailVerificationToken, LoginAttemptKey, MfaChallengeToken,
+        PasswordResetToken, PendingEmailChange, RefreshToken, RefreshTokenEntry,
+        event::{
+            CreateEmailVerificationToken, CreateMfaChallenge, CreatePasswordResetToken,
+            CreateRefreshToken, CreateToken,
+        },
+    },
     id::UserId,
+    oidc::OidcAuthorizationRequest,
+    session::Session,
 };
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use shared::error::AppResult;
 
 #[mockall::automock]
 #[async_trait]
 pub trait AuthRepository: Send + Sync {
+    async fn create_refresh_token(
+        &self,
+        event: CreateRefreshToken,
+        ttl: u64,
+    ) -> AppResult<RefreshToken>;
     async fn create_token(&self, event: CreateToken) -> AppResult<AccessToken>;
+    async fn delete_all_sessions(&self, user_id: UserId) -> AppResult<()>;
+    async fn delete_session(&self, user_id: UserId, session_id: &str) -> AppResult<()>;
     async fn delete_token(&self, access_token: AccessToken) -> AppResult<()>;
+    // 利用のたびに有効期限を延長し、最終利用日時を更新する
+    async fn extend_token(&self, access_token: &AccessToken) -> AppResult<()>;
+    // 系列が失効している場合は None を返す
+    async fn find_refresh_token(
+        &self,
+        refresh_token: &RefreshToken,
+    ) -> AppResult<Option<RefreshTokenEntry>>;
+    // current に渡したトークンのセッションには is_current を立てて返す
+    async fn find_sessions(
+        &self,
+        user_id: UserId,
+        current: &AccessToken,
+    ) -> AppResult<Vec<Session>>;
     async fn fetch_user_id_from_token(
         &self,
         access_token: &AccessToken,
     ) -> AppResult<Option<UserId>>;
+    async fn mark_refresh_token_rotated(&self, refresh_token: &RefreshToken) -> AppResult<()>;
+    // 系列と、その系列で現在有効なセッションを失効させる
+    async fn revoke_refresh_family(&self, user_id: UserId, family_id: &str) -> AppResult<()>;
+    // 有効なロックがあれば、その解除日時を返す
+    async fn find_login_lockout(&self, key: &LoginAttemptKey) -> AppResult<Option<DateTime<Utc>>>;
+    // 失敗回数を 1 増やし、増やした後の回数を返す。最後の失敗から window 秒経つと数え直す
+    async fn record_login_failure(&self, key: &LoginAttemptKey, window: u64) -> AppResult<u32>;
+    async fn lock_login(&self, key: &LoginAttemptKey, until: DateTime<Utc>) -> AppResult<()>;
+    // 失敗回数とロックをまとめて消す。メールアドレスの場合は、記録した接続元も忘れる
+    async fn clear_login_failures(&self, key: &LoginAttemptKey) -> AppResult<()>;
+    // そのメールアドレスでのログインに失敗した接続元を、最後の失敗から window 秒間記録する
+    async fn record_login_failure_ip(
+        &self,
+        email: &str,
+        ip_address: &str,
+        window: u64,
+    ) -> AppResult<()>;
+    async fn find_login_failure_ips(&self, email: &str) -> AppResult<Vec<String>>;
+    async fn create_mfa_challenge(
+        &self,
+        event: CreateMfaChallenge,
+        ttl: u64,
+    ) -> AppResult<MfaChallengeToken>;
+    // 取得と同時に削除し、同じチャレンジで確認コードを繰り返し試せないようにする
+    async fn consume_mfa_challenge(
+        &self,
+        challenge_token: &MfaChallengeToken,
+    ) -> AppResult<Option<UserId>>;
+    async fn create_oidc_request(
+        &self,
+        request: &OidcAuthorizationRequest,
+        ttl: u64,
+    ) -> AppResult<()>;
+    // 取得と同時に削除し、同じ state でコールバックを二度受け付けないようにする
+    async fn consume_oidc_request(
+        &self,
+        state: &str,
+    ) -> AppResult<Option<OidcAuthorizationRequest>>;
+    async fn create_password_reset_token(
+        &self,
+        event: CreatePasswordResetToken,
+        ttl: u64,
+    ) -> AppResult<PasswordResetToken>;
+    // 新しいパスワードを検証する前に対象のユーザーを確かめるため、削除せずに取得する
+    async fn find_password_reset_token(
+        &self,
+        reset_token: &PasswordResetToken,
+    ) -> AppResult<Option<UserId>>;
+    // 取得と同時に削除し、同じトークンを二度使えないようにする
+    async fn consume_password_reset_token(
+        &self,
+        reset_token: &PasswordResetToken,
+    ) -> AppResult<Option<UserId>>;
+    async fn create_email_verification_token(
+        &self,
+        event: CreateEmailVerificationToken,
+        ttl: u64,
+    ) -> AppResult<EmailVerificationToken>;
+    // 取得と同時に削除し、同じトークンを二度使えないようにする
+    async fn consume_email_verification_token(
+        &self,
+        verification_token: &EmailVerificationToken,
+    ) -> AppResult<Option<PendingEmailChange>>;
+    // 有効期限を過ぎた認証関連の状態を削除する。期限付きで保存できるストアでは何もしない
+    async fn delete_expired(&self) -> AppResult<()>;
 }
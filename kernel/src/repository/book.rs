@@ -1,7 +1,7 @@
 use crate::model::{
     book::{
-        Book, BookListOptions,
-        event::{CreateBook, DeleteBook, UpdateBook},
+        Book, BookChangeLog, BookListOptions,
+        event::{CreateBook, DeleteBook, TransferAllBooks, UpdateBook, UpdateBookOwner},
     },
     id::{BookId, UserId},
     list::PaginatedList,
@@ -16,5 +16,10 @@ pub trait BookRepository: Send + Sync {
     async fn delete(&self, event: DeleteBook) -> AppResult<()>;
     async fn find_all(&self, options: BookListOptions) -> AppResult<PaginatedList<Book>>;
     async fn find_by_id(&self, book_id: BookId) -> AppResult<Option<Book>>;
+    // 削除済みの蔵書の履歴も返す。古い順に並べる
+    async fn find_change_logs(&self, book_id: BookId) -> AppResult<Vec<BookChangeLog>>;
+    // 所有者を付け替えるだけなので、貸出中の状態や貸出履歴はそのまま残る
+    async fn transfer_all(&self, event: TransferAllBooks) -> AppResult<u64>;
     async fn update(&self, event: UpdateBook) -> AppResult<()>;
+    async fn update_owner(&self, event: UpdateBookOwner) -> AppResult<()>;
 }
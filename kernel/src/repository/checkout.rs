@@ -1,11 +1,12 @@
 use crate::model::{
     checkout::{
         Checkout, CheckoutState,
-        event::{CreateCheckout, UpdateReturned},
+        event::{CreateCheckout, UpdateRenewed, UpdateReturned},
     },
     id::{BookId, CheckoutId, UserId},
 };
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use shared::error::AppResult;
 
 #[mockall::automock]
@@ -14,8 +15,20 @@ pub trait CheckoutRepository: Send + Sync {
     async fn delete_checkout(&self, checkout_id: CheckoutId) -> AppResult<()>;
     async fn find_checkout_state(&self, book_id: BookId) -> AppResult<Option<CheckoutState>>;
     async fn find_history_by_book_id(&self, book_id: BookId) -> AppResult<Vec<Checkout>>;
+    async fn find_overdue_all(&self, now: DateTime<Utc>) -> AppResult<Vec<Checkout>>;
     async fn find_unreturned_all(&self) -> AppResult<Vec<Checkout>>;
+    async fn find_unreturned_by_checkout_id(
+        &self,
+        checkout_id: CheckoutId,
+    ) -> AppResult<Option<Checkout>>;
     async fn find_unreturned_by_user_id(&self, user_id: UserId) -> AppResult<Vec<Checkout>>;
-    async fn insert_checkout(&self, event: &CreateCheckout) -> AppResult<()>;
+    async fn insert_checkout(&self, event: &CreateCheckout, due_at: DateTime<Utc>)
+    -> AppResult<()>;
+    async fn insert_renewed_checkout(
+        &self,
+        event: &UpdateRenewed,
+        due_at: DateTime<Utc>,
+    ) -> AppResult<()>;
     async fn insert_returned_checkout(&self, event: &UpdateReturned) -> AppResult<()>;
+    async fn update_due_at(&self, checkout_id: CheckoutId, due_at: DateTime<Utc>) -> AppResult<()>;
 }
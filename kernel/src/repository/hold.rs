@@ -0,0 +1,25 @@
+use crate::model::{
+    hold::{Hold, event::CreateHold},
+    id::{BookId, HoldId, UserId},
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use shared::error::AppResult;
+
+#[mockall::automock]
+#[async_trait]
+pub trait HoldRepository: Send + Sync {
+    async fn delete(&self, hold_id: HoldId) -> AppResult<()>;
+    async fn delete_by_user_id(&self, user_id: UserId) -> AppResult<()>;
+    async fn delete_expired(&self, book_id: BookId, now: DateTime<Utc>) -> AppResult<()>;
+    // find_* はいずれも無効化されたユーザーの予約を含めず、順番もそれを除いて数える
+    async fn find_by_book_id(&self, book_id: BookId) -> AppResult<Vec<Hold>>;
+    async fn find_by_user_id(&self, user_id: UserId) -> AppResult<Vec<Hold>>;
+    async fn insert(&self, event: &CreateHold) -> AppResult<()>;
+    async fn update_ready(
+        &self,
+        hold_id: HoldId,
+        ready_at: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+    ) -> AppResult<()>;
+}
@@ -0,0 +1,14 @@
+use crate::model::{
+    loan_policy::{LoanPolicy, event::UpdateLoanPolicy},
+    role::Role,
+};
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+#[mockall::automock]
+#[async_trait]
+pub trait LoanPolicyRepository: Send + Sync {
+    async fn find_all(&self) -> AppResult<Vec<LoanPolicy>>;
+    async fn find_by_role(&self, role: &Role) -> AppResult<Option<LoanPolicy>>;
+    async fn update(&self, event: &UpdateLoanPolicy) -> AppResult<()>;
+}
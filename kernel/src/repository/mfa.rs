@@ -0,0 +1,24 @@
+use crate::model::{
+    id::UserId,
+    mfa::{
+        UserMfa,
+        event::{EnableMfa, StartMfaEnrollment},
+    },
+};
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+#[mockall::automock]
+#[async_trait]
+pub trait MfaRepository: Send + Sync {
+    async fn find_by_user_id(&self, user_id: UserId) -> AppResult<Option<UserMfa>>;
+    async fn start_enrollment(&self, event: StartMfaEnrollment) -> AppResult<()>;
+    // 登録を完了し、以前のリカバリーコードを置き換える
+    async fn enable(&self, event: EnableMfa) -> AppResult<()>;
+    // 共有秘密とリカバリーコードをまとめて削除する
+    async fn disable(&self, user_id: UserId) -> AppResult<()>;
+    // 最後に受け付けたものより後のタイムステップであれば記録して true を返す
+    async fn accept_time_step(&self, user_id: UserId, step: i64) -> AppResult<bool>;
+    // 未使用のコードと一致した場合は使用済みにして true を返す
+    async fn consume_recovery_code(&self, user_id: UserId, code: &str) -> AppResult<bool>;
+}
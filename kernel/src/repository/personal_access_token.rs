@@ -0,0 +1,23 @@
+use crate::model::{
+    auth::AccessToken,
+    id::UserId,
+    personal_access_token::{
+        PersonalAccessToken,
+        event::{CreatePersonalAccessToken, DeletePersonalAccessToken},
+    },
+};
+use async_trait::async_trait;
+use shared::error::AppResult;
+
+#[mockall::automock]
+#[async_trait]
+pub trait PersonalAccessTokenRepository: Send + Sync {
+    async fn create(&self, event: &CreatePersonalAccessToken) -> AppResult<PersonalAccessToken>;
+    async fn delete(&self, event: DeletePersonalAccessToken) -> AppResult<()>;
+    async fn find_by_user_id(&self, user_id: UserId) -> AppResult<Vec<PersonalAccessToken>>;
+    // 有効期限内のトークンを探し、見つかった場合は最終利用日時を更新する
+    async fn find_active_by_secret(
+        &self,
+        access_token: &AccessToken,
+    ) -> AppResult<Option<PersonalAccessToken>>;
+}
@@ -1,8 +1,13 @@
 use crate::model::{
     id::UserId,
+    list::PaginatedList,
+    role::Role,
     user::{
-        User,
-        event::{CreateUser, DeleteUser, UpdateUserPassword, UpdateUserRole},
+        User, UserListOptions,
+        event::{
+            CreateUser, DeactivateUser, DeleteUser, UpdatePasswordHash, UpdateUserEmail,
+            UpdateUserName, UpdateUserRole,
+        },
     },
 };
 use async_trait::async_trait;
@@ -11,12 +16,20 @@ use shared::error::AppResult;
 #[mockall::automock]
 #[async_trait]
 pub trait UserRepository: Send + Sync {
-    async fn create(&self, event: CreateUser) -> AppResult<User>;
+    // 無効化されたユーザーは数えない
+    async fn count_by_role(&self, role: &Role) -> AppResult<i64>;
+    async fn create(&self, event: CreateUser, password_hash: String) -> AppResult<User>;
+    async fn deactivate(&self, event: DeactivateUser) -> AppResult<()>;
+    // 無効化の有無に関わらず、関連する蔵書や貸出の記録ごと削除する
     async fn delete(&self, event: DeleteUser) -> AppResult<()>;
-    async fn find_all(&self) -> AppResult<Vec<User>>;
+    // find_* はいずれも無効化されたユーザーを含めない
+    async fn find_all(&self, options: UserListOptions) -> AppResult<PaginatedList<User>>;
+    async fn find_by_email(&self, email: &str) -> AppResult<Option<User>>;
     async fn find_current_user(&self, current_user_id: UserId) -> AppResult<Option<User>>;
-    async fn find_password_hash_by_email(&self, email: &str) -> AppResult<(UserId, String)>;
     async fn find_password_hash_by_user_id(&self, user_id: UserId) -> AppResult<String>;
-    async fn update_password(&self, event: UpdateUserPassword) -> AppResult<()>;
+    // 他のユーザーが使っているメールアドレスには変更できない
+    async fn update_email(&self, event: UpdateUserEmail) -> AppResult<()>;
+    async fn update_name(&self, event: UpdateUserName) -> AppResult<()>;
+    async fn update_password_hash(&self, event: UpdatePasswordHash) -> AppResult<()>;
     async fn update_role(&self, event: UpdateUserRole) -> AppResult<()>;
 }
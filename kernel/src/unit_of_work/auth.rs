@@ -1,5 +1,8 @@
 use crate::{
-    repository::{auth::AuthRepository, user::UserRepository},
+    repository::{
+        auth::AuthRepository, mfa::MfaRepository,
+        personal_access_token::PersonalAccessTokenRepository, user::UserRepository,
+    },
     unit_of_work::UnitOfWork,
 };
 use async_trait::async_trait;
@@ -8,6 +11,8 @@ use shared::error::AppResult;
 #[async_trait]
 pub trait AuthUnitOfWork: UnitOfWork {
     fn auth_repository(&self) -> Box<dyn AuthRepository + '_>;
+    fn mfa_repository(&self) -> Box<dyn MfaRepository + '_>;
+    fn personal_access_token_repository(&self) -> Box<dyn PersonalAccessTokenRepository + '_>;
     fn user_repository(&self) -> Box<dyn UserRepository + '_>;
 }
 
@@ -29,6 +34,8 @@ mockall::mock! {
 
     impl AuthUnitOfWork for AuthUnitOfWork {
         fn auth_repository<'a>(&'a self) -> Box<dyn AuthRepository + 'a>;
+        fn mfa_repository<'a>(&'a self) -> Box<dyn MfaRepository + 'a>;
+        fn personal_access_token_repository<'a>(&'a self) -> Box<dyn PersonalAccessTokenRepository + 'a>;
         fn user_repository<'a>(&'a self) -> Box<dyn UserRepository + 'a>;
     }
 }
@@ -1,10 +1,14 @@
-use crate::{repository::book::BookRepository, unit_of_work::UnitOfWork};
+use crate::{
+    repository::{book::BookRepository, user::UserRepository},
+    unit_of_work::UnitOfWork,
+};
 use async_trait::async_trait;
 use shared::error::AppResult;
 
 #[async_trait]
 pub trait BookUnitOfWork: UnitOfWork {
     fn book_repository(&self) -> Box<dyn BookRepository + '_>;
+    fn user_repository(&self) -> Box<dyn UserRepository + '_>;
 }
 
 #[async_trait]
@@ -25,6 +29,7 @@ mockall::mock! {
 
     impl BookUnitOfWork for BookUnitOfWork {
         fn book_repository<'a>(&'a self) -> Box<dyn BookRepository + 'a>;
+        fn user_repository<'a>(&'a self) -> Box<dyn UserRepository + 'a>;
     }
 }
 
@@ -1,4 +1,7 @@
-use crate::repository::{book::BookRepository, checkout::CheckoutRepository};
+use crate::repository::{
+    book::BookRepository, checkout::CheckoutRepository, hold::HoldRepository,
+    loan_policy::LoanPolicyRepository, user::UserRepository,
+};
 use crate::unit_of_work::UnitOfWork;
 use async_trait::async_trait;
 use shared::error::AppResult;
@@ -7,6 +10,9 @@ use shared::error::AppResult;
 pub trait CheckoutUnitOfWork: UnitOfWork {
     fn checkout_repository(&self) -> Box<dyn CheckoutRepository + '_>;
     fn book_repository(&self) -> Box<dyn BookRepository + '_>;
+    fn hold_repository(&self) -> Box<dyn HoldRepository + '_>;
+    fn loan_policy_repository(&self) -> Box<dyn LoanPolicyRepository + '_>;
+    fn user_repository(&self) -> Box<dyn UserRepository + '_>;
 }
 
 #[async_trait]
@@ -28,6 +34,9 @@ mockall::mock! {
     impl CheckoutUnitOfWork for CheckoutUnitOfWork {
         fn checkout_repository<'a>(&'a self) -> Box<dyn CheckoutRepository + 'a>;
         fn book_repository<'a>(&'a self) -> Box<dyn BookRepository + 'a>;
+        fn hold_repository<'a>(&'a self) -> Box<dyn HoldRepository + 'a>;
+        fn loan_policy_repository<'a>(&'a self) -> Box<dyn LoanPolicyRepository + 'a>;
+        fn user_repository<'a>(&'a self) -> Box<dyn UserRepository + 'a>;
     }
 }
 
@@ -1,5 +1,8 @@
 use crate::{
-    repository::{checkout::CheckoutRepository, user::UserRepository},
+    repository::{
+        auth::AuthRepository, checkout::CheckoutRepository, hold::HoldRepository,
+        user::UserRepository,
+    },
     unit_of_work::UnitOfWork,
 };
 use async_trait::async_trait;
@@ -7,7 +10,9 @@ use shared::error::AppResult;
 
 #[async_trait]
 pub trait UserUnitOfWork: UnitOfWork {
+    fn auth_repository(&self) -> Box<dyn AuthRepository + '_>;
     fn checkout_repository(&self) -> Box<dyn CheckoutRepository + '_>;
+    fn hold_repository(&self) -> Box<dyn HoldRepository + '_>;
     fn user_repository(&self) -> Box<dyn UserRepository + '_>;
 }
 
@@ -28,7 +33,9 @@ mockall::mock! {
     }
 
     impl UserUnitOfWork for UserUnitOfWork {
+        fn auth_repository<'a>(&'a self) -> Box<dyn AuthRepository + 'a>;
         fn checkout_repository<'a>(&'a self) -> Box<dyn CheckoutRepository + 'a>;
+        fn hold_repository<'a>(&'a self) -> Box<dyn HoldRepository + 'a>;
         fn user_repository<'a>(&'a self) -> Box<dyn UserRepository + 'a>;
     }
 }
@@ -1,35 +1,451 @@
 use crate::{
+    mail::MailSender,
     model::{
-        auth::{AccessToken, event::CreateToken},
+        auth::{
+            AccessToken, LoginAttemptKey, MfaChallengeToken, PasswordResetToken, RefreshToken,
+            event::{
+                CreateMfaChallenge, CreatePasswordResetToken, CreateRefreshToken, CreateToken,
+            },
+        },
         id::UserId,
-        user::User,
+        mail::Mail,
+        mfa::{
+            self, LoginOutcome, MfaEnrollment,
+            event::{EnableMfa, StartMfaEnrollment},
+        },
+        oidc::OidcAuthorizationRequest,
+        personal_access_token::{
+            PersonalAccessToken, TokenScope,
+            event::{CreatePersonalAccessToken, DeletePersonalAccessToken},
+        },
+        role::Role,
+        session::{Session, SessionClient},
+        user::{
+            User,
+            event::{CreateUser, UpdatePasswordHash},
+        },
     },
-    unit_of_work::auth::AuthUnitOfWorkScope,
+    oidc::OidcProvider,
+    password::PasswordHasher,
+    repository::{auth::AuthRepository, mfa::MfaRepository},
+    unit_of_work::auth::{AuthUnitOfWork, AuthUnitOfWorkScope},
 };
 use async_trait::async_trait;
-use shared::error::{AppError, AppResult};
-use std::sync::Arc;
+use chrono::{DateTime, Duration, Utc};
+use shared::{
+    config::{LoginLockoutConfig, MfaConfig},
+    error::{AppError, AppResult},
+    i18n::{Locale, Message, MessageKey},
+};
+use std::sync::{Arc, OnceLock};
+use uuid::Uuid;
 
 #[mockall::automock]
 #[async_trait]
 pub trait AuthUseCase: Send + Sync {
+    // 登録を完了し、リカバリーコードを返す
+    async fn confirm_mfa_enrollment(&self, user_id: UserId, code: &str) -> AppResult<Vec<String>>;
+    async fn create_personal_access_token(
+        &self,
+        event: CreatePersonalAccessToken,
+    ) -> AppResult<(PersonalAccessToken, AccessToken)>;
+    // 有効期限を過ぎたセッションやトークンを削除する。定期的に呼び出す
+    async fn delete_expired(&self) -> AppResult<()>;
+    // 確認コードかリカバリーコードで本人であることを確かめてから無効にする
+    async fn disable_mfa(&self, user_id: UserId, code: &str) -> AppResult<()>;
     async fn find_authorized_user(&self, access_token: &AccessToken) -> AppResult<User>;
-    async fn login(&self, email: &str, password: &str) -> AppResult<(UserId, AccessToken)>;
+    // 新しいパスワードを本人の名前やメールアドレスと照合できるよう、再設定の前に対象のユーザーを返す
+    async fn find_password_reset_user(&self, reset_token: &PasswordResetToken) -> AppResult<User>;
+    async fn find_personal_access_token_user(
+        &self,
+        access_token: &AccessToken,
+    ) -> AppResult<(User, Vec<TokenScope>)>;
+    async fn is_mfa_enabled(&self, user_id: UserId) -> AppResult<bool>;
+    async fn list_personal_access_tokens(
+        &self,
+        user_id: UserId,
+    ) -> AppResult<Vec<PersonalAccessToken>>;
+    async fn list_sessions(
+        &self,
+        user_id: UserId,
+        current: &AccessToken,
+    ) -> AppResult<Vec<Session>>;
+    async fn login(
+        &self,
+        email: &str,
+        password: &str,
+        client: SessionClient,
+    ) -> AppResult<LoginOutcome>;
     async fn logout(&self, access_token: AccessToken) -> AppResult<()>;
+    // ID プロバイダーからのコールバックを検証し、email クレームに一致するユーザーでログインする
+    async fn oidc_login(
+        &self,
+        state: &str,
+        code: &str,
+        client: SessionClient,
+    ) -> AppResult<LoginOutcome>;
+    // 使用済みのリフレッシュトークンが再度使われた場合は、その系列をすべて失効させる
+    async fn refresh(
+        &self,
+        refresh_token: RefreshToken,
+        client: SessionClient,
+    ) -> AppResult<(UserId, AccessToken, RefreshToken)>;
+    async fn request_password_reset(&self, email: &str) -> AppResult<()>;
+    async fn reset_password(
+        &self,
+        reset_token: PasswordResetToken,
+        new_password: String,
+    ) -> AppResult<()>;
+    async fn revoke_all_sessions(&self, user_id: UserId) -> AppResult<()>;
+    async fn revoke_personal_access_token(&self, event: DeletePersonalAccessToken)
+    -> AppResult<()>;
+    async fn revoke_session(&self, user_id: UserId, session_id: &str) -> AppResult<()>;
+    // 未完了の登録がある場合は、新しい共有秘密で登録をやり直す
+    async fn start_mfa_enrollment(&self, user_id: UserId) -> AppResult<MfaEnrollment>;
+    // ID プロバイダーの認可エンドポイントの URL を返す
+    async fn start_oidc_login(&self) -> AppResult<String>;
+    // ログイン時のチャレンジに対して確認コードかリカバリーコードを検証する。
+    // 登録と同時にログインした場合は、発行したリカバリーコードも返す
+    async fn verify_mfa(
+        &self,
+        challenge_token: MfaChallengeToken,
+        code: &str,
+        client: SessionClient,
+    ) -> AppResult<(UserId, AccessToken, RefreshToken, Vec<String>)>;
 }
 
 pub struct AuthUseCaseImpl {
     scope: Arc<dyn AuthUnitOfWorkScope>,
+    mail_sender: Arc<dyn MailSender>,
+    password_hasher: Arc<dyn PasswordHasher>,
+    password_reset_ttl: u64,
+    refresh_token_ttl: u64,
+    lockout: LoginLockoutConfig,
+    mfa: MfaConfig,
+    oidc: Option<Arc<dyn OidcProvider>>,
+    oidc_auto_provision: bool,
+    dummy_password_hash: OnceLock<String>,
 }
 
+// 認可リクエストを送ってから、コールバックを受けるまでの猶予
+const OIDC_REQUEST_TTL: u64 = 600;
+
+// 登録されていないメールアドレスでも、パスワードの照合にかかる時間を揃えるために使う
+const DUMMY_PASSWORD: &str = "dummy-password-for-unknown-email";
+
 impl AuthUseCaseImpl {
-    pub fn new(scope: Arc<dyn AuthUnitOfWorkScope>) -> Self {
-        Self { scope }
+    pub fn new(
+        scope: Arc<dyn AuthUnitOfWorkScope>,
+        mail_sender: Arc<dyn MailSender>,
+        password_hasher: Arc<dyn PasswordHasher>,
+        password_reset_ttl: u64,
+        refresh_token_ttl: u64,
+        lockout: LoginLockoutConfig,
+        mfa: MfaConfig,
+    ) -> Self {
+        Self {
+            scope,
+            mail_sender,
+            password_hasher,
+            password_reset_ttl,
+            refresh_token_ttl,
+            lockout,
+            mfa,
+            oidc: None,
+            oidc_auto_provision: false,
+            dummy_password_hash: OnceLock::new(),
+        }
+    }
+
+    pub fn with_oidc(mut self, provider: Arc<dyn OidcProvider>, auto_provision: bool) -> Self {
+        self.oidc = Some(provider);
+        self.oidc_auto_provision = auto_provision;
+        self
+    }
+
+    // 登録されていないメールアドレスの照合に使う。現在の方式で一度だけ作る
+    fn dummy_password_hash(&self) -> AppResult<&str> {
+        if let Some(hash) = self.dummy_password_hash.get() {
+            return Ok(hash);
+        }
+        let hash = self.password_hasher.hash(DUMMY_PASSWORD)?;
+        Ok(self.dummy_password_hash.get_or_init(|| hash))
+    }
+
+    fn oidc_provider(&self) -> AppResult<&Arc<dyn OidcProvider>> {
+        self.oidc
+            .as_ref()
+            .ok_or_else(|| AppError::EntityNotFound(Message::new(MessageKey::OidcNotConfigured)))
+    }
+
+    async fn issue_tokens(
+        &self,
+        repository: &dyn AuthRepository,
+        user_id: UserId,
+        client: SessionClient,
+    ) -> AppResult<(AccessToken, RefreshToken)> {
+        let event = CreateToken::new(user_id, client);
+        let family_id = event.family_id.clone();
+        let access_token = repository.create_token(event).await?;
+        let refresh_token = repository
+            .create_refresh_token(
+                CreateRefreshToken::new(user_id, family_id),
+                self.refresh_token_ttl,
+            )
+            .await?;
+        Ok((access_token, refresh_token))
+    }
+
+    // パスワードないしはシングルサインオンで本人確認が済んだ後、
+    // 二要素認証が必要ならチャレンジを、不要ならトークンを発行する
+    async fn complete_login(
+        &self,
+        uow: Box<dyn AuthUnitOfWork + '_>,
+        user: User,
+        client: SessionClient,
+    ) -> AppResult<LoginOutcome> {
+        let user_id = user.id();
+
+        // 二要素認証が必要な場合、失敗回数は確認コードの検証まで済んでから消す
+        let mfa_enabled = uow
+            .mfa_repository()
+            .find_by_user_id(user_id)
+            .await?
+            .is_some_and(|mfa| mfa.enabled);
+        if mfa_enabled || self.mfa_required(&user) {
+            let enrollment = if mfa_enabled {
+                None
+            } else {
+                let secret = mfa::generate_secret();
+                uow.mfa_repository()
+                    .start_enrollment(StartMfaEnrollment {
+                        user_id,
+                        secret: secret.clone(),
+                    })
+                    .await?;
+                Some(MfaEnrollment::new(
+                    secret,
+                    &self.mfa.issuer,
+                    user.email().as_ref(),
+                )?)
+            };
+            let challenge_token = uow
+                .auth_repository()
+                .create_mfa_challenge(CreateMfaChallenge::new(user_id), self.mfa.challenge_ttl)
+                .await?;
+            uow.commit().await?;
+            return Ok(match enrollment {
+                None => LoginOutcome::MfaRequired { challenge_token },
+                Some(enrollment) => LoginOutcome::MfaEnrollmentRequired {
+                    challenge_token,
+                    enrollment,
+                },
+            });
+        }
+
+        let (access_token, refresh_token) = {
+            let repository = uow.auth_repository();
+            repository
+                .clear_login_failures(&LoginAttemptKey::Email(
+                    user.email().as_ref().to_lowercase(),
+                ))
+                .await?;
+            self.issue_tokens(repository.as_ref(), user_id, client)
+                .await?
+        };
+        uow.commit().await?;
+        Ok(LoginOutcome::Authenticated {
+            user_id,
+            access_token,
+            refresh_token,
+        })
+    }
+
+    fn mfa_required(&self, user: &User) -> bool {
+        self.mfa.required_for_admin && *user.role() == Role::Admin
+    }
+
+    // 失敗回数がしきい値に達したら、超えた回数に応じて倍々に延ばした時間だけロックする
+    async fn record_login_failure(
+        &self,
+        repository: &dyn AuthRepository,
+        keys: &[LoginAttemptKey],
+    ) -> AppResult<()> {
+        for key in keys {
+            let failures = repository
+                .record_login_failure(key, self.lockout.max_secs)
+                .await?;
+            let threshold = match key {
+                LoginAttemptKey::Email(_) => self.lockout.email_threshold,
+                LoginAttemptKey::IpAddress(_) => self.lockout.ip_threshold,
+            };
+            if failures < threshold {
+                continue;
+            }
+            let lockout_secs = 1u64
+                .checked_shl(failures - threshold)
+                .map_or(u64::MAX, |factor| {
+                    self.lockout.base_secs.saturating_mul(factor)
+                })
+                .min(self.lockout.max_secs);
+            repository
+                .lock_login(key, Utc::now() + Duration::seconds(lockout_secs as i64))
+                .await?;
+        }
+        // 管理者がアカウントのロックを解除する際に、接続元のロックも併せて解除できるようにする
+        if let [
+            LoginAttemptKey::Email(email),
+            LoginAttemptKey::IpAddress(ip_address),
+        ] = keys
+        {
+            repository
+                .record_login_failure_ip(email, ip_address, self.lockout.max_secs)
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+fn login_attempt_keys(email: &str, client: &SessionClient) -> Vec<LoginAttemptKey> {
+    let mut keys = vec![LoginAttemptKey::Email(email.to_lowercase())];
+    if let Some(ip_address) = &client.ip_address {
+        keys.push(LoginAttemptKey::IpAddress(ip_address.clone()));
+    }
+    keys
+}
+
+// 確認コードが一致しても、既に受け付けたタイムステップのものは再利用として拒否する（RFC 6238 5.2 節）
+async fn verify_totp(
+    repository: &dyn MfaRepository,
+    user_id: UserId,
+    secret: &str,
+    code: &str,
+) -> AppResult<bool> {
+    match mfa::verify_code(secret, code)? {
+        Some(step) => repository.accept_time_step(user_id, step).await,
+        None => Ok(false),
     }
 }
 
+// 有効な二要素認証では、確認コードの代わりに未使用のリカバリーコードも受け付ける
+async fn verify_mfa_code(
+    repository: &dyn MfaRepository,
+    user_id: UserId,
+    secret: &str,
+    code: &str,
+) -> AppResult<bool> {
+    if verify_totp(repository, user_id, secret, code).await? {
+        return Ok(true);
+    }
+    repository.consume_recovery_code(user_id, code.trim()).await
+}
+
+// Retry-After には切り上げた秒数を返す
+fn retry_after(locked_until: DateTime<Utc>) -> u64 {
+    let millis = (locked_until - Utc::now()).num_milliseconds().max(1);
+    (millis as u64).div_ceil(1000)
+}
+
 #[async_trait]
 impl AuthUseCase for AuthUseCaseImpl {
+    async fn confirm_mfa_enrollment(&self, user_id: UserId, code: &str) -> AppResult<Vec<String>> {
+        let uow = self.scope.begin().await?;
+        let recovery_codes = {
+            let repository = uow.mfa_repository();
+            let mfa = repository
+                .find_by_user_id(user_id)
+                .await?
+                .filter(|mfa| !mfa.enabled)
+                .ok_or_else(|| {
+                    AppError::UnprocessableEntity(Message::new(MessageKey::MfaNotEnrolling))
+                })?;
+            if !verify_totp(repository.as_ref(), user_id, &mfa.secret, code).await? {
+                return Err(AppError::UnprocessableEntity(Message::new(
+                    MessageKey::MfaCodeInvalid,
+                )));
+            }
+            let recovery_codes = mfa::generate_recovery_codes();
+            repository
+                .enable(EnableMfa {
+                    user_id,
+                    recovery_codes: recovery_codes.clone(),
+                })
+                .await?;
+            recovery_codes
+        };
+        uow.commit().await?;
+        Ok(recovery_codes)
+    }
+
+    async fn create_personal_access_token(
+        &self,
+        event: CreatePersonalAccessToken,
+    ) -> AppResult<(PersonalAccessToken, AccessToken)> {
+        if event
+            .expires_at
+            .is_some_and(|expires_at| expires_at <= Utc::now())
+        {
+            return Err(AppError::UnprocessableEntity(Message::new(
+                MessageKey::PersonalAccessTokenExpiryInPast,
+            )));
+        }
+
+        let uow = self.scope.begin().await?;
+        let token = {
+            let repository = uow.personal_access_token_repository();
+            if repository
+                .find_by_user_id(event.user_id)
+                .await?
+                .iter()
+                .any(|t| t.name() == event.name)
+            {
+                return Err(AppError::UnprocessableEntity(
+                    Message::new(MessageKey::PersonalAccessTokenNameTaken).arg(&event.name),
+                ));
+            }
+            repository.create(&event).await?
+        };
+        uow.commit().await?;
+        Ok((token, AccessToken(event.secret)))
+    }
+
+    async fn delete_expired(&self) -> AppResult<()> {
+        let uow = self.scope.begin().await?;
+        uow.auth_repository().delete_expired().await?;
+        uow.commit().await
+    }
+
+    async fn disable_mfa(&self, user_id: UserId, code: &str) -> AppResult<()> {
+        let uow = self.scope.begin().await?;
+        let user = uow
+            .user_repository()
+            .find_current_user(user_id)
+            .await?
+            .ok_or(AppError::UnauthenticatedError)?;
+        if self.mfa_required(&user) {
+            return Err(AppError::UnprocessableEntity(Message::new(
+                MessageKey::MfaRequiredForAdmin,
+            )));
+        }
+        {
+            let repository = uow.mfa_repository();
+            let mfa = repository
+                .find_by_user_id(user_id)
+                .await?
+                .filter(|mfa| mfa.enabled)
+                .ok_or_else(|| {
+                    AppError::UnprocessableEntity(Message::new(MessageKey::MfaNotEnabled))
+                })?;
+            if !verify_mfa_code(repository.as_ref(), user_id, &mfa.secret, code).await? {
+                return Err(AppError::UnprocessableEntity(Message::new(
+                    MessageKey::MfaCodeInvalid,
+                )));
+            }
+            repository.disable(user_id).await?;
+        }
+        uow.commit().await
+    }
+
     async fn find_authorized_user(&self, access_token: &AccessToken) -> AppResult<User> {
         let uow = self.scope.begin().await?;
         let user_id = uow
@@ -37,32 +453,424 @@ impl AuthUseCase for AuthUseCaseImpl {
             .fetch_user_id_from_token(access_token)
             .await?
             .ok_or(AppError::UnauthenticatedError)?;
-        uow.user_repository()
+        uow.auth_repository().extend_token(access_token).await?;
+        let user = uow
+            .user_repository()
+            .find_current_user(user_id)
+            .await?
+            .ok_or(AppError::UnauthenticatedError)?;
+        uow.commit().await?;
+        Ok(user)
+    }
+
+    async fn find_password_reset_user(&self, reset_token: &PasswordResetToken) -> AppResult<User> {
+        let invalid =
+            || AppError::UnprocessableEntity(Message::new(MessageKey::PasswordResetTokenInvalid));
+        let uow = self.scope.begin().await?;
+        let user_id = uow
+            .auth_repository()
+            .find_password_reset_token(reset_token)
+            .await?
+            .ok_or_else(invalid)?;
+        let user = uow
+            .user_repository()
             .find_current_user(user_id)
             .await?
-            .ok_or(AppError::UnauthenticatedError)
+            .ok_or_else(invalid)?;
+        uow.commit().await?;
+        Ok(user)
     }
 
-    async fn login(&self, email: &str, password: &str) -> AppResult<(UserId, AccessToken)> {
+    async fn find_personal_access_token_user(
+        &self,
+        access_token: &AccessToken,
+    ) -> AppResult<(User, Vec<TokenScope>)> {
         let uow = self.scope.begin().await?;
-        let (user_id, password_hash) = uow
+        let token = uow
+            .personal_access_token_repository()
+            .find_active_by_secret(access_token)
+            .await?
+            .ok_or(AppError::UnauthenticatedError)?;
+        let user = uow
             .user_repository()
-            .find_password_hash_by_email(email)
-            .await?;
-        if !bcrypt::verify(password, &password_hash)? {
+            .find_current_user(token.user_id())
+            .await?
+            .ok_or(AppError::UnauthenticatedError)?;
+        uow.commit().await?;
+        Ok((user, token.scopes().to_vec()))
+    }
+
+    async fn is_mfa_enabled(&self, user_id: UserId) -> AppResult<bool> {
+        let uow = self.scope.begin().await?;
+        Ok(uow
+            .mfa_repository()
+            .find_by_user_id(user_id)
+            .await?
+            .is_some_and(|mfa| mfa.enabled))
+    }
+
+    async fn list_personal_access_tokens(
+        &self,
+        user_id: UserId,
+    ) -> AppResult<Vec<PersonalAccessToken>> {
+        let uow = self.scope.begin().await?;
+        uow.personal_access_token_repository()
+            .find_by_user_id(user_id)
+            .await
+    }
+
+    async fn list_sessions(
+        &self,
+        user_id: UserId,
+        current: &AccessToken,
+    ) -> AppResult<Vec<Session>> {
+        let uow = self.scope.begin().await?;
+        uow.auth_repository().find_sessions(user_id, current).await
+    }
+
+    async fn login(
+        &self,
+        email: &str,
+        password: &str,
+        client: SessionClient,
+    ) -> AppResult<LoginOutcome> {
+        let keys = login_attempt_keys(email, &client);
+        let uow = self.scope.begin().await?;
+        // ロック中はパスワードを照合せずに拒否する
+        for key in &keys {
+            if let Some(locked_until) = uow.auth_repository().find_login_lockout(key).await? {
+                return Err(AppError::TooManyRequests(retry_after(locked_until)));
+            }
+        }
+
+        // 登録されていないメールアドレスも失敗として数える
+        let user = match uow.user_repository().find_by_email(email).await? {
+            Some(user) => {
+                let password_hash = uow
+                    .user_repository()
+                    .find_password_hash_by_user_id(user.id())
+                    .await?;
+                self.password_hasher
+                    .verify(password, &password_hash)?
+                    .then_some((user, password_hash))
+            }
+            // 応答時間からメールアドレスの登録有無を推測されないよう、ダミーのハッシュと照合する
+            None => {
+                self.password_hasher
+                    .verify(password, self.dummy_password_hash()?)?;
+                None
+            }
+        };
+        let Some((user, password_hash)) = user else {
+            self.record_login_failure(uow.auth_repository().as_ref(), &keys)
+                .await?;
+            uow.commit().await?;
+            return Err(AppError::UnauthenticatedError);
+        };
+        // bcrypt や古いパラメータのハッシュは、平文のパスワードが手元にあるうちに作り直す
+        if self.password_hasher.needs_rehash(&password_hash) {
+            uow.user_repository()
+                .update_password_hash(UpdatePasswordHash {
+                    user_id: user.id(),
+                    password_hash: self.password_hasher.hash(password)?,
+                })
+                .await?;
+        }
+        self.complete_login(uow, user, client).await
+    }
+
+    async fn logout(&self, access_token: AccessToken) -> AppResult<()> {
+        let uow = self.scope.begin().await?;
+        uow.auth_repository().delete_token(access_token).await?;
+        uow.commit().await
+    }
+
+    async fn oidc_login(
+        &self,
+        state: &str,
+        code: &str,
+        client: SessionClient,
+    ) -> AppResult<LoginOutcome> {
+        let provider = self.oidc_provider()?;
+        let request = {
+            let uow = self.scope.begin().await?;
+            let request = uow
+                .auth_repository()
+                .consume_oidc_request(state)
+                .await?
+                .ok_or(AppError::UnauthenticatedError)?;
+            uow.commit().await?;
+            request
+        };
+        // ID プロバイダーとの通信中はトランザクションを開いたままにしない
+        let claims = provider.exchange_code(code, &request).await?;
+        if claims.email_verified == Some(false) {
+            return Err(AppError::UnauthenticatedError);
+        }
+
+        let uow = self.scope.begin().await?;
+        let user = match uow.user_repository().find_by_email(&claims.email).await? {
+            Some(user) => user,
+            None if self.oidc_auto_provision => {
+                let name = claims
+                    .name
+                    .as_deref()
+                    .filter(|name| !name.is_empty())
+                    .unwrap_or_else(|| claims.email.split('@').next().unwrap_or_default());
+                // パスワードではログインさせないため、推測できない値を設定しておく
+                let password = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+                let password_hash = self.password_hasher.hash(&password)?;
+                // 無効化されたユーザーのメールアドレスでは作成できないため、ログインを拒否する
+                uow.user_repository()
+                    .create(
+                        CreateUser {
+                            name: name.parse()?,
+                            email: claims.email.parse()?,
+                            password,
+                        },
+                        password_hash,
+                    )
+                    .await
+                    .map_err(|e| match e {
+                        AppError::Conflict(_) => AppError::UnauthenticatedError,
+                        e => e,
+                    })?
+            }
+            None => return Err(AppError::UnauthenticatedError),
+        };
+        self.complete_login(uow, user, client).await
+    }
+
+    async fn refresh(
+        &self,
+        refresh_token: RefreshToken,
+        client: SessionClient,
+    ) -> AppResult<(UserId, AccessToken, RefreshToken)> {
+        let uow = self.scope.begin().await?;
+        let entry = uow
+            .auth_repository()
+            .find_refresh_token(&refresh_token)
+            .await?
+            .ok_or(AppError::UnauthenticatedError)?;
+        if entry.rotated {
+            // 漏えいしたトークンが使われた可能性があるため、正規の利用者側のセッションも含めて失効させる
+            uow.auth_repository()
+                .revoke_refresh_family(entry.user_id, &entry.family_id)
+                .await?;
+            uow.commit().await?;
+            return Err(AppError::UnauthenticatedError);
+        }
+        if uow
+            .user_repository()
+            .find_current_user(entry.user_id)
+            .await?
+            .is_none()
+        {
             return Err(AppError::UnauthenticatedError);
         }
-        let access_token = uow
+
+        let user_id = entry.user_id;
+        let (access_token, new_refresh_token) = {
+            let repository = uow.auth_repository();
+            repository
+                .mark_refresh_token_rotated(&refresh_token)
+                .await?;
+            // 同じ系列の古いセッションは新しいセッションに置き換える
+            repository
+                .revoke_refresh_family(user_id, &entry.family_id)
+                .await?;
+            let access_token = repository
+                .create_token(CreateToken::with_family(
+                    user_id,
+                    client,
+                    entry.family_id.clone(),
+                ))
+                .await?;
+            let new_refresh_token = repository
+                .create_refresh_token(
+                    CreateRefreshToken::new(user_id, entry.family_id),
+                    self.refresh_token_ttl,
+                )
+                .await?;
+            (access_token, new_refresh_token)
+        };
+        uow.commit().await?;
+        Ok((user_id, access_token, new_refresh_token))
+    }
+
+    async fn request_password_reset(&self, email: &str) -> AppResult<()> {
+        let uow = self.scope.begin().await?;
+        // 登録の有無が外部から判別できないよう、該当ユーザーがいなくても成功扱いとする
+        let Some(user) = uow.user_repository().find_by_email(email).await? else {
+            return Ok(());
+        };
+        let reset_token = uow
             .auth_repository()
-            .create_token(CreateToken::new(user_id))
+            .create_password_reset_token(
+                CreatePasswordResetToken::new(user.id()),
+                self.password_reset_ttl,
+            )
             .await?;
         uow.commit().await?;
-        Ok((user_id, access_token))
+
+        let locale = Locale::current();
+        let subject = Message::new(MessageKey::PasswordResetMailSubject).render(locale);
+        let body = Message::new(MessageKey::PasswordResetMailBody)
+            .arg(user.name())
+            .arg(reset_token.0)
+            .arg(self.password_reset_ttl / 60)
+            .render(locale);
+        self.mail_sender
+            .send(Mail::new(user.email().clone(), subject, body))
+            .await
     }
 
-    async fn logout(&self, access_token: AccessToken) -> AppResult<()> {
+    async fn reset_password(
+        &self,
+        reset_token: PasswordResetToken,
+        new_password: String,
+    ) -> AppResult<()> {
         let uow = self.scope.begin().await?;
-        uow.auth_repository().delete_token(access_token).await?;
+        let user_id = uow
+            .auth_repository()
+            .consume_password_reset_token(&reset_token)
+            .await?
+            .ok_or_else(|| {
+                AppError::UnprocessableEntity(Message::new(MessageKey::PasswordResetTokenInvalid))
+            })?;
+        uow.user_repository()
+            .update_password_hash(UpdatePasswordHash {
+                user_id,
+                password_hash: self.password_hasher.hash(&new_password)?,
+            })
+            .await?;
+        uow.auth_repository().delete_all_sessions(user_id).await?;
+        uow.commit().await
+    }
+
+    async fn revoke_all_sessions(&self, user_id: UserId) -> AppResult<()> {
+        let uow = self.scope.begin().await?;
+        uow.auth_repository().delete_all_sessions(user_id).await?;
+        uow.commit().await
+    }
+
+    async fn revoke_personal_access_token(
+        &self,
+        event: DeletePersonalAccessToken,
+    ) -> AppResult<()> {
+        let uow = self.scope.begin().await?;
+        uow.personal_access_token_repository().delete(event).await?;
+        uow.commit().await
+    }
+
+    async fn revoke_session(&self, user_id: UserId, session_id: &str) -> AppResult<()> {
+        let uow = self.scope.begin().await?;
+        uow.auth_repository()
+            .delete_session(user_id, session_id)
+            .await?;
         uow.commit().await
     }
+
+    async fn start_mfa_enrollment(&self, user_id: UserId) -> AppResult<MfaEnrollment> {
+        let uow = self.scope.begin().await?;
+        let user = uow
+            .user_repository()
+            .find_current_user(user_id)
+            .await?
+            .ok_or(AppError::UnauthenticatedError)?;
+        let enrollment = {
+            let repository = uow.mfa_repository();
+            if repository
+                .find_by_user_id(user_id)
+                .await?
+                .is_some_and(|mfa| mfa.enabled)
+            {
+                return Err(AppError::UnprocessableEntity(Message::new(
+                    MessageKey::MfaAlreadyEnabled,
+                )));
+            }
+            let secret = mfa::generate_secret();
+            repository
+                .start_enrollment(StartMfaEnrollment {
+                    user_id,
+                    secret: secret.clone(),
+                })
+                .await?;
+            MfaEnrollment::new(secret, &self.mfa.issuer, user.email().as_ref())?
+        };
+        uow.commit().await?;
+        Ok(enrollment)
+    }
+
+    async fn start_oidc_login(&self) -> AppResult<String> {
+        let provider = self.oidc_provider()?;
+        let request = OidcAuthorizationRequest::new();
+        let uow = self.scope.begin().await?;
+        uow.auth_repository()
+            .create_oidc_request(&request, OIDC_REQUEST_TTL)
+            .await?;
+        uow.commit().await?;
+        provider.authorization_url(&request).await
+    }
+
+    async fn verify_mfa(
+        &self,
+        challenge_token: MfaChallengeToken,
+        code: &str,
+        client: SessionClient,
+    ) -> AppResult<(UserId, AccessToken, RefreshToken, Vec<String>)> {
+        let uow = self.scope.begin().await?;
+        let user_id = uow
+            .auth_repository()
+            .consume_mfa_challenge(&challenge_token)
+            .await?
+            .ok_or(AppError::UnauthenticatedError)?;
+        let user = uow
+            .user_repository()
+            .find_current_user(user_id)
+            .await?
+            .ok_or(AppError::UnauthenticatedError)?;
+        let keys = login_attempt_keys(user.email().as_ref(), &client);
+
+        let verified = {
+            let repository = uow.mfa_repository();
+            let mfa = repository
+                .find_by_user_id(user_id)
+                .await?
+                .ok_or(AppError::UnauthenticatedError)?;
+            if mfa.enabled {
+                verify_mfa_code(repository.as_ref(), user_id, &mfa.secret, code)
+                    .await?
+                    .then(Vec::new)
+            } else if verify_totp(repository.as_ref(), user_id, &mfa.secret, code).await? {
+                // ログイン時に求めた登録は、最初の確認コードの検証をもって完了とする
+                let recovery_codes = mfa::generate_recovery_codes();
+                repository
+                    .enable(EnableMfa {
+                        user_id,
+                        recovery_codes: recovery_codes.clone(),
+                    })
+                    .await?;
+                Some(recovery_codes)
+            } else {
+                None
+            }
+        };
+        // チャレンジは消費済みのため、誤ったコードの場合はパスワードからやり直しになる
+        let Some(recovery_codes) = verified else {
+            self.record_login_failure(uow.auth_repository().as_ref(), &keys)
+                .await?;
+            uow.commit().await?;
+            return Err(AppError::UnauthenticatedError);
+        };
+
+        let (access_token, refresh_token) = {
+            let repository = uow.auth_repository();
+            repository.clear_login_failures(&keys[0]).await?;
+            self.issue_tokens(repository.as_ref(), user_id, client)
+                .await?
+        };
+        uow.commit().await?;
+        Ok((user_id, access_token, refresh_token, recovery_codes))
+    }
 }
@@ -1,26 +1,35 @@
 use crate::{
     model::{
         book::{
-            Book, BookListOptions,
-            event::{CreateBook, DeleteBook, UpdateBook},
+            Book, BookChangeLog, BookListOptions,
+            event::{CreateBook, DeleteBook, TransferAllBooks, UpdateBook, UpdateBookOwner},
         },
         id::{BookId, UserId},
         list::PaginatedList,
+        role::Permission,
     },
-    unit_of_work::book::BookUnitOfWorkScope,
+    unit_of_work::book::{BookUnitOfWork, BookUnitOfWorkScope},
 };
 use async_trait::async_trait;
-use shared::error::AppResult;
+use shared::{
+    error::{AppError, AppResult},
+    i18n::{Message, MessageKey},
+};
 use std::sync::Arc;
 
 #[mockall::automock]
 #[async_trait]
 pub trait BookUseCase: Send + Sync {
+    // 所有者のほか、すべての蔵書を管理できるロールのユーザーも変更・削除できる
     async fn delete_book(&self, delete_book: DeleteBook) -> AppResult<()>;
     async fn register_book(&self, event: CreateBook, user_id: UserId) -> AppResult<()>;
     async fn show_book(&self, book_id: BookId) -> AppResult<Option<Book>>;
+    async fn show_book_change_logs(&self, book_id: BookId) -> AppResult<Vec<BookChangeLog>>;
     async fn show_book_list(&self, options: BookListOptions) -> AppResult<PaginatedList<Book>>;
+    // 移管先は有効なユーザーでなければならない。件数を返す
+    async fn transfer_all_books(&self, event: TransferAllBooks) -> AppResult<u64>;
     async fn update_book(&self, update_book: UpdateBook) -> AppResult<()>;
+    async fn update_book_owner(&self, event: UpdateBookOwner) -> AppResult<()>;
 }
 
 pub struct BookUseCaseImpl {
@@ -31,12 +40,55 @@ impl BookUseCaseImpl {
     pub fn new(scope: Arc<dyn BookUnitOfWorkScope>) -> Self {
         Self { scope }
     }
+
+    async fn authorize_book_change(
+        &self,
+        uow: &dyn BookUnitOfWork,
+        book_id: BookId,
+        requested_user: UserId,
+    ) -> AppResult<Book> {
+        let book = uow
+            .book_repository()
+            .find_by_id(book_id)
+            .await?
+            .ok_or_else(|| {
+                AppError::EntityNotFound(Message::new(MessageKey::BookNotFound).arg(book_id))
+            })?;
+        if book.owner().id() == requested_user {
+            return Ok(book);
+        }
+        let can_manage_all = uow
+            .user_repository()
+            .find_current_user(requested_user)
+            .await?
+            .is_some_and(|u| u.role().has_permission(Permission::ManageAllBooks));
+        if !can_manage_all {
+            return Err(AppError::ForbiddenOperation);
+        }
+        Ok(book)
+    }
+
+    async fn ensure_active_user(&self, uow: &dyn BookUnitOfWork, user_id: UserId) -> AppResult<()> {
+        uow.user_repository()
+            .find_current_user(user_id)
+            .await?
+            .ok_or_else(|| {
+                AppError::EntityNotFound(Message::new(MessageKey::UserNotFound).arg(user_id))
+            })
+            .map(|_| ())
+    }
 }
 
 #[async_trait]
 impl BookUseCase for BookUseCaseImpl {
     async fn delete_book(&self, delete_book: DeleteBook) -> AppResult<()> {
         let uow = self.scope.begin().await?;
+        self.authorize_book_change(
+            uow.as_ref(),
+            delete_book.book_id,
+            delete_book.requested_user,
+        )
+        .await?;
         uow.book_repository().delete(delete_book).await?;
         uow.commit().await
     }
@@ -52,6 +104,11 @@ impl BookUseCase for BookUseCaseImpl {
         uow.book_repository().find_by_id(book_id).await
     }
 
+    async fn show_book_change_logs(&self, book_id: BookId) -> AppResult<Vec<BookChangeLog>> {
+        let uow = self.scope.begin().await?;
+        uow.book_repository().find_change_logs(book_id).await
+    }
+
     async fn show_book_list(&self, options: BookListOptions) -> AppResult<PaginatedList<Book>> {
         let uow = self.scope.begin().await?;
         uow.book_repository().find_all(options).await
@@ -59,7 +116,44 @@ impl BookUseCase for BookUseCaseImpl {
 
     async fn update_book(&self, update_book: UpdateBook) -> AppResult<()> {
         let uow = self.scope.begin().await?;
+        self.authorize_book_change(
+            uow.as_ref(),
+            update_book.book_id,
+            update_book.requested_user,
+        )
+        .await?;
         uow.book_repository().update(update_book).await?;
         uow.commit().await
     }
+
+    async fn transfer_all_books(&self, event: TransferAllBooks) -> AppResult<u64> {
+        if event.from_user == event.to_user {
+            return Err(AppError::UnprocessableEntity(
+                Message::new(MessageKey::TransferToSameUser).arg(event.from_user),
+            ));
+        }
+        let uow = self.scope.begin().await?;
+        self.ensure_active_user(uow.as_ref(), event.to_user).await?;
+        let transferred = uow.book_repository().transfer_all(event).await?;
+        uow.commit().await?;
+        Ok(transferred)
+    }
+
+    async fn update_book_owner(&self, event: UpdateBookOwner) -> AppResult<()> {
+        let uow = self.scope.begin().await?;
+        let book = self
+            .authorize_book_change(uow.as_ref(), event.book_id, event.requested_user)
+            .await?;
+        if book.owner().id() == event.new_owner {
+            return Err(AppError::UnprocessableEntity(
+                Message::new(MessageKey::BookAlreadyOwnedByUser)
+                    .arg(event.book_id)
+                    .arg(event.new_owner),
+            ));
+        }
+        self.ensure_active_user(uow.as_ref(), event.new_owner)
+            .await?;
+        uow.book_repository().update_owner(event).await?;
+        uow.commit().await
+    }
 }
@@ -2,37 +2,128 @@ use crate::{
     model::{
         checkout::{
             Checkout, CheckoutState,
-            event::{CreateCheckout, UpdateReturned},
+            event::{CreateCheckout, UpdateRenewed, UpdateReturned},
         },
-        id::BookId,
+        hold::{
+            Hold,
+            event::{CreateHold, DeleteHold},
+        },
+        id::{BookId, UserId},
+        loan_policy::{LoanPolicy, event::UpdateLoanPolicy},
+        role::{Permission, Role},
     },
-    unit_of_work::checkout::CheckoutUnitOfWorkScope,
+    repository::hold::HoldRepository,
+    unit_of_work::checkout::{CheckoutUnitOfWork, CheckoutUnitOfWorkScope},
 };
 use async_trait::async_trait;
-use shared::error::{AppError, AppResult};
+use chrono::{DateTime, Duration, Utc};
+use shared::{
+    config::CheckoutConfig,
+    error::{AppError, AppResult},
+    i18n::{Message, MessageKey},
+};
 use std::sync::Arc;
+use strum::IntoEnumIterator;
 
 #[mockall::automock]
 #[async_trait]
 pub trait CheckoutUseCase: Send + Sync {
+    async fn cancel_hold(&self, event: DeleteHold) -> AppResult<()>;
     async fn checkout_book(&self, event: CreateCheckout) -> AppResult<()>;
     async fn checkout_history(&self, book_id: BookId) -> AppResult<Vec<Checkout>>;
+    async fn place_hold(&self, event: CreateHold) -> AppResult<()>;
+    async fn renew_book(&self, event: UpdateRenewed) -> AppResult<()>;
     async fn return_book(&self, event: UpdateReturned) -> AppResult<()>;
     async fn show_checked_out_list(&self) -> AppResult<Vec<Checkout>>;
+    // 期限切れの取り置きは除くが、次の予約者の繰り上げはこの蔵書の次の貸出・返却・予約の取り消しまで行わない
+    async fn show_hold_list(&self, book_id: BookId) -> AppResult<Vec<Hold>>;
+    async fn show_loan_policies(&self) -> AppResult<Vec<LoanPolicy>>;
+    async fn show_overdue_list(&self) -> AppResult<Vec<Checkout>>;
+    async fn update_loan_policy(&self, event: UpdateLoanPolicy) -> AppResult<()>;
 }
 
 pub struct CheckoutUseCaseImpl {
     scope: Arc<dyn CheckoutUnitOfWorkScope>,
+    config: CheckoutConfig,
 }
 
 impl CheckoutUseCaseImpl {
-    pub fn new(scope: Arc<dyn CheckoutUnitOfWorkScope>) -> Self {
-        Self { scope }
+    pub fn new(scope: Arc<dyn CheckoutUnitOfWorkScope>, config: CheckoutConfig) -> Self {
+        Self { scope, config }
+    }
+
+    // 利用者のロールに対応する貸出ポリシーを取得する
+    async fn resolve_loan_policy(
+        &self,
+        uow: &dyn CheckoutUnitOfWork,
+        user_id: UserId,
+    ) -> AppResult<LoanPolicy> {
+        let Some(user) = uow.user_repository().find_current_user(user_id).await? else {
+            return Err(AppError::EntityNotFound(
+                Message::new(MessageKey::UserNotFound).arg(user_id),
+            ));
+        };
+        let (_, _, _, role) = user.into_parts();
+        let policy = uow.loan_policy_repository().find_by_role(&role).await?;
+        Ok(policy.unwrap_or_else(|| LoanPolicy::default_for(role, &self.config)))
+    }
+
+    // 期限切れの取り置きを取り消し、予約待ちの先頭を取り置き状態にする。
+    // 期限切れを監視する仕組みはないため、貸出・返却・予約の取り消しの際に遅れて行う
+    async fn promote_next_hold(
+        &self,
+        hold_repository: &dyn HoldRepository,
+        book_id: BookId,
+        now: DateTime<Utc>,
+    ) -> AppResult<()> {
+        hold_repository.delete_expired(book_id, now).await?;
+        let holds = hold_repository.find_by_book_id(book_id).await?;
+        match holds.first() {
+            Some(hold) if !hold.is_ready() => {
+                let expires_at = now + Duration::days(self.config.hold_pickup_days);
+                hold_repository
+                    .update_ready(hold.id(), now, expires_at)
+                    .await
+            }
+            _ => Ok(()),
+        }
     }
 }
 
 #[async_trait]
 impl CheckoutUseCase for CheckoutUseCaseImpl {
+    async fn cancel_hold(&self, event: DeleteHold) -> AppResult<()> {
+        let uow = self.scope.begin_serializable().await?;
+
+        {
+            let hold_repository = uow.hold_repository();
+            let holds = hold_repository.find_by_book_id(event.book_id).await?;
+            let Some(hold) = holds.iter().find(|h| h.id() == event.hold_id) else {
+                return Err(AppError::EntityNotFound(
+                    Message::new(MessageKey::HoldNotFound).arg(event.hold_id),
+                ));
+            };
+            if hold.held_by() != event.requested_user {
+                return Err(AppError::ForbiddenOperation);
+            }
+            hold_repository.delete(event.hold_id).await?;
+
+            let res = uow
+                .checkout_repository()
+                .find_checkout_state(event.book_id)
+                .await?;
+            if let Some(CheckoutState {
+                checkout_id: None, ..
+            }) = res
+            {
+                self.promote_next_hold(&*hold_repository, event.book_id, event.requested_at)
+                    .await?;
+            }
+        }
+
+        uow.commit().await
+    }
+
     async fn checkout_book(&self, event: CreateCheckout) -> AppResult<()> {
         let uow = self.scope.begin_serializable().await?;
 
@@ -44,24 +135,48 @@ impl CheckoutUseCase for CheckoutUseCaseImpl {
 
             match res {
                 None => {
-                    return Err(AppError::EntityNotFound(format!(
-                        " 書籍（{}）が見つかりませんでした。",
-                        event.book_id
-                    )));
+                    return Err(AppError::EntityNotFound(
+                        Message::new(MessageKey::BookNotFound).arg(event.book_id),
+                    ));
                 }
                 Some(CheckoutState {
                     checkout_id: Some(_),
                     ..
                 }) => {
-                    return Err(AppError::UnprocessableEntity(format!(
-                        " 書籍（{}）に対する貸出が既に存在します。",
-                        event.book_id
-                    )));
+                    return Err(AppError::UnprocessableEntity(
+                        Message::new(MessageKey::BookAlreadyCheckedOut).arg(event.book_id),
+                    ));
                 }
                 _ => {}
             }
 
-            checkout_repository.insert_checkout(&event).await?;
+            let policy = self
+                .resolve_loan_policy(&*uow, event.checked_out_by)
+                .await?;
+            let loans = checkout_repository
+                .find_unreturned_by_user_id(event.checked_out_by)
+                .await?;
+            if loans.len() as i64 >= policy.max_loans() {
+                return Err(AppError::UnprocessableEntity(
+                    Message::new(MessageKey::LoanLimitReached).arg(policy.max_loans()),
+                ));
+            }
+
+            let hold_repository = uow.hold_repository();
+            self.promote_next_hold(&*hold_repository, event.book_id, event.checked_out_at)
+                .await?;
+            let holds = hold_repository.find_by_book_id(event.book_id).await?;
+            if let Some(hold) = holds.first() {
+                if hold.held_by() != event.checked_out_by {
+                    return Err(AppError::UnprocessableEntity(
+                        Message::new(MessageKey::BookHeldForOtherUser).arg(event.book_id),
+                    ));
+                }
+                hold_repository.delete(hold.id()).await?;
+            }
+
+            let due_at = event.checked_out_at + Duration::days(policy.loan_period_days());
+            checkout_repository.insert_checkout(&event, due_at).await?;
         }
 
         uow.commit().await
@@ -74,7 +189,49 @@ impl CheckoutUseCase for CheckoutUseCaseImpl {
             .await
     }
 
-    async fn return_book(&self, event: UpdateReturned) -> AppResult<()> {
+    async fn place_hold(&self, event: CreateHold) -> AppResult<()> {
+        let uow = self.scope.begin_serializable().await?;
+
+        {
+            let res = uow
+                .checkout_repository()
+                .find_checkout_state(event.book_id)
+                .await?;
+            let Some(state) = res else {
+                return Err(AppError::EntityNotFound(
+                    Message::new(MessageKey::BookNotFound).arg(event.book_id),
+                ));
+            };
+
+            let hold_repository = uow.hold_repository();
+            hold_repository
+                .delete_expired(event.book_id, event.created_at)
+                .await?;
+            let holds = hold_repository.find_by_book_id(event.book_id).await?;
+
+            if state.user_id == Some(event.held_by) {
+                return Err(AppError::UnprocessableEntity(
+                    Message::new(MessageKey::HoldOnOwnCheckout).arg(event.book_id),
+                ));
+            }
+            if state.checkout_id.is_none() && holds.is_empty() {
+                return Err(AppError::UnprocessableEntity(
+                    Message::new(MessageKey::HoldOnAvailableBook).arg(event.book_id),
+                ));
+            }
+            if holds.iter().any(|h| h.held_by() == event.held_by) {
+                return Err(AppError::UnprocessableEntity(
+                    Message::new(MessageKey::HoldAlreadyExists).arg(event.book_id),
+                ));
+            }
+
+            hold_repository.insert(&event).await?;
+        }
+
+        uow.commit().await
+    }
+
+    async fn renew_book(&self, event: UpdateRenewed) -> AppResult<()> {
         let uow = self.scope.begin_serializable().await?;
 
         {
@@ -83,30 +240,109 @@ impl CheckoutUseCase for CheckoutUseCaseImpl {
                 .find_checkout_state(event.book_id)
                 .await?;
 
-            match res {
+            let renewal_count = match res {
                 None => {
-                    return Err(AppError::EntityNotFound(format!(
-                        " 書籍（{}）が見つかりませんでした。",
-                        event.book_id
-                    )));
+                    return Err(AppError::EntityNotFound(
+                        Message::new(MessageKey::BookNotFound).arg(event.book_id),
+                    ));
                 }
                 Some(CheckoutState {
                     checkout_id: Some(c),
                     user_id: Some(u),
+                    renewal_count,
                     ..
-                }) if (c, u) != (event.checkout_id, event.returned_by) => {
-                    return Err(AppError::UnprocessableEntity(format!(
-                        " 指定の貸出（ID（{}）, ユーザー（{}）, 書籍（{}））は返却できません。",
-                        event.checkout_id, event.returned_by, event.book_id
-                    )));
+                }) if (c, u) == (event.checkout_id, event.renewed_by) => renewal_count,
+                _ => {
+                    return Err(AppError::UnprocessableEntity(
+                        Message::new(MessageKey::CheckoutNotRenewable)
+                            .arg(event.checkout_id)
+                            .arg(event.renewed_by)
+                            .arg(event.book_id),
+                    ));
+                }
+            };
+
+            let policy = self.resolve_loan_policy(&*uow, event.renewed_by).await?;
+            if renewal_count >= policy.max_renewals() {
+                return Err(AppError::UnprocessableEntity(
+                    Message::new(MessageKey::RenewalLimitReached)
+                        .arg(event.checkout_id)
+                        .arg(policy.max_renewals()),
+                ));
+            }
+
+            let holds = uow.hold_repository().find_by_book_id(event.book_id).await?;
+            if !holds.is_empty() {
+                return Err(AppError::UnprocessableEntity(
+                    Message::new(MessageKey::RenewalBlockedByHold).arg(event.book_id),
+                ));
+            }
+
+            let due_at = event.renewed_at + Duration::days(policy.loan_period_days());
+            checkout_repository
+                .insert_renewed_checkout(&event, due_at)
+                .await?;
+            checkout_repository
+                .update_due_at(event.checkout_id, due_at)
+                .await?;
+        }
+
+        uow.commit().await
+    }
+
+    async fn return_book(&self, event: UpdateReturned) -> AppResult<()> {
+        let uow = self.scope.begin_serializable().await?;
+
+        {
+            let Some(book) = uow.book_repository().find_by_id(event.book_id).await? else {
+                return Err(AppError::EntityNotFound(
+                    Message::new(MessageKey::BookNotFound).arg(event.book_id),
+                ));
+            };
+
+            let checkout_repository = uow.checkout_repository();
+            let Some(checkout) = checkout_repository
+                .find_unreturned_by_checkout_id(event.checkout_id)
+                .await?
+            else {
+                return Err(AppError::EntityNotFound(
+                    Message::new(MessageKey::CheckoutNotFound).arg(event.checkout_id),
+                ));
+            };
+
+            if checkout.book().book_id() != event.book_id {
+                return Err(AppError::CheckoutBookMismatch(
+                    Message::new(MessageKey::CheckoutBookMismatch)
+                        .arg(event.checkout_id)
+                        .arg(event.book_id),
+                ));
+            }
+
+            // 借りた本人のほか、代理で手続きできるロールのユーザーと書籍の所有者も返却できる
+            if checkout.checked_out_by() != event.returned_by
+                && book.owner().id() != event.returned_by
+            {
+                let on_behalf = uow
+                    .user_repository()
+                    .find_current_user(event.returned_by)
+                    .await?
+                    .is_some_and(|u| u.role().has_permission(Permission::CheckoutOnBehalf));
+                if !on_behalf {
+                    return Err(AppError::ReturnForbidden(
+                        Message::new(MessageKey::ReturnForbidden)
+                            .arg(event.returned_by)
+                            .arg(event.checkout_id),
+                    ));
                 }
-                _ => {}
             }
 
             checkout_repository.insert_returned_checkout(&event).await?;
             checkout_repository
                 .delete_checkout(event.checkout_id)
                 .await?;
+
+            self.promote_next_hold(&*uow.hold_repository(), event.book_id, event.returned_at)
+                .await?;
         }
 
         uow.commit().await
@@ -116,4 +352,33 @@ impl CheckoutUseCase for CheckoutUseCaseImpl {
         let uow = self.scope.begin().await?;
         uow.checkout_repository().find_unreturned_all().await
     }
+
+    async fn show_hold_list(&self, book_id: BookId) -> AppResult<Vec<Hold>> {
+        let uow = self.scope.begin().await?;
+        uow.hold_repository().find_by_book_id(book_id).await
+    }
+
+    async fn show_loan_policies(&self) -> AppResult<Vec<LoanPolicy>> {
+        let uow = self.scope.begin().await?;
+        let mut policies = uow.loan_policy_repository().find_all().await?;
+        Ok(Role::iter()
+            .map(
+                |role| match policies.iter().position(|p| p.role() == &role) {
+                    Some(i) => policies.swap_remove(i),
+                    None => LoanPolicy::default_for(role, &self.config),
+                },
+            )
+            .collect())
+    }
+
+    async fn show_overdue_list(&self) -> AppResult<Vec<Checkout>> {
+        let uow = self.scope.begin().await?;
+        uow.checkout_repository().find_overdue_all(Utc::now()).await
+    }
+
+    async fn update_loan_policy(&self, event: UpdateLoanPolicy) -> AppResult<()> {
+        let uow = self.scope.begin().await?;
+        uow.loan_policy_repository().update(&event).await?;
+        uow.commit().await
+    }
 }
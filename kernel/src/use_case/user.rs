@@ -1,15 +1,29 @@
 use crate::{
+    mail::MailSender,
     model::{
+        auth::{EmailVerificationToken, LoginAttemptKey, event::CreateEmailVerificationToken},
         checkout::Checkout,
+        hold::Hold,
+        id::UserId,
+        list::PaginatedList,
+        mail::Mail,
+        role::Role,
         user::{
-            User,
-            event::{CreateUser, DeleteUser, UpdateUserPassword, UpdateUserRole},
+            User, UserListOptions,
+            event::{
+                CreateUser, DeactivateUser, DeleteUser, UpdatePasswordHash, UpdateUserEmail,
+                UpdateUserName, UpdateUserPassword, UpdateUserProfile, UpdateUserRole,
+            },
         },
     },
-    unit_of_work::user::UserUnitOfWorkScope,
+    password::PasswordHasher,
+    unit_of_work::user::{UserUnitOfWork, UserUnitOfWorkScope},
 };
 use async_trait::async_trait;
-use shared::error::{AppError, AppResult};
+use shared::{
+    error::{AppError, AppResult},
+    i18n::{Locale, Message, MessageKey},
+};
 use std::sync::Arc;
 
 #[mockall::automock]
@@ -17,19 +31,135 @@ use std::sync::Arc;
 pub trait UserUseCase: Send + Sync {
     async fn change_password(&self, event: UpdateUserPassword) -> AppResult<()>;
     async fn change_role(&self, event: UpdateUserRole) -> AppResult<()>;
+    // 確認用のトークンを検証し、変更後のメールアドレスを反映する
+    async fn confirm_email_change(
+        &self,
+        verification_token: EmailVerificationToken,
+    ) -> AppResult<()>;
+    // 返却されていない貸出があるユーザーは無効化できない
+    async fn deactivate_user(&self, event: DeactivateUser) -> AppResult<()>;
+    // 無効化済みのユーザーのみ、蔵書や貸出の記録ごと完全に削除できる
     async fn delete_user(&self, event: DeleteUser) -> AppResult<()>;
-    async fn get_checkouts(&self, user_id: crate::model::id::UserId) -> AppResult<Vec<Checkout>>;
-    async fn list_users(&self) -> AppResult<Vec<User>>;
+    async fn get_checkouts(&self, user_id: UserId) -> AppResult<Vec<Checkout>>;
+    async fn get_holds(&self, user_id: UserId) -> AppResult<Vec<Hold>>;
+    async fn list_users(&self, options: UserListOptions) -> AppResult<PaginatedList<User>>;
     async fn register_user(&self, event: CreateUser) -> AppResult<User>;
+    // ログイン失敗によるアカウントのロックを解除する。
+    // そのアカウントへの試行でロックされた接続元があれば、それらのロックも併せて解除する
+    async fn unlock_user(&self, user_id: UserId) -> AppResult<()>;
+    // 名前はすぐに反映する。メールアドレスは変更後のアドレスに確認用のメールを送り、
+    // 確認が済むまでは変更前のアドレスのままとする
+    async fn update_profile(&self, event: UpdateUserProfile) -> AppResult<User>;
 }
 
 pub struct UserUseCaseImpl {
     scope: Arc<dyn UserUnitOfWorkScope>,
+    password_hasher: Arc<dyn PasswordHasher>,
+    mail_sender: Arc<dyn MailSender>,
+    email_verification_ttl: u64,
 }
 
 impl UserUseCaseImpl {
-    pub fn new(scope: Arc<dyn UserUnitOfWorkScope>) -> Self {
-        Self { scope }
+    pub fn new(
+        scope: Arc<dyn UserUnitOfWorkScope>,
+        password_hasher: Arc<dyn PasswordHasher>,
+        mail_sender: Arc<dyn MailSender>,
+        email_verification_ttl: u64,
+    ) -> Self {
+        Self {
+            scope,
+            password_hasher,
+            mail_sender,
+            email_verification_ttl,
+        }
+    }
+
+    async fn find_user(&self, uow: &dyn UserUnitOfWork, user_id: UserId) -> AppResult<User> {
+        uow.user_repository()
+            .find_current_user(user_id)
+            .await?
+            .ok_or_else(|| {
+                AppError::EntityNotFound(Message::new(MessageKey::UserNotFound).arg(user_id))
+            })
+    }
+
+    // 有効な管理者が一人もいなくなると、ユーザーを管理できなくなるため拒否する
+    async fn ensure_not_last_admin(&self, uow: &dyn UserUnitOfWork, user: &User) -> AppResult<()> {
+        if user.role() == &Role::Admin
+            && uow.user_repository().count_by_role(&Role::Admin).await? <= 1
+        {
+            return Err(AppError::UnprocessableEntity(
+                Message::new(MessageKey::LastAdministrator).arg(user.id()),
+            ));
+        }
+        Ok(())
+    }
+
+    async fn try_change_role(&self, event: &UpdateUserRole) -> AppResult<()> {
+        // 管理者の人数を数えてから変更するまでの間に、他の管理者が降格されないようにする
+        let uow = self.scope.begin_serializable().await?;
+        let user = self.find_user(uow.as_ref(), event.user_id).await?;
+        if event.role != Role::Admin {
+            self.ensure_not_last_admin(uow.as_ref(), &user).await?;
+        }
+        uow.user_repository().update_role(event.clone()).await?;
+        uow.commit().await
+    }
+
+    async fn try_deactivate_user(&self, event: &DeactivateUser) -> AppResult<()> {
+        let uow = self.scope.begin_serializable().await?;
+        let user_id = event.user_id;
+        let user = self.find_user(uow.as_ref(), user_id).await?;
+        self.ensure_not_last_admin(uow.as_ref(), &user).await?;
+        let checkouts = uow
+            .checkout_repository()
+            .find_unreturned_by_user_id(user_id)
+            .await?;
+        if !checkouts.is_empty() {
+            return Err(AppError::UnprocessableEntity(
+                Message::new(MessageKey::UserHasUnreturnedCheckouts)
+                    .arg(user_id)
+                    .arg(checkouts.len()),
+            ));
+        }
+        uow.user_repository().deactivate(event.clone()).await?;
+        // 予約の順番待ちを塞がないよう、予約も取り消す。取り置き中だった書籍は、
+        // 次に貸出の手続きや予約の取り消しがあった時点で次の予約者に回る
+        uow.hold_repository().delete_by_user_id(user_id).await?;
+        uow.commit().await
+    }
+
+    // 権限の変更や無効化が確定してから、変更前の状態で発行されたセッションを失効させる
+    async fn revoke_sessions(&self, user_id: UserId) -> AppResult<()> {
+        let uow = self.scope.begin().await?;
+        uow.auth_repository().delete_all_sessions(user_id).await?;
+        uow.commit().await
+    }
+}
+
+// 直列化の競合で失敗したトランザクションをやり直す回数
+const SERIALIZATION_RETRIES: usize = 3;
+
+// SERIALIZABLE のトランザクションが競合で失敗した場合は、決まった回数までやり直す。
+// それでも競合する場合は、クライアントに再試行を促すため Conflict とする
+async fn retry_on_serialization_failure<F, Fut>(mut run: F) -> AppResult<()>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = AppResult<()>>,
+{
+    let mut retries = 0;
+    loop {
+        match run().await {
+            Err(e) if e.is_serialization_failure() => {
+                if retries == SERIALIZATION_RETRIES {
+                    return Err(AppError::Conflict(Message::new(
+                        MessageKey::SerializationConflict,
+                    )));
+                }
+                retries += 1;
+            }
+            result => return result,
+        }
     }
 }
 
@@ -42,42 +172,176 @@ impl UserUseCase for UserUseCaseImpl {
             let original_password_hash = user_repository
                 .find_password_hash_by_user_id(event.user_id)
                 .await?;
-            if !bcrypt::verify(&event.current_password, &original_password_hash)? {
+            if !self
+                .password_hasher
+                .verify(&event.current_password, &original_password_hash)?
+            {
                 return Err(AppError::UnauthenticatedError);
             }
-            user_repository.update_password(event).await?;
+            let user_id = event.user_id;
+            user_repository
+                .update_password_hash(UpdatePasswordHash {
+                    user_id,
+                    password_hash: self.password_hasher.hash(&event.new_password)?,
+                })
+                .await?;
+            uow.auth_repository().delete_all_sessions(user_id).await?;
         }
         uow.commit().await
     }
 
     async fn change_role(&self, event: UpdateUserRole) -> AppResult<()> {
+        let user_id = event.user_id;
+        retry_on_serialization_failure(|| self.try_change_role(&event)).await?;
+        self.revoke_sessions(user_id).await
+    }
+
+    async fn confirm_email_change(
+        &self,
+        verification_token: EmailVerificationToken,
+    ) -> AppResult<()> {
         let uow = self.scope.begin().await?;
-        uow.user_repository().update_role(event).await?;
+        let change = uow
+            .auth_repository()
+            .consume_email_verification_token(&verification_token)
+            .await?
+            .ok_or_else(|| {
+                AppError::UnprocessableEntity(Message::new(
+                    MessageKey::EmailVerificationTokenInvalid,
+                ))
+            })?;
+        uow.user_repository()
+            .update_email(UpdateUserEmail {
+                user_id: change.user_id,
+                email: change.email,
+            })
+            .await?;
         uow.commit().await
     }
 
+    async fn deactivate_user(&self, event: DeactivateUser) -> AppResult<()> {
+        let user_id = event.user_id;
+        retry_on_serialization_failure(|| self.try_deactivate_user(&event)).await?;
+        self.revoke_sessions(user_id).await
+    }
+
     async fn delete_user(&self, event: DeleteUser) -> AppResult<()> {
         let uow = self.scope.begin().await?;
+        let user_id = event.user_id;
+        if uow
+            .user_repository()
+            .find_current_user(user_id)
+            .await?
+            .is_some()
+        {
+            return Err(AppError::UnprocessableEntity(
+                Message::new(MessageKey::UserNotDeactivated).arg(user_id),
+            ));
+        }
         uow.user_repository().delete(event).await?;
+        uow.auth_repository().delete_all_sessions(user_id).await?;
         uow.commit().await
     }
 
-    async fn get_checkouts(&self, user_id: crate::model::id::UserId) -> AppResult<Vec<Checkout>> {
+    async fn get_checkouts(&self, user_id: UserId) -> AppResult<Vec<Checkout>> {
         let uow = self.scope.begin().await?;
         uow.checkout_repository()
             .find_unreturned_by_user_id(user_id)
             .await
     }
 
-    async fn list_users(&self) -> AppResult<Vec<User>> {
+    async fn get_holds(&self, user_id: UserId) -> AppResult<Vec<Hold>> {
+        let uow = self.scope.begin().await?;
+        uow.hold_repository().find_by_user_id(user_id).await
+    }
+
+    async fn list_users(&self, options: UserListOptions) -> AppResult<PaginatedList<User>> {
         let uow = self.scope.begin().await?;
-        uow.user_repository().find_all().await
+        uow.user_repository().find_all(options).await
     }
 
     async fn register_user(&self, event: CreateUser) -> AppResult<User> {
+        let password_hash = self.password_hasher.hash(&event.password)?;
         let uow = self.scope.begin().await?;
-        let user = uow.user_repository().create(event).await?;
+        let user = uow.user_repository().create(event, password_hash).await?;
         uow.commit().await?;
         Ok(user)
     }
+
+    async fn unlock_user(&self, user_id: UserId) -> AppResult<()> {
+        let uow = self.scope.begin().await?;
+        let user = self.find_user(uow.as_ref(), user_id).await?;
+        let email = user.email().as_ref().to_lowercase();
+        {
+            let repository = uow.auth_repository();
+            for ip_address in repository.find_login_failure_ips(&email).await? {
+                repository
+                    .clear_login_failures(&LoginAttemptKey::IpAddress(ip_address))
+                    .await?;
+            }
+            repository
+                .clear_login_failures(&LoginAttemptKey::Email(email))
+                .await?;
+        }
+        uow.commit().await
+    }
+
+    async fn update_profile(&self, event: UpdateUserProfile) -> AppResult<User> {
+        let UpdateUserProfile {
+            user_id,
+            name,
+            email,
+        } = event;
+        let uow = self.scope.begin().await?;
+        let user = self.find_user(uow.as_ref(), user_id).await?;
+        if user.name() != &name {
+            uow.user_repository()
+                .update_name(UpdateUserName {
+                    user_id,
+                    name: name.clone(),
+                })
+                .await?;
+        }
+        let verification_token = if user.email() != &email {
+            // 確認が済んだ時点でも重複は検出するが、使えないアドレスには確認用のメールを送らない
+            if uow
+                .user_repository()
+                .find_by_email(email.as_ref())
+                .await?
+                .is_some_and(|other| other.id() != user_id)
+            {
+                return Err(AppError::Conflict(
+                    Message::new(MessageKey::EmailAlreadyInUse).arg(&email),
+                ));
+            }
+            let token = uow
+                .auth_repository()
+                .create_email_verification_token(
+                    CreateEmailVerificationToken::new(user_id, email.clone()),
+                    self.email_verification_ttl,
+                )
+                .await?;
+            Some(token)
+        } else {
+            None
+        };
+        uow.commit().await?;
+
+        if let Some(verification_token) = verification_token {
+            let locale = Locale::current();
+            let subject = Message::new(MessageKey::EmailVerificationMailSubject).render(locale);
+            let body = Message::new(MessageKey::EmailVerificationMailBody)
+                .arg(&name)
+                .arg(&email)
+                .arg(verification_token.0)
+                .arg(self.email_verification_ttl / 60)
+                .render(locale);
+            self.mail_sender
+                .send(Mail::new(email, subject, body))
+                .await?;
+        }
+
+        let (id, _, current_email, role) = user.into_parts();
+        Ok(User::new(id, name, current_email, role))
+    }
 }
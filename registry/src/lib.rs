@@ -1,4 +1,7 @@
-use adapter::{database::ConnectionPool, redis::RedisClient, unit_of_work::UnitOfWorkScopeImpl};
+use adapter::{
+    database::ConnectionPool, jwt::JwtKeys, mail::LocalMailSender, oidc::OidcClient,
+    password::Argon2PasswordHasher, redis::RedisClient, unit_of_work::UnitOfWorkScopeImpl,
+};
 use kernel::use_case::{
     auth::{AuthUseCase, AuthUseCaseImpl},
     book::{BookUseCase, BookUseCaseImpl},
@@ -6,7 +9,10 @@ use kernel::use_case::{
     health::{HealthCheckUseCase, HealthCheckUseCaseImpl},
     user::{UserUseCase, UserUseCaseImpl},
 };
-use shared::config::AppConfig;
+use shared::{
+    config::{AppConfig, AuthBackend, PasswordPolicyConfig, ProxyConfig},
+    error::AppResult,
+};
 use std::sync::Arc;
 
 #[derive(Clone)]
@@ -16,32 +22,61 @@ pub struct AppRegistryImpl {
     auth_use_case: Arc<dyn AuthUseCase>,
     user_use_case: Arc<dyn UserUseCase>,
     checkout_use_case: Arc<dyn CheckoutUseCase>,
+    password_policy: PasswordPolicyConfig,
+    proxy: ProxyConfig,
 }
 
 impl AppRegistryImpl {
-    pub fn new(
-        pool: ConnectionPool,
-        redis_client: Arc<RedisClient>,
-        app_config: AppConfig,
-    ) -> Self {
-        let scope = Arc::new(UnitOfWorkScopeImpl::new(
-            Arc::new(pool.clone()),
-            redis_client.clone(),
-            app_config.auth.ttl,
-        ));
+    pub fn new(pool: ConnectionPool, app_config: AppConfig) -> AppResult<Self> {
+        let db = Arc::new(pool);
+        let scope = Arc::new(match &app_config.auth.backend {
+            AuthBackend::Redis(redis) => UnitOfWorkScopeImpl::new(
+                db,
+                Arc::new(RedisClient::new(redis)?),
+                app_config.auth.ttl,
+            ),
+            AuthBackend::Jwt(jwt) => UnitOfWorkScopeImpl::new_with_jwt_keys(
+                db,
+                Arc::new(JwtKeys::new(jwt)?),
+                app_config.auth.ttl,
+            ),
+        });
         let health_check_use_case = Arc::new(HealthCheckUseCaseImpl::new(scope.clone()));
         let book_use_case = Arc::new(BookUseCaseImpl::new(scope.clone()));
-        let auth_use_case = Arc::new(AuthUseCaseImpl::new(scope.clone()));
-        let user_use_case = Arc::new(UserUseCaseImpl::new(scope.clone()));
-        let checkout_use_case = Arc::new(CheckoutUseCaseImpl::new(scope.clone()));
+        let mail_sender = Arc::new(LocalMailSender::new(&app_config.mail));
+        let password_hasher = Arc::new(Argon2PasswordHasher::new(&app_config.auth.password_hash)?);
+        let mut auth_use_case = AuthUseCaseImpl::new(
+            scope.clone(),
+            mail_sender.clone(),
+            password_hasher.clone(),
+            app_config.auth.password_reset_ttl,
+            app_config.auth.refresh_token_ttl,
+            app_config.auth.lockout,
+            app_config.auth.mfa.clone(),
+        );
+        if let Some(oidc) = &app_config.auth.oidc {
+            auth_use_case =
+                auth_use_case.with_oidc(Arc::new(OidcClient::new(oidc)), oidc.auto_provision);
+        }
+        let auth_use_case = Arc::new(auth_use_case);
+        let user_use_case = Arc::new(UserUseCaseImpl::new(
+            scope.clone(),
+            password_hasher,
+            mail_sender,
+            app_config.auth.email_verification_ttl,
+        ));
+        let checkout_use_case =
+            Arc::new(CheckoutUseCaseImpl::new(scope.clone(), app_config.checkout));
 
-        Self {
+        Ok(Self {
             health_check_use_case,
             book_use_case,
             auth_use_case,
             user_use_case,
             checkout_use_case,
-        }
+            password_policy: app_config.auth.password_policy,
+            proxy: app_config.proxy,
+        })
     }
 
     pub fn health_check_use_case(&self) -> Arc<dyn HealthCheckUseCase> {
@@ -63,6 +98,14 @@ impl AppRegistryImpl {
     pub fn checkout_use_case(&self) -> Arc<dyn CheckoutUseCase> {
         self.checkout_use_case.clone()
     }
+
+    pub fn password_policy(&self) -> PasswordPolicyConfig {
+        self.password_policy
+    }
+
+    pub fn proxy_config(&self) -> ProxyConfig {
+        self.proxy.clone()
+    }
 }
 
 #[mockall::automock]
@@ -72,6 +115,8 @@ pub trait AppRegistryExt {
     fn auth_use_case(&self) -> Arc<dyn AuthUseCase>;
     fn checkout_use_case(&self) -> Arc<dyn CheckoutUseCase>;
     fn user_use_case(&self) -> Arc<dyn UserUseCase>;
+    fn password_policy(&self) -> PasswordPolicyConfig;
+    fn proxy_config(&self) -> ProxyConfig;
 }
 
 impl AppRegistryExt for AppRegistryImpl {
@@ -94,6 +139,14 @@ impl AppRegistryExt for AppRegistryImpl {
     fn checkout_use_case(&self) -> Arc<dyn CheckoutUseCase> {
         self.checkout_use_case.clone()
     }
+
+    fn password_policy(&self) -> PasswordPolicyConfig {
+        self.password_policy
+    }
+
+    fn proxy_config(&self) -> ProxyConfig {
+        self.proxy.clone()
+    }
 }
 
 pub type AppRegistry = Arc<dyn AppRegistryExt + Send + Sync + 'static>;
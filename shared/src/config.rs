@@ -1,9 +1,14 @@
-use anyhow::Result;
+use anyhow::{Result, bail};
+use ipnet::IpNet;
+use std::{net::IpAddr, str::FromStr};
+use strum::EnumString;
 
 pub struct AppConfig {
     pub database: DatabaseConfig,
-    pub redis: RedisConfig,
     pub auth: AuthConfig,
+    pub checkout: CheckoutConfig,
+    pub mail: MailConfig,
+    pub proxy: ProxyConfig,
 }
 
 impl AppConfig {
@@ -16,17 +21,53 @@ impl AppConfig {
             database: std::env::var("DATABASE_NAME")?,
         };
 
-        let redis = RedisConfig {
-            host: std::env::var("REDIS_HOST")?,
-            port: std::env::var("REDIS_PORT")?.parse::<u16>()?,
-        };
         let auth = AuthConfig {
             ttl: std::env::var("AUTH_TOKEN_TTL")?.parse::<u64>()?,
+            password_reset_ttl: std::env::var("AUTH_PASSWORD_RESET_TTL")?.parse::<u64>()?,
+            email_verification_ttl: std::env::var("AUTH_EMAIL_VERIFICATION_TTL")?.parse::<u64>()?,
+            refresh_token_ttl: std::env::var("AUTH_REFRESH_TOKEN_TTL")?.parse::<u64>()?,
+            backend: AuthBackend::from_env()?,
+            lockout: LoginLockoutConfig {
+                email_threshold: std::env::var("AUTH_LOCKOUT_EMAIL_THRESHOLD")?.parse::<u32>()?,
+                ip_threshold: std::env::var("AUTH_LOCKOUT_IP_THRESHOLD")?.parse::<u32>()?,
+                base_secs: std::env::var("AUTH_LOCKOUT_BASE_SECS")?.parse::<u64>()?,
+                max_secs: std::env::var("AUTH_LOCKOUT_MAX_SECS")?.parse::<u64>()?,
+            },
+            mfa: MfaConfig {
+                issuer: std::env::var("AUTH_MFA_ISSUER")?,
+                challenge_ttl: std::env::var("AUTH_MFA_CHALLENGE_TTL")?.parse::<u64>()?,
+                required_for_admin: std::env::var("AUTH_MFA_REQUIRED_FOR_ADMIN")?
+                    .parse::<bool>()?,
+            },
+            oidc: OidcConfig::from_env()?,
+            password_hash: PasswordHashConfig {
+                memory_cost: std::env::var("AUTH_PASSWORD_HASH_MEMORY_COST")?.parse::<u32>()?,
+                time_cost: std::env::var("AUTH_PASSWORD_HASH_TIME_COST")?.parse::<u32>()?,
+                parallelism: std::env::var("AUTH_PASSWORD_HASH_PARALLELISM")?.parse::<u32>()?,
+            },
+            password_policy: PasswordPolicyConfig {
+                min_length: std::env::var("AUTH_PASSWORD_MIN_LENGTH")?.parse::<usize>()?,
+                min_char_classes: std::env::var("AUTH_PASSWORD_MIN_CHAR_CLASSES")?
+                    .parse::<usize>()?,
+            },
+        };
+        let checkout = CheckoutConfig {
+            loan_period_days: std::env::var("CHECKOUT_LOAN_PERIOD_DAYS")?.parse::<i64>()?,
+            hold_pickup_days: std::env::var("CHECKOUT_HOLD_PICKUP_DAYS")?.parse::<i64>()?,
+            max_renewals: std::env::var("CHECKOUT_MAX_RENEWALS")?.parse::<i64>()?,
+            max_loans: std::env::var("CHECKOUT_MAX_LOANS")?.parse::<i64>()?,
         };
+        let mail = MailConfig {
+            sender: std::env::var("MAIL_SENDER")?,
+            output_dir: std::env::var("MAIL_OUTPUT_DIR").ok(),
+        };
+        let proxy = ProxyConfig::from_env()?;
         Ok(Self {
             database,
-            redis,
             auth,
+            checkout,
+            mail,
+            proxy,
         })
     }
 }
@@ -46,4 +87,222 @@ pub struct RedisConfig {
 
 pub struct AuthConfig {
     pub ttl: u64,
+    pub password_reset_ttl: u64,
+    pub email_verification_ttl: u64,
+    pub refresh_token_ttl: u64,
+    pub backend: AuthBackend,
+    pub lockout: LoginLockoutConfig,
+    pub mfa: MfaConfig,
+    pub oidc: Option<OidcConfig>,
+    pub password_hash: PasswordHashConfig,
+    pub password_policy: PasswordPolicyConfig,
+}
+
+/// ログイン失敗が続いた場合の一時的なロックの設定。
+#[derive(Debug, Clone, Copy)]
+pub struct LoginLockoutConfig {
+    // 同じメールアドレスでこの回数失敗するとロックする
+    pub email_threshold: u32,
+    // 同じ IP アドレスからこの回数失敗するとロックする
+    pub ip_threshold: u32,
+    // 最初のロック時間。以降は失敗のたびに倍にする
+    pub base_secs: u64,
+    // ロック時間の上限。失敗回数もこの時間が経つと数え直す
+    pub max_secs: u64,
+}
+
+/// TOTP による二要素認証の設定。
+#[derive(Debug, Clone)]
+pub struct MfaConfig {
+    // 認証アプリに表示する発行者名
+    pub issuer: String,
+    // パスワード認証後、確認コードの入力を待つ秒数
+    pub challenge_ttl: u64,
+    // 有効にすると、管理者は二要素認証を設定するまでログインを完了できない
+    pub required_for_admin: bool,
+}
+
+/// argon2id でパスワードをハッシュ化する際のパラメータ。
+/// 変更すると、以降のログイン時に既存のハッシュも新しいパラメータで作り直す。
+#[derive(Debug, Clone, Copy)]
+pub struct PasswordHashConfig {
+    // 使用するメモリ量 (KiB)
+    pub memory_cost: u32,
+    // 反復回数
+    pub time_cost: u32,
+    // 並列度
+    pub parallelism: u32,
+}
+
+/// 登録時やパスワード変更時に求めるパスワードの強度。
+#[derive(Debug, Clone, Copy)]
+pub struct PasswordPolicyConfig {
+    // 最小の文字数
+    pub min_length: usize,
+    // 英小文字・英大文字・数字・記号のうち、含める必要がある種類の数
+    pub min_char_classes: usize,
+}
+
+/// 外部の ID プロバイダーによるシングルサインオンの設定。
+pub struct OidcConfig {
+    // ディスカバリー文書の取得元。ID トークンの iss もこの値と照合する
+    pub issuer_url: String,
+    pub client_id: String,
+    // PKCE のみで認証する公開クライアントの場合は未指定
+    pub client_secret: Option<String>,
+    // ID プロバイダーに登録した /auth/oidc/callback の URL
+    pub redirect_url: String,
+    // 有効にすると、未登録のメールアドレスでログインした場合に一般ユーザーとして登録する
+    pub auto_provision: bool,
+}
+
+impl OidcConfig {
+    // AUTH_OIDC_ISSUER_URL が未指定ないしは空の場合はシングルサインオンを無効にする
+    fn from_env() -> Result<Option<Self>> {
+        let Some(issuer_url) = non_empty_var("AUTH_OIDC_ISSUER_URL") else {
+            return Ok(None);
+        };
+        Ok(Some(Self {
+            issuer_url,
+            client_id: std::env::var("AUTH_OIDC_CLIENT_ID")?,
+            client_secret: non_empty_var("AUTH_OIDC_CLIENT_SECRET"),
+            redirect_url: std::env::var("AUTH_OIDC_REDIRECT_URL")?,
+            auto_provision: non_empty_var("AUTH_OIDC_AUTO_PROVISION")
+                .map(|v| v.parse::<bool>())
+                .transpose()?
+                .unwrap_or(false),
+        }))
+    }
+}
+
+fn non_empty_var(key: &str) -> Option<String> {
+    std::env::var(key).ok().filter(|v| !v.is_empty())
+}
+
+/// アクセストークンの発行・検証方式。
+pub enum AuthBackend {
+    /// トークンを Redis に保存し、リクエストごとに照会する。
+    Redis(RedisConfig),
+    /// 署名付きの JWT を発行し、署名と有効期限だけで検証する。
+    /// セッションやログインの失敗回数など、認証に関わる状態はすべて PostgreSQL に保存するため、
+    /// Redis は使わない。
+    /// 署名済みのトークンは延長できないため、Redis と違って利用のたびに有効期限が延びることはない。
+    /// 発行から AUTH_TOKEN_TTL が経過したら、リフレッシュトークンで再発行する。
+    Jwt(JwtConfig),
+}
+
+impl AuthBackend {
+    // AUTH_BACKEND が未指定の場合は Redis を使う。REDIS_* は Redis を使う場合のみ読み込む
+    fn from_env() -> Result<Self> {
+        match std::env::var("AUTH_BACKEND").as_deref() {
+            Err(_) | Ok("redis") => Ok(Self::Redis(RedisConfig {
+                host: std::env::var("REDIS_HOST")?,
+                port: std::env::var("REDIS_PORT")?.parse::<u16>()?,
+            })),
+            Ok("jwt") => Ok(Self::Jwt(JwtConfig::from_env()?)),
+            Ok(other) => bail!("unknown AUTH_BACKEND: {other}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString)]
+pub enum JwtAlgorithm {
+    HS256,
+    EdDSA,
+}
+
+pub struct JwtConfig {
+    pub algorithm: JwtAlgorithm,
+    // HS256 では共通鍵、EdDSA では PEM 形式の秘密鍵
+    pub signing_key: String,
+    // HS256 では共通鍵、EdDSA では PEM 形式の公開鍵
+    pub verifying_key: String,
+}
+
+// HS256 の共通鍵は、ハッシュの出力長と同じ 256 ビット以上を求める
+const MIN_HS256_SECRET_BYTES: usize = 32;
+
+impl JwtConfig {
+    pub fn new(
+        algorithm: JwtAlgorithm,
+        signing_key: String,
+        verifying_key: String,
+    ) -> Result<Self> {
+        if algorithm == JwtAlgorithm::HS256 && signing_key.len() < MIN_HS256_SECRET_BYTES {
+            bail!("AUTH_JWT_SECRET must be at least {MIN_HS256_SECRET_BYTES} bytes");
+        }
+        Ok(Self {
+            algorithm,
+            signing_key,
+            verifying_key,
+        })
+    }
+
+    fn from_env() -> Result<Self> {
+        let algorithm = JwtAlgorithm::from_str(&std::env::var("AUTH_JWT_ALGORITHM")?)?;
+        let (signing_key, verifying_key) = match algorithm {
+            JwtAlgorithm::HS256 => {
+                let secret = std::env::var("AUTH_JWT_SECRET")?;
+                (secret.clone(), secret)
+            }
+            JwtAlgorithm::EdDSA => (
+                std::env::var("AUTH_JWT_PRIVATE_KEY")?,
+                std::env::var("AUTH_JWT_PUBLIC_KEY")?,
+            ),
+        };
+        Self::new(algorithm, signing_key, verifying_key)
+    }
+}
+
+pub struct CheckoutConfig {
+    pub loan_period_days: i64,
+    pub hold_pickup_days: i64,
+    pub max_renewals: i64,
+    pub max_loans: i64,
+}
+
+pub struct MailConfig {
+    pub sender: String,
+    // 指定した場合はメールをファイルとして書き出し、未指定の場合はログに出力する
+    pub output_dir: Option<String>,
+}
+
+/// リバースプロキシ配下で動かす場合の設定。
+#[derive(Debug, Clone, Default)]
+pub struct ProxyConfig {
+    // X-Forwarded-For を信頼する接続元。IP アドレスないしは CIDR 形式で指定する
+    pub trusted_proxies: Vec<IpNet>,
+}
+
+impl ProxyConfig {
+    // TRUSTED_PROXIES はカンマ区切り。未指定の場合は X-Forwarded-For を一切信頼しない
+    fn from_env() -> Result<Self> {
+        let trusted_proxies = non_empty_var("TRUSTED_PROXIES")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(|v| {
+                v.parse::<IpNet>()
+                    .or_else(|_| v.parse::<IpAddr>().map(IpNet::from))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { trusted_proxies })
+    }
+
+    pub fn is_trusted(&self, ip: IpAddr) -> bool {
+        self.trusted_proxies.iter().any(|net| net.contains(&ip))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jwt_secret_length() {
+        let secret = |len: usize| "s".repeat(len);
+        assert!(JwtConfig::new(JwtAlgorithm::HS256, secret(31), secret(31)).is_err());
+        assert!(JwtConfig::new(JwtAlgorithm::HS256, secret(32), secret(32)).is_ok());
+    }
 }
@@ -1,12 +1,30 @@
-use axum::{http::StatusCode, response::IntoResponse};
+use axum::{
+    Json,
+    http::{StatusCode, header},
+    response::IntoResponse,
+};
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::i18n::{Locale, Message, MessageKey};
+
+#[cfg(debug_assertions)]
+use utoipa::ToSchema;
+
+tokio::task_local! {
+    // リクエストごとに払い出した ID。エラーレスポンスに含めるためにミドルウェアで設定する
+    pub static REQUEST_ID: String;
+}
+
 #[derive(Error, Debug)]
 pub enum AppError {
     #[error("{0}")]
-    UnprocessableEntity(String),
+    UnprocessableEntity(Message),
     #[error("{0}")]
-    EntityNotFound(String),
+    EntityNotFound(Message),
+    // 一意であるべき値が既に使われている場合
+    #[error("{0}")]
+    Conflict(Message),
     #[error("{0}")]
     JsonParseError(#[from] axum::extract::rejection::JsonRejection),
     #[error("{0}")]
@@ -24,43 +42,199 @@ pub enum AppError {
     #[error("{0}")]
     BcryptError(#[from] bcrypt::BcryptError),
     #[error("{0}")]
+    PasswordHashError(#[from] argon2::password_hash::Error),
+    #[error("{0}")]
     ConvertToUuidError(#[from] uuid::Error),
-    #[error("ログインに失敗しました")]
+    #[error("{}", Message::new(MessageKey::LoginFailed))]
     UnauthenticatedError,
-    #[error("認可情報が誤っています")]
+    #[error("{}", Message::new(MessageKey::InvalidCredentials))]
     UnauthorizedError,
-    #[error("許可されていない操作です")]
+    #[error("{}", Message::new(MessageKey::ForbiddenOperation))]
     ForbiddenOperation,
     #[error("{0}")]
+    CheckoutBookMismatch(Message),
+    #[error("{0}")]
+    ReturnForbidden(Message),
+    #[error("{0}")]
     ConversionEntityError(String),
+    // 外部の ID プロバイダーなど、依存するサービスとの通信に失敗した場合
+    #[error("{0}")]
+    ExternalServiceError(String),
+    // 再試行できるまでの秒数を持つ
+    #[error("{}", Message::new(MessageKey::LoginLocked).arg(.0))]
+    TooManyRequests(u64),
 }
 
-impl IntoResponse for AppError {
-    fn into_response(self) -> axum::response::Response {
-        let status_code = match self {
+impl AppError {
+    // SERIALIZABLE のトランザクションが他のトランザクションと競合して失敗した場合。
+    // やり直せば成功しうる
+    pub fn is_serialization_failure(&self) -> bool {
+        match self {
+            AppError::TransactionError(sqlx::Error::Database(e))
+            | AppError::SpecificOperationError(sqlx::Error::Database(e)) => {
+                e.code().as_deref() == Some("40001")
+            }
+            _ => false,
+        }
+    }
+
+    pub fn status_code(&self) -> StatusCode {
+        match self {
             AppError::UnprocessableEntity(_) => StatusCode::UNPROCESSABLE_ENTITY,
             AppError::EntityNotFound(_) => StatusCode::NOT_FOUND,
+            AppError::Conflict(_) => StatusCode::CONFLICT,
             AppError::JsonParseError(_)
             | AppError::QueryParseError(_)
             | AppError::ValidationError(_)
-            | AppError::ConvertToUuidError(_) => StatusCode::BAD_REQUEST,
-            AppError::UnauthenticatedError | AppError::ForbiddenOperation => StatusCode::FORBIDDEN,
+            | AppError::ConvertToUuidError(_)
+            | AppError::CheckoutBookMismatch(_) => StatusCode::BAD_REQUEST,
+            AppError::UnauthenticatedError
+            | AppError::ForbiddenOperation
+            | AppError::ReturnForbidden(_) => StatusCode::FORBIDDEN,
             AppError::UnauthorizedError => StatusCode::UNAUTHORIZED,
-            e @ (AppError::TransactionError(_)
+            AppError::TooManyRequests(_) => StatusCode::TOO_MANY_REQUESTS,
+            AppError::TransactionError(_)
             | AppError::SpecificOperationError(_)
             | AppError::NoRowsAffectedError(_)
             | AppError::KeyValueStoreError(_)
             | AppError::BcryptError(_)
-            | AppError::ConversionEntityError(_)) => {
-                tracing::error!(
-                error.cause_chain = ?e,
-                error.message = %e,
-                "Unexpected error happened"
-                );
-                StatusCode::INTERNAL_SERVER_ERROR
+            | AppError::PasswordHashError(_)
+            | AppError::ConversionEntityError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            AppError::ExternalServiceError(_) => StatusCode::BAD_GATEWAY,
+        }
+    }
+
+    // クライアントが分岐に利用する、バリアントごとに固定のエラーコード
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::UnprocessableEntity(_) => "UNPROCESSABLE_ENTITY",
+            AppError::EntityNotFound(_) => "ENTITY_NOT_FOUND",
+            AppError::Conflict(_) => "CONFLICT",
+            AppError::JsonParseError(_) => "INVALID_JSON",
+            AppError::QueryParseError(_) => "INVALID_QUERY",
+            AppError::ValidationError(_) => "VALIDATION_FAILED",
+            AppError::TransactionError(_) => "TRANSACTION_FAILED",
+            AppError::SpecificOperationError(_) => "DATABASE_ERROR",
+            AppError::NoRowsAffectedError(_) => "NO_ROWS_AFFECTED",
+            AppError::KeyValueStoreError(_) => "KEY_VALUE_STORE_ERROR",
+            AppError::BcryptError(_) | AppError::PasswordHashError(_) => "PASSWORD_HASH_ERROR",
+            AppError::ConvertToUuidError(_) => "INVALID_UUID",
+            AppError::UnauthenticatedError => "UNAUTHENTICATED",
+            AppError::UnauthorizedError => "UNAUTHORIZED",
+            AppError::ForbiddenOperation => "FORBIDDEN_OPERATION",
+            AppError::CheckoutBookMismatch(_) => "CHECKOUT_BOOK_MISMATCH",
+            AppError::ReturnForbidden(_) => "RETURN_FORBIDDEN",
+            AppError::ConversionEntityError(_) => "CONVERSION_ERROR",
+            AppError::ExternalServiceError(_) => "EXTERNAL_SERVICE_ERROR",
+            AppError::TooManyRequests(_) => "TOO_MANY_REQUESTS",
+        }
+    }
+
+    // クライアントに返すメッセージを指定の言語で組み立てる
+    pub fn localized_message(&self, locale: Locale) -> String {
+        // サーバー側の不具合の詳細はクライアントに返さない
+        if self.status_code().is_server_error() {
+            return Message::new(MessageKey::InternalServerError).render(locale);
+        }
+        match self {
+            AppError::UnprocessableEntity(m)
+            | AppError::EntityNotFound(m)
+            | AppError::Conflict(m)
+            | AppError::CheckoutBookMismatch(m)
+            | AppError::ReturnForbidden(m) => m.render(locale),
+            AppError::JsonParseError(e) => e.body_text(),
+            AppError::QueryParseError(e) => e.body_text(),
+            AppError::ValidationError(_) | AppError::ConvertToUuidError(_) => {
+                Message::new(MessageKey::InvalidRequest).render(locale)
+            }
+            AppError::UnauthenticatedError => Message::new(MessageKey::LoginFailed).render(locale),
+            AppError::UnauthorizedError => {
+                Message::new(MessageKey::InvalidCredentials).render(locale)
+            }
+            AppError::ForbiddenOperation => {
+                Message::new(MessageKey::ForbiddenOperation).render(locale)
             }
+            AppError::TooManyRequests(retry_after) => Message::new(MessageKey::LoginLocked)
+                .arg(retry_after)
+                .render(locale),
+            e => e.to_string(),
+        }
+    }
+}
+
+/// RFC 7807 形式のエラーレスポンス。
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct ProblemDetails {
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    pub title: String,
+    pub status: u16,
+    pub detail: String,
+    pub code: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<FieldError>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(debug_assertions, derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+impl From<&AppError> for ProblemDetails {
+    fn from(value: &AppError) -> Self {
+        let status = value.status_code();
+        let detail = value.localized_message(Locale::current());
+        let errors = match value {
+            AppError::ValidationError(report) => report
+                .iter()
+                .map(|(path, error)| FieldError {
+                    field: path.to_string(),
+                    message: error.message().to_string(),
+                })
+                .collect(),
+            _ => Vec::new(),
         };
-        status_code.into_response()
+        Self {
+            problem_type: "about:blank".into(),
+            title: status.canonical_reason().unwrap_or_default().into(),
+            status: status.as_u16(),
+            detail,
+            code: value.code().into(),
+            errors,
+            request_id: REQUEST_ID.try_with(|id| id.clone()).ok(),
+        }
+    }
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> axum::response::Response {
+        let status_code = self.status_code();
+        if status_code.is_server_error() {
+            tracing::error!(
+            error.cause_chain = ?self,
+            error.message = %self,
+            "Unexpected error happened"
+            );
+        }
+        let mut response = (
+            status_code,
+            [(header::CONTENT_TYPE, "application/problem+json")],
+            Json(ProblemDetails::from(&self)),
+        )
+            .into_response();
+        if let AppError::TooManyRequests(retry_after) = self {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, retry_after.into());
+        }
+        response
     }
 }
 
@@ -0,0 +1,374 @@
+use std::fmt;
+
+tokio::task_local! {
+    // リクエストの Accept-Language から決めた表示言語。ミドルウェアで設定する
+    pub static LOCALE: Locale;
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Locale {
+    #[default]
+    Ja,
+    En,
+}
+
+impl Locale {
+    /// Accept-Language ヘッダーの値から、対応している言語のうち q 値が最も高いものを選ぶ。
+    /// 対応している言語が含まれない場合は日本語とする。
+    pub fn from_accept_language(value: &str) -> Self {
+        let mut selected: Option<(Locale, f32)> = None;
+        for range in value.split(',') {
+            let mut params = range.trim().split(';');
+            let tag = params.next().unwrap_or_default().trim();
+            let q = params
+                .find_map(|p| p.trim().strip_prefix("q="))
+                .map(|q| q.parse::<f32>().unwrap_or(0.0))
+                .unwrap_or(1.0);
+            let primary = tag.split('-').next().unwrap_or_default();
+            let locale = if primary.eq_ignore_ascii_case("ja") {
+                Locale::Ja
+            } else if primary.eq_ignore_ascii_case("en") {
+                Locale::En
+            } else {
+                continue;
+            };
+            if q > 0.0 && selected.is_none_or(|(_, current)| q > current) {
+                selected = Some((locale, q));
+            }
+        }
+        selected.map(|(locale, _)| locale).unwrap_or_default()
+    }
+
+    /// 現在のリクエストの表示言語。リクエスト外では日本語とする。
+    pub fn current() -> Self {
+        LOCALE.try_with(|locale| *locale).unwrap_or_default()
+    }
+}
+
+/// ユースケースとエラーレスポンスで共有するメッセージのキー。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageKey {
+    UserNotFound,
+    BookNotFound,
+    CheckoutNotFound,
+    HoldNotFound,
+    BookAlreadyCheckedOut,
+    LoanLimitReached,
+    BookHeldForOtherUser,
+    HoldOnOwnCheckout,
+    HoldOnAvailableBook,
+    HoldAlreadyExists,
+    CheckoutNotRenewable,
+    RenewalLimitReached,
+    RenewalBlockedByHold,
+    CheckoutBookMismatch,
+    ReturnForbidden,
+    TransferToSameUser,
+    BookAlreadyOwnedByUser,
+    PasswordResetTokenInvalid,
+    PasswordTooShort,
+    PasswordTooFewCharClasses,
+    PasswordTooCommon,
+    PasswordContainsPersonalInfo,
+    EmailAlreadyInUse,
+    EmailVerificationTokenInvalid,
+    UserHasUnreturnedCheckouts,
+    UserNotDeactivated,
+    LastAdministrator,
+    SerializationConflict,
+    PersonalAccessTokenNotFound,
+    PersonalAccessTokenNameTaken,
+    PersonalAccessTokenExpiryInPast,
+    SessionNotFound,
+    LoginLocked,
+    MfaAlreadyEnabled,
+    MfaNotEnrolling,
+    MfaNotEnabled,
+    MfaCodeInvalid,
+    MfaRequiredForAdmin,
+    OidcNotConfigured,
+    PasswordResetMailSubject,
+    PasswordResetMailBody,
+    EmailVerificationMailSubject,
+    EmailVerificationMailBody,
+    LoginFailed,
+    InvalidCredentials,
+    ForbiddenOperation,
+    InvalidRequest,
+    InternalServerError,
+}
+
+impl MessageKey {
+    // {0}, {1}, ... は Message に渡した引数に順に置き換える
+    fn template(self, locale: Locale) -> &'static str {
+        use MessageKey::*;
+        match locale {
+            Locale::Ja => match self {
+                UserNotFound => "ユーザー（{0}）が見つかりませんでした。",
+                BookNotFound => "書籍（{0}）が見つかりませんでした。",
+                CheckoutNotFound => "貸出（{0}）が見つかりませんでした。",
+                HoldNotFound => "予約（{0}）が見つかりませんでした。",
+                BookAlreadyCheckedOut => "書籍（{0}）に対する貸出が既に存在します。",
+                LoanLimitReached => "貸出中の書籍が上限（{0}冊）に達しています。",
+                BookHeldForOtherUser => "書籍（{0}）は他のユーザーの予約により取り置き中です。",
+                HoldOnOwnCheckout => "書籍（{0}）は既に貸出中のため予約できません。",
+                HoldOnAvailableBook => "書籍（{0}）は貸出可能なため予約できません。",
+                HoldAlreadyExists => "書籍（{0}）に対する予約が既に存在します。",
+                CheckoutNotRenewable => {
+                    "指定の貸出（ID（{0}）, ユーザー（{1}）, 書籍（{2}））は延長できません。"
+                }
+                RenewalLimitReached => "貸出（{0}）の延長回数が上限（{1}回）に達しています。",
+                RenewalBlockedByHold => "書籍（{0}）には予約があるため延長できません。",
+                CheckoutBookMismatch => "貸出（{0}）は書籍（{1}）に対するものではありません。",
+                ReturnForbidden => "ユーザー（{0}）は貸出（{1}）を返却できません。",
+                TransferToSameUser => "移管元と移管先が同じユーザー（{0}）です。",
+                BookAlreadyOwnedByUser => "蔵書（{0}）の所有者は既にユーザー（{1}）です。",
+                PasswordResetTokenInvalid => {
+                    "パスワード再設定用のトークンが無効か、有効期限が切れています。"
+                }
+                PasswordTooShort => "パスワードは{0}文字以上にしてください。",
+                PasswordTooFewCharClasses => {
+                    "パスワードには英小文字・英大文字・数字・記号のうち{0}種類以上を含めてください。"
+                }
+                PasswordTooCommon => "よく使われているパスワードのため使用できません。",
+                PasswordContainsPersonalInfo => {
+                    "パスワードに名前やメールアドレスを含めることはできません。"
+                }
+                EmailAlreadyInUse => "メールアドレス（{0}）は既に使われています。",
+                EmailVerificationTokenInvalid => {
+                    "メールアドレス確認用のトークンが無効か、有効期限が切れています。"
+                }
+                UserHasUnreturnedCheckouts => {
+                    "ユーザー（{0}）には返却されていない貸出（{1}件）があるため無効化できません。"
+                }
+                UserNotDeactivated => {
+                    "ユーザー（{0}）は無効化されていないため削除できません。先に無効化してください。"
+                }
+                LastAdministrator => {
+                    "ユーザー（{0}）は最後の管理者のため、ロールの変更や無効化はできません。先に他のユーザーを管理者にしてください。"
+                }
+                SerializationConflict => {
+                    "他の操作と競合したため処理できませんでした。もう一度お試しください。"
+                }
+                PersonalAccessTokenNotFound => "アクセストークン（{0}）が見つかりませんでした。",
+                PersonalAccessTokenNameTaken => "アクセストークン名（{0}）は既に使われています。",
+                PersonalAccessTokenExpiryInPast => {
+                    "アクセストークンの有効期限には未来の日時を指定してください。"
+                }
+                SessionNotFound => "セッション（{0}）が見つかりませんでした。",
+                LoginLocked => {
+                    "ログインの失敗が続いたため、一時的にロックしています。{0} 秒後に再度お試しください。"
+                }
+                MfaAlreadyEnabled => "二要素認証は既に有効です。",
+                MfaNotEnrolling => "二要素認証の設定が開始されていません。",
+                MfaNotEnabled => "二要素認証は有効になっていません。",
+                MfaCodeInvalid => "確認コードが正しくありません。",
+                MfaRequiredForAdmin => "管理者は二要素認証を無効にできません。",
+                OidcNotConfigured => "シングルサインオンは設定されていません。",
+                PasswordResetMailSubject => "パスワード再設定のご案内",
+                PasswordResetMailBody => {
+                    "{0} 様\n\n以下のトークンを使ってパスワードを再設定してください。\n\n{1}\n\nトークンの有効期限は {2} 分です。心当たりがない場合はこのメールを破棄してください。\n"
+                }
+                EmailVerificationMailSubject => "メールアドレス確認のご案内",
+                EmailVerificationMailBody => {
+                    "{0} 様\n\nメールアドレスを {1} に変更するには、以下のトークンを使って確認を完了してください。\n\n{2}\n\nトークンの有効期限は {3} 分です。心当たりがない場合はこのメールを破棄してください。\n"
+                }
+                LoginFailed => "ログインに失敗しました",
+                InvalidCredentials => "認可情報が誤っています",
+                ForbiddenOperation => "許可されていない操作です",
+                InvalidRequest => "リクエストの内容に誤りがあります。",
+                InternalServerError => "サーバー内部でエラーが発生しました。",
+            },
+            Locale::En => match self {
+                UserNotFound => "User ({0}) was not found.",
+                BookNotFound => "Book ({0}) was not found.",
+                CheckoutNotFound => "Checkout ({0}) was not found.",
+                HoldNotFound => "Hold ({0}) was not found.",
+                BookAlreadyCheckedOut => "Book ({0}) is already checked out.",
+                LoanLimitReached => "You have reached the limit of {0} concurrent loans.",
+                BookHeldForOtherUser => "Book ({0}) is on hold for another user.",
+                HoldOnOwnCheckout => "Book ({0}) is already checked out by you.",
+                HoldOnAvailableBook => "Book ({0}) is available and cannot be put on hold.",
+                HoldAlreadyExists => "A hold on book ({0}) already exists.",
+                CheckoutNotRenewable => {
+                    "Checkout (ID ({0}), user ({1}), book ({2})) cannot be renewed."
+                }
+                RenewalLimitReached => "Checkout ({0}) has reached the limit of {1} renewals.",
+                RenewalBlockedByHold => "Book ({0}) has holds and cannot be renewed.",
+                CheckoutBookMismatch => "Checkout ({0}) is not for book ({1}).",
+                ReturnForbidden => "User ({0}) is not allowed to return checkout ({1}).",
+                TransferToSameUser => "Cannot transfer books from user ({0}) to the same user.",
+                BookAlreadyOwnedByUser => "Book ({0}) is already owned by user ({1}).",
+                PasswordResetTokenInvalid => "The password reset token is invalid or has expired.",
+                PasswordTooShort => "The password must be at least {0} characters long.",
+                PasswordTooFewCharClasses => {
+                    "The password must contain at least {0} of lowercase letters, uppercase letters, digits and symbols."
+                }
+                PasswordTooCommon => "The password is too common.",
+                PasswordContainsPersonalInfo => {
+                    "The password must not contain the user's name or email address."
+                }
+                EmailAlreadyInUse => "Email address ({0}) is already in use.",
+                EmailVerificationTokenInvalid => {
+                    "The email verification token is invalid or has expired."
+                }
+                UserHasUnreturnedCheckouts => {
+                    "User ({0}) cannot be deactivated while {1} checkouts are unreturned."
+                }
+                UserNotDeactivated => {
+                    "User ({0}) must be deactivated before it can be permanently deleted."
+                }
+                LastAdministrator => {
+                    "User ({0}) is the last administrator and cannot be demoted or deactivated. Promote another user to administrator first."
+                }
+                SerializationConflict => {
+                    "The request conflicted with another operation. Please try again."
+                }
+                PersonalAccessTokenNotFound => "Access token ({0}) was not found.",
+                PersonalAccessTokenNameTaken => "Access token name ({0}) is already in use.",
+                PersonalAccessTokenExpiryInPast => "The access token expiry must be in the future.",
+                SessionNotFound => "Session ({0}) was not found.",
+                LoginLocked => "Too many failed login attempts. Please try again in {0} seconds.",
+                MfaAlreadyEnabled => "Two-factor authentication is already enabled.",
+                MfaNotEnrolling => "Two-factor authentication setup has not been started.",
+                MfaNotEnabled => "Two-factor authentication is not enabled.",
+                MfaCodeInvalid => "The verification code is incorrect.",
+                MfaRequiredForAdmin => "Administrators cannot disable two-factor authentication.",
+                OidcNotConfigured => "Single sign-on is not configured.",
+                PasswordResetMailSubject => "Reset your password",
+                PasswordResetMailBody => {
+                    "Hello {0},\n\nUse the following token to reset your password.\n\n{1}\n\nThe token expires in {2} minutes. If you did not request this, please ignore this email.\n"
+                }
+                EmailVerificationMailSubject => "Verify your email address",
+                EmailVerificationMailBody => {
+                    "Hello {0},\n\nUse the following token to confirm changing your email address to {1}.\n\n{2}\n\nThe token expires in {3} minutes. If you did not request this, please ignore this email.\n"
+                }
+                LoginFailed => "Login failed.",
+                InvalidCredentials => "The credentials are invalid.",
+                ForbiddenOperation => "This operation is not permitted.",
+                InvalidRequest => "The request contains invalid values.",
+                InternalServerError => "An internal server error occurred.",
+            },
+        }
+    }
+}
+
+/// キーと埋め込む値からなる、表示言語に依存しないメッセージ。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    key: MessageKey,
+    args: Vec<String>,
+}
+
+impl Message {
+    pub fn new(key: MessageKey) -> Self {
+        Self {
+            key,
+            args: Vec::new(),
+        }
+    }
+
+    pub fn arg(mut self, value: impl ToString) -> Self {
+        self.args.push(value.to_string());
+        self
+    }
+
+    pub fn key(&self) -> MessageKey {
+        self.key
+    }
+
+    // テンプレートを先頭から一度だけ走査して置き換えるため、引数に {n} が含まれていても展開しない
+    pub fn render(&self, locale: Locale) -> String {
+        let template = self.key.template(locale);
+        let mut message = String::with_capacity(template.len());
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            message.push_str(&rest[..start]);
+            rest = &rest[start..];
+            let arg = rest.find('}').and_then(|end| {
+                let index = rest[1..end].parse::<usize>().ok()?;
+                Some((self.args.get(index)?, end))
+            });
+            match arg {
+                Some((arg, end)) => {
+                    message.push_str(arg);
+                    rest = &rest[end + 1..];
+                }
+                // 対応する引数がないものはそのまま残す
+                None => {
+                    message.push('{');
+                    rest = &rest[1..];
+                }
+            }
+        }
+        message.push_str(rest);
+        message
+    }
+}
+
+impl From<MessageKey> for Message {
+    fn from(value: MessageKey) -> Self {
+        Self::new(value)
+    }
+}
+
+// ログに出力する際は言語を固定する
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.render(Locale::default()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_accept_language() {
+        assert_eq!(Locale::from_accept_language("en"), Locale::En);
+        assert_eq!(Locale::from_accept_language("en-US,en;q=0.9"), Locale::En);
+        assert_eq!(Locale::from_accept_language("ja,en;q=0.8"), Locale::Ja);
+        assert_eq!(
+            Locale::from_accept_language("ja;q=0.5,en;q=0.8"),
+            Locale::En
+        );
+        assert_eq!(Locale::from_accept_language("fr,en;q=0.5"), Locale::En);
+        assert_eq!(Locale::from_accept_language("fr"), Locale::Ja);
+        assert_eq!(Locale::from_accept_language("en;q=0"), Locale::Ja);
+        assert_eq!(Locale::from_accept_language(""), Locale::Ja);
+    }
+
+    #[test]
+    fn test_render_message() {
+        let message = Message::new(MessageKey::CheckoutBookMismatch)
+            .arg("c1")
+            .arg("b1");
+        assert_eq!(
+            message.render(Locale::Ja),
+            "貸出（c1）は書籍（b1）に対するものではありません。"
+        );
+        assert_eq!(
+            message.render(Locale::En),
+            "Checkout (c1) is not for book (b1)."
+        );
+        assert_eq!(message.to_string(), message.render(Locale::Ja));
+    }
+
+    #[test]
+    fn test_render_message_with_placeholder_in_arg() {
+        let message = Message::new(MessageKey::CheckoutBookMismatch)
+            .arg("{1}")
+            .arg("b1");
+        assert_eq!(
+            message.render(Locale::En),
+            "Checkout ({1}) is not for book (b1)."
+        );
+
+        // 引数が足りない場合は、プレースホルダーをそのまま残す
+        let message = Message::new(MessageKey::CheckoutBookMismatch).arg("c1");
+        assert_eq!(
+            message.render(Locale::En),
+            "Checkout (c1) is not for book ({1})."
+        );
+    }
+}
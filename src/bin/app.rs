@@ -1,7 +1,11 @@
-use adapter::{database::connect_database_with, redis::RedisClient};
+use adapter::database::connect_database_with;
 use anyhow::{Context, Result};
-use api::route::{auth, v1};
-use axum::{Router, http::Method};
+use api::{
+    middleware::{locale, request_id},
+    route::{auth, v1},
+};
+use axum::{Router, http::Method, middleware};
+use kernel::use_case::auth::AuthUseCase;
 use opentelemetry::global;
 use registry::AppRegistryImpl;
 use shared::{
@@ -11,6 +15,7 @@ use shared::{
 use std::{
     net::{Ipv4Addr, SocketAddr},
     sync::Arc,
+    time::Duration,
 };
 use tokio::net::TcpListener;
 use tower_http::{
@@ -28,6 +33,9 @@ use utoipa::OpenApi;
 #[cfg(debug_assertions)]
 use utoipa_redoc::{Redoc, Servable};
 
+// 有効期限を過ぎたセッションやトークンを削除する間隔
+const AUTH_CLEANUP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
 fn cors() -> CorsLayer {
     CorsLayer::new()
         .allow_headers(cors::Any)
@@ -81,9 +89,8 @@ fn init_logger() -> Result<()> {
 async fn bootstrap() -> Result<()> {
     let app_config = AppConfig::new()?;
     let pool = connect_database_with(&app_config.database);
-    let kv = Arc::new(RedisClient::new(&app_config.redis)?);
-
-    let registry = Arc::new(AppRegistryImpl::new(pool, kv, app_config));
+    let registry = Arc::new(AppRegistryImpl::new(pool, app_config)?);
+    spawn_auth_cleanup(registry.auth_use_case());
 
     let router = Router::new().merge(v1::routes()).merge(auth::routes());
 
@@ -91,6 +98,8 @@ async fn bootstrap() -> Result<()> {
     let router = router.merge(Redoc::with_url("/docs", ApiDoc::openapi()));
 
     let app = router
+        .layer(middleware::from_fn(locale))
+        .layer(middleware::from_fn(request_id))
         .layer(
             TraceLayer::new_for_http()
                 .make_span_with(DefaultMakeSpan::new().level(Level::INFO))
@@ -107,15 +116,30 @@ async fn bootstrap() -> Result<()> {
     let addr = SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), 8080);
     let listener = TcpListener::bind(addr).await?;
     tracing::info!("Listening on {}", addr);
-    axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal())
-        .await
-        .context("Unexpected error happened in server")
-        .inspect_err(|e| {
-            tracing::error!(
-                error.cause_chain = ?e,error.message = %e, "Unexpected error"
-            )
-        })
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(shutdown_signal())
+    .await
+    .context("Unexpected error happened in server")
+    .inspect_err(|e| {
+        tracing::error!(
+            error.cause_chain = ?e,error.message = %e, "Unexpected error"
+        )
+    })
+}
+
+fn spawn_auth_cleanup(auth_use_case: Arc<dyn AuthUseCase>) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(AUTH_CLEANUP_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = auth_use_case.delete_expired().await {
+                tracing::warn!(error.message = %e, "Failed to delete expired auth state");
+            }
+        }
+    });
 }
 
 async fn shutdown_signal() {